@@ -0,0 +1,15 @@
+#!/usr/bin/env bash
+
+# Fail immediately in case of errors and/or unset variables
+set -eu -o pipefail
+
+# Check that the embedded uncaught exception handler class is compiled from its
+# source with JDK 17, as it's checked in and not built with the crate
+SRC_DIR=src/wrapper/exception_handler
+OUT_DIR=$(mktemp -d)
+javac --release 8 -d "$OUT_DIR" "$SRC_DIR/NativeUncaughtExceptionHandler.java"
+if ! cmp "$OUT_DIR/jni/rs/NativeUncaughtExceptionHandler.class" "$SRC_DIR/NativeUncaughtExceptionHandler.class"; then
+    echo "NativeUncaughtExceptionHandler.class is out of date, rebuild it with" \
+        "'javac --release 8 NativeUncaughtExceptionHandler.java'"
+    exit 1
+fi
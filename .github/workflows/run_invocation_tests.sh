@@ -4,5 +4,5 @@
 # shellcheck source=/dev/null
 source test_profile
 
-# Run all tests with invocation feature (enables JavaVM ITs)
-cargo test --features=invocation
+# Run all tests with invocation feature (enables JavaVM ITs) and the optional conversions
+cargo test --features=invocation,debug-refs,macros,metrics,num-bigint,uuid
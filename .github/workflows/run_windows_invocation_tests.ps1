@@ -1,3 +1,3 @@
 $env:Path += ";$(Split-Path -Path (Get-Childitem –Path $Env:JAVA_HOME -Filter jvm.dll -Recurse) -Parent)"
 
-cargo test --features=invocation
+cargo test --features=invocation,debug-refs,macros,metrics,num-bigint,uuid
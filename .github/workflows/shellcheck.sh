@@ -6,4 +6,5 @@ set -x
 
 # Check the shell scripts
 shellcheck .github/workflows/run_invocation_tests.sh
+shellcheck .github/workflows/check_exception_handler_class.sh
 shellcheck test_profile
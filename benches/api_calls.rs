@@ -121,6 +121,26 @@ mod tests {
         b.iter(|| jni_call_static_unchecked(&env, class, method_id, -3));
     }
 
+    /// Calls through a `MethodHandle` box the arguments and the result, see
+    /// `jni_call_static_method_unchecked_jclass` for the same call through a
+    /// method id.
+    #[bench]
+    fn jni_call_static_method_handle(b: &mut Bencher) {
+        let env = VM.attach_current_thread().unwrap();
+        let handle = env
+            .bind_static_method_handle(CLASS_MATH, METHOD_MATH_ABS, SIG_MATH_ABS)
+            .unwrap();
+
+        b.iter(|| {
+            env.with_local_frame(8, || {
+                let v = handle.invoke(&env, &[JValue::from(-3)]).unwrap();
+                black_box(v.i().unwrap());
+                Ok(JObject::null())
+            })
+            .unwrap()
+        });
+    }
+
     #[bench]
     fn jni_call_object_method_safe(b: &mut Bencher) {
         let env = VM.attach_current_thread().unwrap();
@@ -0,0 +1,174 @@
+//! Procedural macros for the [`jni`](https://docs.rs/jni) crate. Use them
+//! through the `macros` feature of `jni` rather than depending on this crate
+//! directly.
+
+#![warn(missing_docs)]
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, spanned::Spanned, FnArg, Ident, ItemFn, LitStr, ReturnType, Type};
+
+/// Exports a rust function as the implementation of a java native method.
+///
+/// This generates the `#[no_mangle] pub extern "system"` function the JVM
+/// looks up, named after the java class and method, which calls the annotated
+/// function. The annotated function is left as it is, and takes the
+/// `&JNIEnv` followed by the object or class and the arguments of the native
+/// method. It may return `jni::errors::Result<T>`, where `T` is a type from
+/// `jni::sys` or a `JObject`, `JClass`, `JString` or `JThrowable`, or
+/// nothing.
+///
+/// The generated function turns failures into java exceptions:
+///
+/// * An `Err` with a java exception that is still pending leaves it to be
+///   thrown, and an exception that was cleared into the error is thrown again.
+/// * Other errors throw an exception based on the kind of the error: a
+///   `NullPointerException` for null pointers, an `IllegalArgumentException`
+///   for invalid arguments, a `NoSuchMethodError`/`NoSuchFieldError` for
+///   missing members, and a `RuntimeException` otherwise.
+/// * A panic is caught rather than unwinding into the JVM, and throws a
+///   `RuntimeException` with the panic message.
+///
+/// The attribute takes the fully qualified name of the java `class`, with
+/// either `.` or `/` separators, and optionally the `name` of the java method,
+/// which defaults to the name of the rust function.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use jni::{errors::Result, jni_export, objects::{JClass, JString}, JNIEnv};
+///
+/// // Implements `static native String hello(String input)` of `com.example.HelloWorld`
+/// #[jni_export(class = "com.example.HelloWorld")]
+/// fn hello<'a>(env: &JNIEnv<'a>, _class: JClass<'a>, input: JString<'a>) -> Result<JString<'a>> {
+///     let input: String = env.get_string(input)?.into();
+///     env.new_string(format!("Hello, {}!", input))
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn jni_export(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut class: Option<LitStr> = None;
+    let mut name: Option<LitStr> = None;
+    let attr_parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("class") {
+            class = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("name") {
+            name = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported jni_export property, expected `class` or `name`"))
+        }
+    });
+    parse_macro_input!(attr with attr_parser);
+    let function = parse_macro_input!(item as ItemFn);
+
+    let class = match class {
+        Some(class) => class,
+        None => {
+            return syn::Error::new(Span::call_site(), "jni_export requires a `class`")
+                .to_compile_error()
+                .into()
+        }
+    };
+
+    match export(&class, name.as_ref(), &function) {
+        Ok(shim) => quote!(#function #shim).into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn export(
+    class: &LitStr,
+    name: Option<&LitStr>,
+    function: &ItemFn,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let sig = &function.sig;
+    let method = name
+        .map(LitStr::value)
+        .unwrap_or_else(|| sig.ident.to_string());
+    let shim_name = Ident::new(
+        &format!(
+            "Java_{}_{}",
+            mangle(&class.value().replace('.', "/")),
+            mangle(&method)
+        ),
+        sig.ident.span(),
+    );
+
+    let mut inputs = sig.inputs.iter().map(|arg| match arg {
+        FnArg::Typed(arg) => Ok(&*arg.ty),
+        FnArg::Receiver(receiver) => Err(syn::Error::new(
+            receiver.span(),
+            "jni_export can't be used on methods",
+        )),
+    });
+    let env_type = match inputs.next().transpose()? {
+        Some(Type::Reference(env)) if env.mutability.is_none() => &*env.elem,
+        _ => {
+            return Err(syn::Error::new(
+                sig.inputs.span(),
+                "the first argument of a jni_export function must be `&JNIEnv`",
+            ))
+        }
+    };
+    let arg_types = inputs.collect::<syn::Result<Vec<_>>>()?;
+    let arg_names: Vec<_> = (0..arg_types.len())
+        .map(|i| Ident::new(&format!("arg{}", i), Span::call_site()))
+        .collect();
+
+    let ret = match &sig.output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(_, ty) => quote!(#ty),
+    };
+    let ident = &sig.ident;
+    let (impl_generics, _, where_clause) = sig.generics.split_for_impl();
+
+    Ok(quote! {
+        #[no_mangle]
+        #[allow(non_snake_case)]
+        pub extern "system" fn #shim_name #impl_generics (
+            env: #env_type,
+            #( #arg_names: #arg_types ),*
+        ) -> <#ret as ::jni::export::ExportResult>::Output #where_clause {
+            ::jni::export::export_call::<#ret, _>(&env, || #ident(&env, #( #arg_names ),*))
+        }
+    })
+}
+
+/// Escapes a class or method name for use in a native method name, as
+/// described in the JNI specification.
+fn mangle(name: &str) -> String {
+    let mut mangled = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            '/' => mangled.push('_'),
+            '_' => mangled.push_str("_1"),
+            ';' => mangled.push_str("_2"),
+            '[' => mangled.push_str("_3"),
+            c if c.is_ascii_alphanumeric() => mangled.push(c),
+            c => {
+                let mut units = [0; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    mangled.push_str(&format!("_0{:04x}", unit));
+                }
+            }
+        }
+    }
+    mangled
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_mangle() {
+        assert_eq!(mangle("com/example/HelloWorld"), "com_example_HelloWorld");
+        assert_eq!(mangle("under_score"), "under_1score");
+        assert_eq!(mangle("a;b[c"), "a_2b_3c");
+        assert_eq!(mangle("caf\u{e9}"), "caf_000e9");
+        assert_eq!(mangle("\u{1f600}"), "_0d83d_0de00");
+    }
+}
@@ -136,6 +136,10 @@
 //! Note that the type signature for our function is almost identical to the one
 //! from the generated header, aside from our lifetime-carrying arguments.
 //!
+//! With the `macros` feature enabled, the `jni_export` attribute can generate
+//! this function from a regular one returning a `Result`, and turns errors and
+//! panics into Java exceptions instead of crashing the JVM.
+//!
 //! ### Final steps
 //!
 //! That's it! Build your crate and try to run your Java class again.
@@ -198,6 +202,12 @@ mod wrapper {
     #[macro_use]
     mod macros;
 
+    /// Macros for calling methods with signatures built at compile time.
+    mod call_macros;
+
+    /// Macros for running code in local reference frames.
+    mod scope_macros;
+
     /// Errors. Do you really need more explanation?
     pub mod errors;
 
@@ -213,6 +223,22 @@ mod wrapper {
     /// String types for going to/from java strings.
     pub mod strings;
 
+    /// Metrics of method calls.
+    #[cfg(feature = "metrics")]
+    mod call_metrics;
+
+    /// Tracking of deleted local references.
+    #[cfg(all(feature = "debug-refs", debug_assertions))]
+    mod debug_refs;
+
+    /// Forwarding of uncaught java exceptions to rust closures.
+    mod exception_handler;
+
+    /// Support code for native methods generated by `jni_export`.
+    #[doc(hidden)]
+    pub mod export;
+    pub use self::export::JniReturn;
+
     /// Actual communication with the JVM.
     mod jnienv;
     pub use self::jnienv::*;
@@ -227,3 +253,6 @@ mod wrapper {
 }
 
 pub use wrapper::*;
+
+#[cfg(feature = "macros")]
+pub use jni_macros::jni_export;
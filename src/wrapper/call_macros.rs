@@ -0,0 +1,244 @@
+/// Builds a java method or type signature at compile time.
+///
+/// Types are written as the java primitive names (`boolean`, `byte`, `char`,
+/// `short`, `int`, `long`, `float`, `double` and `void`), as string literals
+/// holding a class name (`"java/lang/String"`), or as arrays of those
+/// (`[int]`, `["java/lang/String"]`).
+///
+/// The result is a `&'static str`.
+///
+/// # Example
+/// ```rust
+/// # use jni::jni_signature;
+/// assert_eq!(jni_signature!(int), "I");
+/// assert_eq!(jni_signature!([[byte]]), "[[B");
+/// assert_eq!(jni_signature!(("java/lang/String", long) -> void), "(Ljava/lang/String;J)V");
+/// ```
+#[macro_export]
+macro_rules! jni_signature {
+    (( $($arg:tt),* ) -> $ret:tt) => {
+        concat!("(", $($crate::jni_signature!($arg),)* ")", $crate::jni_signature!($ret))
+    };
+    (boolean) => { "Z" };
+    (byte) => { "B" };
+    (char) => { "C" };
+    (short) => { "S" };
+    (int) => { "I" };
+    (long) => { "J" };
+    (float) => { "F" };
+    (double) => { "D" };
+    (void) => { "V" };
+    ([ $inner:tt ]) => { concat!("[", $crate::jni_signature!($inner)) };
+    ($class:literal) => { concat!("L", $class, ";") };
+    ($other:tt) => {
+        compile_error!(concat!("unsupported java type: ", stringify!($other)))
+    };
+}
+
+/// Calls a java method with a signature that is generated at compile time
+/// from the declared argument and return types.
+///
+/// The call is written much like a java method call, with each argument
+/// followed by its java type (see `jni_signature!` for the type syntax):
+///
+/// * `call!(env, obj.name(arg: type, ...) -> type)` calls an object method
+///   through `JNIEnv::call_method`.
+/// * `call!(env, static class.name(arg: type, ...) -> type)` calls a static
+///   method through `JNIEnv::call_static_method`.
+///
+/// Arguments that are not simple identifiers or literals must be wrapped in
+/// parentheses. Each argument is checked against its declared type, so
+/// passing e.g. a `jlong` to an `int` parameter is a compile error. `boolean`
+/// arguments take a Rust `bool`, and object and array arguments accept
+/// anything that converts into a `JObject`. `[byte]` arguments also accept a
+/// `&[u8]`, `&Vec<u8>` or `Vec<u8>`, which is copied into a new java array
+/// that is deleted once the call returns.
+///
+/// The arguments are evaluated in order, after the object or class. The macro
+/// expands to an expression rather than a closure, so `?` and `return` in the
+/// arguments apply to the enclosing function.
+///
+/// The result is returned already unwrapped to the declared return type:
+/// `Result<jint>` for `int`, `Result<JObject>` for objects and arrays,
+/// `Result<()>` for `void` and so on.
+///
+/// # Example
+/// ```rust,ignore
+/// let abs: jint = call!(env, static "java/lang/Math".abs((-2): int) -> int)?;
+/// let len: jint = call!(env, string.length() -> int)?;
+/// let digest = call!(env, md.digest((&bytes[..]): [byte]) -> [byte])?;
+/// ```
+#[macro_export]
+macro_rules! call {
+    ($env:expr, static $class:tt . $name:ident ( $($arg:tt : $ty:tt),* ) -> $ret:tt) => {{
+        let env: &$crate::JNIEnv = &$env;
+        let class = $crate::__jni_arg_expr!($class);
+        $crate::__jni_call!(
+            env,
+            (call_static_method class),
+            stringify!($name),
+            $crate::jni_signature!(($($ty),*) -> $ret),
+            $ret,
+            [],
+            [$($arg: $ty),*]
+        )
+    }};
+    ($env:expr, $obj:tt . $name:ident ( $($arg:tt : $ty:tt),* ) -> $ret:tt) => {{
+        let env: &$crate::JNIEnv = &$env;
+        let obj = $crate::__jni_arg_expr!($obj);
+        $crate::__jni_call!(
+            env,
+            (call_method obj),
+            stringify!($name),
+            $crate::jni_signature!(($($ty),*) -> $ret),
+            $ret,
+            [],
+            [$($arg: $ty),*]
+        )
+    }};
+}
+
+// Converts the arguments of `call!` one by one, in order, and then makes the
+// call. This expands to nested blocks rather than a closure, so `?` and
+// `return` in the arguments apply to the enclosing function.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __jni_call {
+    ($env:ident, ($method:ident $target:ident), $name:expr, $sig:expr, $ret:tt,
+        [$($value:ident),*], []) => {
+        $crate::__jni_unwrap_ret!($ret, $env.$method($target, $name, $sig, &[$($value),*]))
+    };
+    // A new java array created for a byte array argument is deleted when
+    // `array` is dropped, after the call returns
+    ($env:ident, $method:tt, $name:expr, $sig:expr, $ret:tt,
+        [$($value:ident),*], [$arg:tt : [byte] $(, $rest:tt : $rest_ty:tt)*]) => {
+        match $crate::objects::IntoByteArrayArg::into_byte_array_arg(
+            $crate::__jni_arg_expr!($arg),
+            $env,
+        ) {
+            Ok(array) => {
+                let value = $crate::objects::JValue::Object(array.as_obj());
+                $crate::__jni_call!(
+                    $env, $method, $name, $sig, $ret, [$($value,)* value], [$($rest: $rest_ty),*]
+                )
+            }
+            Err(e) => Err(e),
+        }
+    };
+    ($env:ident, $method:tt, $name:expr, $sig:expr, $ret:tt,
+        [$($value:ident),*], [$arg:tt : $ty:tt $(, $rest:tt : $rest_ty:tt)*]) => {{
+        let value = $crate::__jni_typed_arg!($ty, $arg);
+        $crate::__jni_call!(
+            $env, $method, $name, $sig, $ret, [$($value,)* value], [$($rest: $rest_ty),*]
+        )
+    }};
+}
+
+// Removes the parentheses around an argument, which are only needed to pass
+// it to `call!` as a single token tree.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __jni_arg_expr {
+    (($arg:expr)) => {
+        $arg
+    };
+    ($arg:tt) => {
+        $arg
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __jni_typed_arg {
+    (boolean, $arg:tt) => {
+        $crate::objects::JValue::from({
+            let v: bool = $crate::__jni_arg_expr!($arg);
+            v
+        })
+    };
+    (byte, $arg:tt) => {
+        $crate::objects::JValue::Byte({
+            let v: $crate::sys::jbyte = $crate::__jni_arg_expr!($arg);
+            v
+        })
+    };
+    (char, $arg:tt) => {
+        $crate::objects::JValue::Char({
+            let v: $crate::sys::jchar = $crate::__jni_arg_expr!($arg);
+            v
+        })
+    };
+    (short, $arg:tt) => {
+        $crate::objects::JValue::Short({
+            let v: $crate::sys::jshort = $crate::__jni_arg_expr!($arg);
+            v
+        })
+    };
+    (int, $arg:tt) => {
+        $crate::objects::JValue::Int({
+            let v: $crate::sys::jint = $crate::__jni_arg_expr!($arg);
+            v
+        })
+    };
+    (long, $arg:tt) => {
+        $crate::objects::JValue::Long({
+            let v: $crate::sys::jlong = $crate::__jni_arg_expr!($arg);
+            v
+        })
+    };
+    (float, $arg:tt) => {
+        $crate::objects::JValue::Float({
+            let v: $crate::sys::jfloat = $crate::__jni_arg_expr!($arg);
+            v
+        })
+    };
+    (double, $arg:tt) => {
+        $crate::objects::JValue::Double({
+            let v: $crate::sys::jdouble = $crate::__jni_arg_expr!($arg);
+            v
+        })
+    };
+    (void, $arg:tt) => {
+        compile_error!("an argument can't have the void type")
+    };
+    ($ty:tt, $arg:tt) => {
+        $crate::objects::JValue::Object($crate::objects::JObject::from($crate::__jni_arg_expr!(
+            $arg
+        )))
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __jni_unwrap_ret {
+    (boolean, $res:expr) => {
+        $res.and_then(|v| v.z())
+    };
+    (byte, $res:expr) => {
+        $res.and_then(|v| v.b())
+    };
+    (char, $res:expr) => {
+        $res.and_then(|v| v.c())
+    };
+    (short, $res:expr) => {
+        $res.and_then(|v| v.s())
+    };
+    (int, $res:expr) => {
+        $res.and_then(|v| v.i())
+    };
+    (long, $res:expr) => {
+        $res.and_then(|v| v.j())
+    };
+    (float, $res:expr) => {
+        $res.and_then(|v| v.f())
+    };
+    (double, $res:expr) => {
+        $res.and_then(|v| v.d())
+    };
+    (void, $res:expr) => {
+        $res.and_then(|v| v.v())
+    };
+    ($ty:tt, $res:expr) => {
+        $res.and_then(|v| v.l())
+    };
+}
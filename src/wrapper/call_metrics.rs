@@ -0,0 +1,40 @@
+use std::{borrow::Cow, sync::Arc, time::Instant};
+
+use crate::strings::JNIStr;
+
+/// Records the number and the latency of java method calls through the
+/// `metrics` facade. The call is recorded when the timer is dropped, so calls
+/// failing with an error are included as well.
+///
+/// Both metrics are labeled with the `class` and the `method` names:
+/// * `jni_calls_total` — a counter of the calls
+/// * `jni_call_duration_seconds` — a histogram of the call latencies
+pub(crate) struct CallTimer {
+    start: Instant,
+    class: Arc<str>,
+    method: String,
+}
+
+impl CallTimer {
+    /// Starts timing a call of the method of the class.
+    pub(crate) fn start(class: Arc<str>, method: &JNIStr) -> Self {
+        CallTimer {
+            start: Instant::now(),
+            class,
+            method: Cow::from(method).into_owned(),
+        }
+    }
+}
+
+impl Drop for CallTimer {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed();
+        let class = String::from(&*self.class);
+        let method = std::mem::take(&mut self.method);
+
+        metrics::counter!("jni_calls_total", "class" => class.clone(), "method" => method.clone())
+            .increment(1);
+        metrics::histogram!("jni_call_duration_seconds", "class" => class, "method" => method)
+            .record(elapsed);
+    }
+}
@@ -0,0 +1,108 @@
+use std::{any::Any, cell::RefCell, collections::HashSet};
+
+use log::error;
+
+use crate::sys::jobject;
+
+thread_local! {
+    // Local references deleted in this thread, by their raw pointers, for each
+    // of the frames whose end is known: local frames, native methods run
+    // through `jni_export` or `catch_unwind`, and attachments of the thread.
+    // The innermost frame is the last one.
+    //
+    // Deletions outside of them are not tracked: in a native method called by
+    // the JVM, the slots of the deleted references get reused for the arguments
+    // of later native calls, which would be mistaken for uses of deleted
+    // references.
+    static DELETED_LOCAL_REFS: RefCell<Vec<HashSet<usize>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Starts tracking the deletions of a new frame.
+pub(crate) fn push_frame() {
+    DELETED_LOCAL_REFS.with(|frames| frames.borrow_mut().push(HashSet::new()));
+}
+
+/// Forgets the deletions of the innermost frame when it ends, as all of its
+/// slots are freed with it.
+pub(crate) fn pop_frame() {
+    DELETED_LOCAL_REFS.with(|frames| frames.borrow_mut().pop());
+}
+
+/// Forgets all the deletions when the thread is detached.
+pub(crate) fn clear() {
+    // The thread may be detached when its thread locals are destroyed
+    let _ = DELETED_LOCAL_REFS.try_with(|frames| frames.borrow_mut().clear());
+}
+
+/// Tracks the deletions of the frame of a native method for as long as it
+/// lives.
+pub(crate) struct NativeFrame(());
+
+impl NativeFrame {
+    pub(crate) fn enter() -> Self {
+        push_frame();
+        NativeFrame(())
+    }
+}
+
+impl Drop for NativeFrame {
+    fn drop(&mut self) {
+        pop_frame();
+    }
+}
+
+fn is_deleted(obj: jobject) -> bool {
+    !obj.is_null()
+        && DELETED_LOCAL_REFS.with(|frames| {
+            frames
+                .borrow()
+                .iter()
+                .any(|deleted| deleted.contains(&(obj as usize)))
+        })
+}
+
+/// Panics if a local reference is about to be deleted a second time.
+pub(crate) fn check_delete_local_ref(obj: jobject) {
+    if is_deleted(obj) {
+        error!("local reference {:?} is deleted twice", obj);
+        panic!("local reference {:?} is deleted twice", obj);
+    }
+}
+
+/// Records the deletion of a local reference in the innermost frame.
+pub(crate) fn deleted_local_ref(obj: jobject) {
+    if obj.is_null() {
+        return;
+    }
+    DELETED_LOCAL_REFS.with(|frames| {
+        if let Some(deleted) = frames.borrow_mut().last_mut() {
+            deleted.insert(obj as usize);
+        }
+    });
+}
+
+/// Panics if an argument of a JNI call is a deleted local reference.
+pub(crate) fn check_arg<T: Any>(arg: T) -> T {
+    if let Some(&obj) = (&arg as &dyn Any).downcast_ref::<jobject>() {
+        if is_deleted(obj) {
+            error!("local reference {:?} is used after being deleted", obj);
+            panic!("local reference {:?} is used after being deleted", obj);
+        }
+    }
+    arg
+}
+
+/// Forgets the deletion of a slot that the JVM reused for a reference
+/// returned from a JNI call.
+pub(crate) fn check_result<T: Any>(res: &T) {
+    if let Some(&obj) = (res as &dyn Any).downcast_ref::<jobject>() {
+        if obj.is_null() {
+            return;
+        }
+        DELETED_LOCAL_REFS.with(|frames| {
+            for deleted in frames.borrow_mut().iter_mut() {
+                deleted.remove(&(obj as usize));
+            }
+        });
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     descriptors::Desc,
     errors::*,
-    objects::{JClass, JObject, JThrowable, JValue},
+    objects::{GlobalRef, JClass, JObject, JThrowable, JValue},
     strings::JNIString,
     JNIEnv,
 };
@@ -45,3 +45,11 @@ impl<'a, 'b> Desc<'a, JThrowable<'a>> for JNIString {
         (DEFAULT_EXCEPTION_CLASS, self).lookup(env)
     }
 }
+
+/// This conversion assumes that the `GlobalRef` is a pointer to a throwable
+/// object, such as the one returned by `Error#java_exception`.
+impl<'a, 'b> Desc<'a, JThrowable<'b>> for &'b GlobalRef {
+    fn lookup(self, _: &JNIEnv<'a>) -> Result<JThrowable<'b>> {
+        Ok(self.as_obj().into())
+    }
+}
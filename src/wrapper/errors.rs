@@ -3,7 +3,11 @@
 use thiserror::Error;
 
 use crate::sys;
-use crate::wrapper::signature::TypeSignature;
+use crate::wrapper::{
+    objects::{GlobalRef, JObject, JThrowable},
+    signature::TypeSignature,
+    JNIEnv,
+};
 
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -13,18 +17,49 @@ pub enum Error {
     WrongJValueType(&'static str, &'static str),
     #[error("Invalid constructor return type (must be void)")]
     InvalidCtorReturn,
+    #[error("Method doesn't return void: {0}")]
+    NonVoidMethod(TypeSignature),
     #[error("Invalid number of arguments passed to java method: {0}")]
     InvalidArgList(TypeSignature),
+    #[error("Parameter {0} can't be used as an out-param")]
+    InvalidOutParam(usize),
+    #[error("Buffer too small: {actual} bytes, {required} required")]
+    BufferTooSmall { required: usize, actual: usize },
+    #[error("Array length mismatch: {actual} elements, {expected} expected")]
+    ArrayLengthMismatch { expected: usize, actual: usize },
+    #[error("Invalid modified UTF-8 at byte offset {offset}")]
+    InvalidUtf8 { offset: usize },
+    #[error("Invalid char: unpaired surrogate {0:#06x}")]
+    InvalidChar(sys::jchar),
     #[error("Method not found: {name} {sig}")]
     MethodNotFound { name: String, sig: String },
     #[error("Field not found: {name} {sig}")]
     FieldNotFound { name: String, sig: String },
+    #[error("Primitive type descriptor used as a class: {0}")]
+    PrimitiveClassDesc(String),
+    #[error("Class is not a record: {0}")]
+    NotRecord(String),
     #[error("Java exception was thrown")]
     JavaException,
+    #[error("Java thread was interrupted")]
+    Interrupted,
+    #[error(
+        "Java exception was thrown and cleared: {class}{}",
+        .message.as_ref().map(|m| format!(": {}", m)).unwrap_or_default()
+    )]
+    JavaExceptionCleared {
+        exception: GlobalRef,
+        class: String,
+        message: Option<String>,
+    },
+    #[error("No current JNIEnv, see JavaVM::with_env")]
+    NoCurrentEnv,
     #[error("JNIEnv null method pointer for {0}")]
     JNIEnvMethodNotFound(&'static str),
     #[error("Null pointer in {0}")]
     NullPtr(&'static str),
+    #[error("Object is not an instance of the expected class in {0}")]
+    WrongObjectClass(&'static str),
     #[error("Null pointer deref in {0}")]
     NullDeref(&'static str),
     #[error("Mutex already locked")]
@@ -59,6 +94,86 @@ pub enum JniError {
     Other(sys::jint),
 }
 
+impl Error {
+    /// Returns the exception captured by a `JavaExceptionCleared` error, so it
+    /// can be inspected or thrown again.
+    ///
+    /// Returns `None` for all other errors, including `JavaException`, whose
+    /// exception is still pending and can be obtained with
+    /// `JNIEnv#exception_occurred`.
+    pub fn java_exception(&self) -> Option<&GlobalRef> {
+        match self {
+            Error::JavaExceptionCleared { exception, .. } => Some(exception),
+            _ => None,
+        }
+    }
+}
+
+/// Conversion of java exceptions into caller-defined errors, for the results
+/// returned from `JNIEnv` methods.
+///
+/// # Example
+/// ```rust,ignore
+/// enum ParseError {
+///     InvalidNumber,
+///     Unexpected,
+///     Jni(jni::errors::Error),
+/// }
+///
+/// impl From<jni::errors::Error> for ParseError {
+///     fn from(other: jni::errors::Error) -> Self {
+///         ParseError::Jni(other)
+///     }
+/// }
+///
+/// let value = env
+///     .call_static_method("java/lang/Integer", "parseInt", "(Ljava/lang/String;)I", &[input.into()])
+///     .map_java_err(&env, |exception, env| {
+///         match env.is_instance_of(exception, "java/lang/NumberFormatException") {
+///             Ok(true) => ParseError::InvalidNumber,
+///             _ => ParseError::Unexpected,
+///         }
+///     })?;
+/// ```
+pub trait MapJavaErr<T> {
+    /// Maps a java exception to an error with the given function, which gets
+    /// the exception as a local reference. Other errors are converted with
+    /// `From<Error>`.
+    ///
+    /// Both a `JavaException` error, whose exception is still pending, and a
+    /// `JavaExceptionCleared` error are mapped. The pending exception gets
+    /// cleared before the function is called, so it is free to call into
+    /// java.
+    fn map_java_err<'a, E, F>(self, env: &JNIEnv<'a>, f: F) -> std::result::Result<T, E>
+    where
+        E: From<Error>,
+        F: FnOnce(JThrowable<'a>, &JNIEnv<'a>) -> E;
+}
+
+impl<T> MapJavaErr<T> for Result<T> {
+    fn map_java_err<'a, E, F>(self, env: &JNIEnv<'a>, f: F) -> std::result::Result<T, E>
+    where
+        E: From<Error>,
+        F: FnOnce(JThrowable<'a>, &JNIEnv<'a>) -> E,
+    {
+        let throwable = match self {
+            Ok(value) => return Ok(value),
+            Err(e @ Error::JavaException) | Err(e @ Error::Interrupted) => {
+                match env.clear_pending_exception()? {
+                    Some(throwable) => JThrowable::from(throwable.forget()),
+                    None => return Err(e.into()),
+                }
+            }
+            Err(Error::JavaExceptionCleared { exception, .. }) => {
+                let local = env.new_local_ref::<JObject>(exception.as_obj())?;
+                JThrowable::from(local.into_inner())
+            }
+            Err(e) => return Err(e.into()),
+        };
+        Err(f(throwable, env))
+    }
+}
+
 impl<T> From<::std::sync::TryLockError<T>> for Error {
     fn from(_: ::std::sync::TryLockError<T>) -> Self {
         Error::TryLock
@@ -0,0 +1,65 @@
+package jni.rs;
+
+import java.lang.ref.PhantomReference;
+import java.lang.ref.Reference;
+import java.lang.ref.ReferenceQueue;
+import java.util.Map;
+import java.util.concurrent.ConcurrentHashMap;
+
+/**
+ * Forwards uncaught exceptions to a Rust closure, installed with
+ * {@code JNIEnv#set_uncaught_exception_handler}.
+ *
+ * <p>The closure is freed once the handler has been garbage collected, by a daemon thread
+ * watching phantom references to the handlers. This is what {@code java.lang.ref.Cleaner}
+ * does, which is not available on Java 8.
+ *
+ * <p>The compiled class is embedded into the crate, rebuild it with
+ * {@code javac --release 8 NativeUncaughtExceptionHandler.java} from JDK 17 after changing this
+ * file. CI checks that both match.
+ */
+final class NativeUncaughtExceptionHandler implements Thread.UncaughtExceptionHandler {
+    private static final ReferenceQueue<NativeUncaughtExceptionHandler> COLLECTED =
+            new ReferenceQueue<>();
+
+    // The closures of the handlers that are not freed yet, by the references to the handlers,
+    // which also keeps the references reachable until they are enqueued.
+    private static final Map<Reference<?>, Long> HANDLERS = new ConcurrentHashMap<>();
+
+    static {
+        Thread releaser = new Thread(
+                NativeUncaughtExceptionHandler::freeCollected,
+                "jni-rs uncaught exception handler releaser");
+        releaser.setDaemon(true);
+        releaser.start();
+    }
+
+    private final long handler;
+
+    private NativeUncaughtExceptionHandler(long handler) {
+        this.handler = handler;
+        HANDLERS.put(new PhantomReference<>(this, COLLECTED), handler);
+    }
+
+    @Override
+    public void uncaughtException(Thread thread, Throwable exception) {
+        uncaughtException(handler, thread, exception);
+    }
+
+    private static void freeCollected() {
+        while (true) {
+            try {
+                Long handler = HANDLERS.remove(COLLECTED.remove());
+                if (handler != null) {
+                    free(handler);
+                }
+            } catch (InterruptedException e) {
+                // Keep going, the closures can only be freed by this thread
+            }
+        }
+    }
+
+    private static native void uncaughtException(long handler, Thread thread, Throwable exception);
+
+    private static native void free(long handler);
+}
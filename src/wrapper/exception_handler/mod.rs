@@ -0,0 +1,108 @@
+use std::{
+    os::raw::c_void,
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::Mutex,
+};
+
+use lazy_static::lazy_static;
+use log::error;
+
+use crate::{
+    errors::*,
+    objects::{GlobalRef, JClass, JObject, JThrowable},
+    sys::jlong,
+    JNIEnv, NativeMethod,
+};
+
+/// A rust closure called for uncaught java exceptions, with the thread that
+/// was terminated by the exception.
+pub(crate) type UncaughtExceptionHandler =
+    Box<dyn for<'a> Fn(&JNIEnv<'a>, JObject<'a>, JThrowable<'a>) + Send + Sync>;
+
+const HANDLER_CLASS_NAME: &str = "jni/rs/NativeUncaughtExceptionHandler";
+
+/// Compiled from `NativeUncaughtExceptionHandler.java` for Java 8.
+const HANDLER_CLASS_DATA: &[u8] = include_bytes!("NativeUncaughtExceptionHandler.class");
+
+lazy_static! {
+    // The handler class and its class loader, created the first time a handler
+    // is created. The class is defined in a class loader of its own rather
+    // than in the bootstrap one, so that other native libraries linking this
+    // crate into the same JVM can define it too, and so that it can be
+    // unloaded with the loader.
+    static ref HANDLER_CLASS: Mutex<Option<(GlobalRef, GlobalRef)>> = Mutex::new(None);
+}
+
+/// Creates a `java.lang.Thread.UncaughtExceptionHandler` which calls the given
+/// closure. The closure is freed when the handler is garbage collected.
+pub(crate) fn new_handler<'a>(
+    env: &JNIEnv<'a>,
+    handler: UncaughtExceptionHandler,
+) -> Result<JObject<'a>> {
+    let class = handler_class(env)?;
+
+    let raw = Box::into_raw(Box::new(handler));
+    let res = env.new_object(&class, "(J)V", &[(raw as jlong).into()]);
+    if res.is_err() {
+        // The java object was not created, so it won't free the closure
+        drop(unsafe { Box::from_raw(raw) });
+    }
+    res
+}
+
+fn handler_class(env: &JNIEnv) -> Result<GlobalRef> {
+    let mut class = HANDLER_CLASS.lock().unwrap();
+    if let Some((class, _)) = class.as_ref() {
+        return Ok(class.clone());
+    }
+
+    // An empty `URLClassLoader` whose parent is the bootstrap class loader
+    let urls = env.auto_local(env.new_object_array(0, "java/net/URL", JObject::null())?);
+    let loader = env.auto_local(env.new_object(
+        "java/net/URLClassLoader",
+        "([Ljava/net/URL;Ljava/lang/ClassLoader;)V",
+        &[urls.as_obj().into(), JObject::null().into()],
+    )?);
+    let local = env.auto_local(env.define_class(
+        HANDLER_CLASS_NAME,
+        loader.as_obj(),
+        HANDLER_CLASS_DATA,
+    )?);
+    env.register_native_methods(
+        &local,
+        &[
+            NativeMethod {
+                name: "uncaughtException".into(),
+                sig: "(JLjava/lang/Thread;Ljava/lang/Throwable;)V".into(),
+                fn_ptr: uncaught_exception as *mut c_void,
+            },
+            NativeMethod {
+                name: "free".into(),
+                sig: "(J)V".into(),
+                fn_ptr: free as *mut c_void,
+            },
+        ],
+    )?;
+
+    let global = env.new_global_ref(&local)?;
+    *class = Some((global.clone(), env.new_global_ref(&loader)?));
+    Ok(global)
+}
+
+extern "system" fn uncaught_exception(
+    env: JNIEnv,
+    _class: JClass,
+    handler: jlong,
+    thread: JObject,
+    exception: JThrowable,
+) {
+    let handler = unsafe { &*(handler as *const UncaughtExceptionHandler) };
+    // Unwinding into the JVM is undefined behaviour
+    if catch_unwind(AssertUnwindSafe(|| handler(&env, thread, exception))).is_err() {
+        error!("uncaught exception handler panicked");
+    }
+}
+
+extern "system" fn free(_env: JNIEnv, _class: JClass, handler: jlong) {
+    drop(unsafe { Box::from_raw(handler as *mut UncaughtExceptionHandler) });
+}
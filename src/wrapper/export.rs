@@ -0,0 +1,197 @@
+use std::{
+    any::Any,
+    panic::{catch_unwind, AssertUnwindSafe},
+    ptr,
+};
+
+use log::error;
+
+use crate::{
+    descriptors::Desc,
+    errors::*,
+    objects::{JClass, JObject, JString, JThrowable},
+    sys::{jboolean, jbyte, jchar, jdouble, jfloat, jint, jlong, jobject, jshort},
+    JNIEnv,
+};
+
+/// The class of the exceptions thrown for panics, unless specified otherwise.
+pub(crate) const DEFAULT_PANIC_EXCEPTION_CLASS: &str = "java/lang/RuntimeException";
+
+/// A value that can be returned from a native method, with a placeholder for
+/// when the method throws instead. The JVM ignores the returned value while an
+/// exception is pending.
+pub trait JniReturn {
+    /// The value to return when the method throws.
+    fn default_return() -> Self;
+}
+
+macro_rules! impl_jni_return {
+    ( $( $jni_type:ty => $value:expr ),* ) => {
+        $(
+            impl JniReturn for $jni_type {
+                fn default_return() -> Self {
+                    $value
+                }
+            }
+        )*
+    };
+}
+
+impl_jni_return!(
+    () => (),
+    jboolean => 0,
+    jbyte => 0,
+    jchar => 0,
+    jshort => 0,
+    jint => 0,
+    jlong => 0,
+    jfloat => 0.0,
+    jdouble => 0.0,
+    jobject => ptr::null_mut()
+);
+
+impl<'a> JniReturn for JObject<'a> {
+    fn default_return() -> Self {
+        JObject::null()
+    }
+}
+
+impl<'a> JniReturn for JClass<'a> {
+    fn default_return() -> Self {
+        JObject::null().into()
+    }
+}
+
+impl<'a> JniReturn for JString<'a> {
+    fn default_return() -> Self {
+        JObject::null().into()
+    }
+}
+
+impl<'a> JniReturn for JThrowable<'a> {
+    fn default_return() -> Self {
+        JObject::null().into()
+    }
+}
+
+/// The return type of a function annotated with `jni_export`.
+pub trait ExportResult {
+    /// The type returned to the JVM.
+    type Output: JniReturn;
+
+    /// Converts the return value into the result of the native method.
+    fn into_result(self) -> Result<Self::Output>;
+}
+
+impl ExportResult for () {
+    type Output = ();
+
+    fn into_result(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<T: JniReturn> ExportResult for Result<T> {
+    type Output = T;
+
+    fn into_result(self) -> Result<T> {
+        self
+    }
+}
+
+/// Runs the body of an exported native method, turning errors and panics
+/// into java exceptions. Used by the code generated by `jni_export`.
+pub fn export_call<R, F>(env: &JNIEnv, f: F) -> R::Output
+where
+    R: ExportResult,
+    F: FnOnce() -> R,
+{
+    #[cfg(all(feature = "debug-refs", debug_assertions))]
+    let _frame = super::debug_refs::NativeFrame::enter();
+
+    match catch_unwind(AssertUnwindSafe(|| f().into_result())) {
+        Ok(Ok(value)) => value,
+        Ok(Err(err)) => {
+            throw_error(env, err);
+            R::Output::default_return()
+        }
+        Err(payload) => {
+            throw_panic(env, DEFAULT_PANIC_EXCEPTION_CLASS, payload);
+            R::Output::default_return()
+        }
+    }
+}
+
+/// Throws a java exception for an error returned from a native method.
+///
+/// An exception that is still pending is left alone, and one that was
+/// cleared into the error is thrown again. Otherwise, the exception class
+/// depends on the kind of the error.
+fn throw_error(env: &JNIEnv, err: Error) {
+    if let Error::JavaExceptionCleared { ref exception, .. } = err {
+        if env.throw(exception).is_ok() {
+            return;
+        }
+    }
+    if env.exception_check().unwrap_or(true) {
+        return;
+    }
+
+    let class = match err {
+        Error::NullPtr(_) | Error::NullDeref(_) => "java/lang/NullPointerException",
+        Error::WrongJValueType(..)
+        | Error::InvalidArgList(_)
+        | Error::InvalidUtf8 { .. }
+        | Error::InvalidChar(_)
+        | Error::ArrayLengthMismatch { .. }
+        | Error::WrongObjectClass(_)
+        | Error::ParseFailed(..) => "java/lang/IllegalArgumentException",
+        Error::MethodNotFound { .. } => "java/lang/NoSuchMethodError",
+        Error::FieldNotFound { .. } => "java/lang/NoSuchFieldError",
+        _ => "java/lang/RuntimeException",
+    };
+    if let Err(e) = env.throw_new(class, err.to_string()) {
+        error!("failed to throw {} for {}: {}", class, err, e);
+    }
+}
+
+/// Throws an exception of the given class for a panic in a native method,
+/// unless an exception is already pending.
+fn throw_panic<'a, 'c, T>(env: &JNIEnv<'a>, class: T, payload: Box<dyn Any + Send>)
+where
+    T: Desc<'a, JClass<'c>>,
+{
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("Box<dyn Any>");
+    if env.exception_check().unwrap_or(true) {
+        error!(
+            "native method panicked with a pending exception: {}",
+            message
+        );
+        return;
+    }
+
+    if let Err(e) = env.throw_new(class, message) {
+        error!("failed to throw for panic {}: {}", message, e);
+    }
+}
+
+/// Runs a closure, catching a panic and throwing a java exception of the given
+/// class for it instead. See `JNIEnv::catch_unwind`.
+pub(crate) fn catch_unwind_throwing<'a, 'c, T, R, F>(env: &JNIEnv<'a>, class: T, f: F) -> R
+where
+    T: Desc<'a, JClass<'c>>,
+    R: JniReturn,
+    F: FnOnce() -> R,
+{
+    #[cfg(all(feature = "debug-refs", debug_assertions))]
+    let _frame = super::debug_refs::NativeFrame::enter();
+
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        throw_panic(env, class, payload);
+        R::default_return()
+    })
+}
@@ -1,5 +1,5 @@
 use std::{
-    cell::RefCell,
+    cell::{Cell, RefCell},
     ops::Deref,
     ptr,
     sync::atomic::{AtomicUsize, Ordering},
@@ -8,7 +8,7 @@ use std::{
 
 use log::{debug, error};
 
-use crate::{errors::*, sys, JNIEnv};
+use crate::{errors::*, objects::JObject, sys, JNIEnv};
 
 #[cfg(feature = "invocation")]
 use crate::InitArgs;
@@ -166,6 +166,31 @@ impl JavaVM {
         }
     }
 
+    /// Returns the JavaVMs that have been created in this process, for example by the
+    /// application that loaded this library. A process supports at most one JavaVM, so use this
+    /// to obtain it instead of launching a new one with `JavaVM#new`.
+    ///
+    /// *This API requires "invocation" feature to be enabled,
+    /// see ["Launching JVM from Rust"](struct.JavaVM.html#launching-jvm-from-rust).*
+    #[cfg(feature = "invocation")]
+    pub fn get_created_java_vms() -> Result<Vec<Self>> {
+        let mut count: sys::jsize = 0;
+
+        unsafe {
+            jni_error_code_to_result(sys::JNI_GetCreatedJavaVMs(ptr::null_mut(), 0, &mut count))?;
+
+            let mut buf: Vec<*mut sys::JavaVM> = vec![ptr::null_mut(); count as usize];
+            jni_error_code_to_result(sys::JNI_GetCreatedJavaVMs(
+                buf.as_mut_ptr(),
+                count,
+                &mut count,
+            ))?;
+            buf.truncate(count as usize);
+
+            buf.into_iter().map(|vm| Self::from_raw(vm)).collect()
+        }
+    }
+
     /// Create a JavaVM from a raw pointer.
     ///
     /// # Safety
@@ -221,6 +246,72 @@ impl JavaVM {
         }
     }
 
+    /// Executes the given closure in the current thread, attached to the Java VM, inside a new
+    /// local reference frame with at least the given capacity. All the local references
+    /// created in the closure are freed when it returns.
+    ///
+    /// The thread is attached with [`attach_current_thread`][act] for the duration of the call,
+    /// and detached afterwards. If the thread is already attached, the existing attachment is
+    /// reused and the thread is _not_ detached at the end, so it's safe to call this from
+    /// native methods and from threads attached permanently.
+    ///
+    /// Like `attach_current_thread`, attaching and detaching is expensive; for frequent calls in
+    /// threads that aren't attached yet, consider the [`Executor`](struct.Executor.html), which
+    /// attaches threads permanently.
+    ///
+    /// [act]: struct.JavaVM.html#method.attach_current_thread
+    pub fn with_attached_frame<F, R>(&self, capacity: i32, f: F) -> Result<R>
+    where
+        F: FnOnce(&JNIEnv) -> Result<R>,
+    {
+        let env = self.attach_current_thread()?;
+        let mut result = None;
+        env.with_local_frame(capacity, || {
+            result = Some(f(&env));
+            Ok(JObject::null())
+        })?;
+
+        result.expect("The result should be Some or this line shouldn't be reached")
+    }
+
+    /// Executes the given closure in the current thread, attached to the Java VM, and makes its
+    /// `JNIEnv` the _current_ one for the duration of the call. Code called by the closure can
+    /// then get it with [`with_current_env`][wce] instead of passing a `&JNIEnv` through every
+    /// function.
+    ///
+    /// The thread is attached like with [`attach_current_thread`][act]: an existing attachment
+    /// is reused, otherwise the thread is detached when the closure returns. Calls can be
+    /// nested, and the previous current `JNIEnv` is restored when the inner call returns.
+    ///
+    /// [wce]: struct.JavaVM.html#method.with_current_env
+    /// [act]: struct.JavaVM.html#method.attach_current_thread
+    pub fn with_env<F, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(&JNIEnv) -> R,
+    {
+        let env = self.attach_current_thread()?;
+        let _scope = CurrentEnvScope::enter(&env);
+        Ok(f(&env))
+    }
+
+    /// Executes the given closure with the current `JNIEnv` of this thread, set by the innermost
+    /// enclosing call to [`with_env`][we].
+    ///
+    /// Returns `Error::NoCurrentEnv` outside of `with_env`, even if the thread is attached.
+    ///
+    /// [we]: struct.JavaVM.html#method.with_env
+    pub fn with_current_env<F, R>(f: F) -> Result<R>
+    where
+        F: FnOnce(&JNIEnv) -> R,
+    {
+        let ptr = CURRENT_ENV.with(Cell::get);
+        if ptr.is_null() {
+            return Err(Error::NoCurrentEnv);
+        }
+        let env = unsafe { JNIEnv::from_raw(ptr) }?;
+        Ok(f(&env))
+    }
+
     /// Detaches current thread from the JVM. This operation is _rarely_ appropriate to use,
     /// because the attachment methods [ensure](#attaching-native-threads) that the thread is
     /// promptly detached.
@@ -245,7 +336,15 @@ impl JavaVM {
     /// Attaches the current thread to the Java VM as a _daemon_. Calling this in a thread
     /// that is already attached is a no-op, and will not change its status to a daemon thread.
     ///
-    /// The thread will detach itself automatically when it exits.
+    /// Daemon threads don't block JVM exit. Like with
+    /// [`attach_current_thread_permanently`][attach-permanently], the thread stays attached
+    /// until it exits, when it detaches itself automatically; `AttachGuard`s created for it
+    /// later are nested and don't detach it. Use
+    /// [`attach_current_thread_as_daemon_scoped`][attach-as-daemon-scoped] to detach the thread
+    /// earlier.
+    ///
+    /// [attach-permanently]: struct.JavaVM.html#method.attach_current_thread_permanently
+    /// [attach-as-daemon-scoped]: struct.JavaVM.html#method.attach_current_thread_as_daemon_scoped
     pub fn attach_current_thread_as_daemon(&self) -> Result<JNIEnv> {
         match self.get_env() {
             Ok(env) => Ok(env),
@@ -253,6 +352,22 @@ impl JavaVM {
         }
     }
 
+    /// Attaches the current thread to the Java VM as a _daemon_, and returns an `AttachGuard`
+    /// which detaches the thread when dropped, like [`attach_current_thread`][act] does for
+    /// non-daemon threads. Calling this in a thread that is already attached is a no-op, and
+    /// will neither change its daemon status nor prematurely detach it.
+    ///
+    /// [act]: struct.JavaVM.html#method.attach_current_thread
+    pub fn attach_current_thread_as_daemon_scoped(&self) -> Result<AttachGuard<'_>> {
+        match self.get_env() {
+            Ok(env) => Ok(AttachGuard::new_nested(env)),
+            Err(_) => {
+                let env = self.attach_current_thread_impl(ThreadType::Daemon)?;
+                Ok(AttachGuard::new(env))
+            }
+        }
+    }
+
     /// Returns the current number of threads attached to the JVM.
     ///
     /// This method is provided mostly for diagnostic purposes.
@@ -285,6 +400,8 @@ impl JavaVM {
         };
 
         InternalAttachGuard::fill_tls(guard);
+        #[cfg(all(feature = "debug-refs", debug_assertions))]
+        crate::wrapper::debug_refs::push_frame();
 
         unsafe { JNIEnv::from_raw(env_ptr as *mut sys::JNIEnv) }
     }
@@ -294,8 +411,31 @@ thread_local! {
     static THREAD_ATTACH_GUARD: RefCell<Option<InternalAttachGuard>> = RefCell::new(None)
 }
 
+thread_local! {
+    static CURRENT_ENV: Cell<*mut sys::JNIEnv> = const { Cell::new(ptr::null_mut()) }
+}
+
 static ATTACHED_THREADS: AtomicUsize = AtomicUsize::new(0);
 
+/// Sets the current `JNIEnv` of the thread for `JavaVM::with_env`, and restores the previous
+/// one when dropped, even if the closure panics.
+struct CurrentEnvScope {
+    previous: *mut sys::JNIEnv,
+}
+
+impl CurrentEnvScope {
+    fn enter(env: &JNIEnv) -> Self {
+        let previous = CURRENT_ENV.with(|current| current.replace(env.get_native_interface()));
+        CurrentEnvScope { previous }
+    }
+}
+
+impl Drop for CurrentEnvScope {
+    fn drop(&mut self) {
+        CURRENT_ENV.with(|current| current.set(self.previous));
+    }
+}
+
 /// A RAII implementation of scoped guard which detaches the current thread
 /// when dropped. The attached `JNIEnv` can be accessed through this guard
 /// via its `Deref` implementation.
@@ -418,6 +558,8 @@ impl InternalAttachGuard {
         unsafe {
             java_vm_unchecked!(self.java_vm, DetachCurrentThread);
         }
+        #[cfg(all(feature = "debug-refs", debug_assertions))]
+        crate::wrapper::debug_refs::clear();
         ATTACHED_THREADS.fetch_sub(1, Ordering::SeqCst);
         debug!(
             "Detached thread {} ({:?}). {} threads remain attached",
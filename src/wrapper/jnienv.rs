@@ -1,31 +1,85 @@
 use std::{
+    convert::TryFrom,
+    ffi::OsStr,
     marker::PhantomData,
     os::raw::{c_char, c_void},
     ptr, slice, str,
     str::FromStr,
-    sync::{Mutex, MutexGuard},
+    sync::{Arc, Mutex, MutexGuard},
 };
 
+use lazy_static::lazy_static;
 use log::warn;
 
+#[cfg(feature = "metrics")]
+use super::call_metrics::CallTimer;
+use super::exception_handler;
+use super::export::{self, JniReturn, DEFAULT_PANIC_EXCEPTION_CLASS};
+
 use crate::{
     descriptors::Desc,
     errors::*,
     objects::{
-        AutoArray, AutoLocal, AutoPrimitiveArray, GlobalRef, JByteBuffer, JClass, JFieldID, JList,
-        JMap, JMethodID, JObject, JStaticFieldID, JStaticMethodID, JString, JThrowable, JValue,
-        ReleaseMode, TypeArray,
+        AutoArray, AutoLocal, AutoPrimitiveArray, AutoStringChars, Constructor, DirectBufferGuard,
+        GlobalRef, IdentityCache, JByteBuffer, JClass, JFieldID, JList, JMap, JMethodID, JObject,
+        JStaticFieldID, JStaticMethodID, JString, JThrowable, JValue, LocalFrame, MethodHandle,
+        MethodIdCache, Modifiers, ObjectArrayAutoIter, ObjectArrayIter, ObjectPool, RefType,
+        ReleaseMode, TypeArray, WeakRef,
     },
     signature::{JavaType, Primitive, TypeSignature},
     strings::{JNIString, JavaStr},
     sys::{
         self, jarray, jboolean, jbooleanArray, jbyte, jbyteArray, jchar, jcharArray, jdouble,
-        jdoubleArray, jfloat, jfloatArray, jint, jintArray, jlong, jlongArray, jobjectArray,
-        jshort, jshortArray, jsize, jvalue, JNINativeMethod,
+        jdoubleArray, jfieldID, jfloat, jfloatArray, jint, jintArray, jlong, jlongArray, jmethodID,
+        jobjectArray, jshort, jshortArray, jsize, jvalue, JNINativeMethod,
     },
     JNIVersion, JavaVM,
 };
 
+lazy_static! {
+    // Field signatures found by `get_field_inferred`/`set_field_inferred`, keyed by
+    // the class and the field name.
+    static ref INFERRED_FIELD_SIGS: Mutex<IdentityCache<String, String>> =
+        Mutex::new(IdentityCache::new(FIELD_CACHE_CAPACITY));
+
+    // Accessible reflective fields used by `get_volatile_field`/`set_volatile_field`,
+    // keyed by the class and the field name and type.
+    static ref VOLATILE_FIELDS: Mutex<IdentityCache<(String, String), GlobalRef>> =
+        Mutex::new(IdentityCache::new(FIELD_CACHE_CAPACITY));
+
+    // Strings decoded by `get_string_cached`.
+    static ref STRING_CACHE: Mutex<IdentityCache<(), Arc<str>>> =
+        Mutex::new(IdentityCache::new(STRING_CACHE_CAPACITY));
+
+    // Method ids of `java.lang.Object` used by `object_hash_code`/`objects_equal`.
+    static ref OBJECT_METHODS: MethodIdCache = MethodIdCache::new();
+
+    // Method id of `System#identityHashCode`, used to key the identity caches.
+    static ref SYSTEM_METHODS: MethodIdCache = MethodIdCache::new();
+
+    // `java.lang.InterruptedException`, looked up by `check_interrupted` the
+    // first time a called method throws.
+    static ref INTERRUPTED_EXCEPTION_CLASS: Mutex<Option<GlobalRef>> = Mutex::new(None);
+}
+
+/// The maximum number of strings kept by `get_string_cached`.
+const STRING_CACHE_CAPACITY: usize = 256;
+
+#[cfg(feature = "metrics")]
+lazy_static! {
+    // Names of the classes in the labels of the call metrics.
+    static ref METRICS_CLASS_NAMES: Mutex<IdentityCache<(), Arc<str>>> =
+        Mutex::new(IdentityCache::new(METRICS_CLASS_NAMES_CAPACITY));
+}
+
+/// The maximum number of class names kept for the labels of the call metrics.
+#[cfg(feature = "metrics")]
+const METRICS_CLASS_NAMES_CAPACITY: usize = 256;
+
+/// The maximum number of fields kept by each of the caches of
+/// `get_field_inferred` and `get_volatile_field`.
+const FIELD_CACHE_CAPACITY: usize = 256;
+
 /// FFI-compatible JNIEnv struct. You can safely use this as the JNIEnv argument
 /// to exported methods that will be called by java. This is where most of the
 /// magic happens. All methods on this object are wrappers around JNI functions,
@@ -39,6 +93,17 @@ use crate::{
 /// will _not_ clear the exception - it's up to the caller to decide whether to
 /// do so or to let it continue being thrown.
 ///
+/// An `InterruptedException` thrown by a called method or constructor is
+/// reported with the kind `Interrupted` instead, as it usually means that the
+/// thread is asked to stop rather than a failure. The exception is still left
+/// pending. This is done by `call_method_unchecked`,
+/// `call_static_method_unchecked`, `call_nonvirtual_method_unchecked` and
+/// `new_object_unchecked`, and so by all the methods calling into java
+/// through them, like `call_method`, `new_object`, the cached calls,
+/// `DynamicDispatch` and the `JList`, `JMap` and `JIterator` wrappers.
+/// Exceptions thrown by other JNI functions, such as a failed class or method
+/// lookup, are always reported as `JavaException`.
+///
 /// ## `null` Java references
 /// `null` Java references are handled by the following rules:
 ///   - If a `null` Java reference is passed to a method that expects a non-`null`
@@ -74,6 +139,18 @@ use crate::{
 ///
 /// Calling unchecked methods with invalid arguments and/or invalid class and
 /// method descriptors may lead to segmentation fault.
+///
+/// # Metrics
+///
+/// With the `metrics` feature, `call_method` and `call_static_method` record
+/// their calls through the [`metrics`](https://docs.rs/metrics) facade, labeled
+/// with the `class` and `method` names:
+///   - `jni_calls_total` — a counter of the calls
+///   - `jni_call_duration_seconds` — a histogram of the call latencies
+///
+/// Getting the class name takes an extra reflective call per method call.
+/// The unchecked methods are never recorded, and nothing is recorded without
+/// the feature.
 #[derive(Clone, Copy)]
 #[repr(transparent)]
 pub struct JNIEnv<'a> {
@@ -95,13 +172,24 @@ impl<'a> JNIEnv<'a> {
         })
     }
 
-    /// Get the java version that we're being executed from.
+    /// Get the version of the JNI interface provided by the JVM, e.g. `V10`
+    /// for java 10 and later. This can be used to check for the availability
+    /// of JNI functions added in later versions.
+    ///
+    /// Versions that are not known to this crate are returned as
+    /// `JNIVersion::Invalid` with the raw version number; newer versions have
+    /// greater numbers.
     pub fn get_version(&self) -> Result<JNIVersion> {
         Ok(jni_unchecked!(self.internal, GetVersion).into())
     }
 
-    /// Load a class from a buffer of raw class data. The name of the class must match the name
-    /// encoded within the class file data.
+    /// Load a class from a buffer of raw class data, e.g. generated at runtime, with `DefineClass`.
+    /// The name of the class must match the name encoded within the class file data. The class is
+    /// defined in the given class loader, or the bootstrap class loader if it's `null`.
+    ///
+    /// If the class can't be defined, an `Error::JavaException` is returned, with the exception
+    /// pending: a `ClassFormatError` for invalid class data, a `NoClassDefFoundError` if the name
+    /// doesn't match, or a `LinkageError` if the loader already defined a class with that name.
     pub fn define_class<S>(&self, name: S, loader: JObject<'a>, buf: &[u8]) -> Result<JClass<'a>>
     where
         S: Into<JNIString>,
@@ -111,11 +199,8 @@ impl<'a> JNIEnv<'a> {
     }
 
     /// Load a class from a buffer of raw class data. The name of the class is inferred from the
-    /// buffer.
-    pub fn define_unnamed_class<S>(&self, loader: JObject<'a>, buf: &[u8]) -> Result<JClass<'a>>
-    where
-        S: Into<JNIString>,
-    {
+    /// buffer. Errors are reported like with `define_class`.
+    pub fn define_unnamed_class(&self, loader: JObject<'a>, buf: &[u8]) -> Result<JClass<'a>> {
         self.define_class_impl(ptr::null(), loader, buf)
     }
 
@@ -161,6 +246,21 @@ impl<'a> JNIEnv<'a> {
         Ok(jni_non_void_call!(self.internal, GetSuperclass, class.into_inner()).into())
     }
 
+    /// Returns the superclass for a particular class, like `get_superclass`,
+    /// but returns `None` instead of a null class for `java.lang.Object` and
+    /// interfaces.
+    pub fn get_superclass_opt<'c, T>(&self, class: T) -> Result<Option<JClass<'a>>>
+    where
+        T: Desc<'a, JClass<'c>>,
+    {
+        let superclass = self.get_superclass(class)?;
+        Ok(if superclass.is_null() {
+            None
+        } else {
+            Some(superclass)
+        })
+    }
+
     /// Tests whether class1 is assignable from class2.
     pub fn is_assignable_from<'t, 'u, T, U>(&self, class1: T, class2: U) -> Result<bool>
     where
@@ -200,6 +300,10 @@ impl<'a> JNIEnv<'a> {
 
     /// Returns true if ref1 and ref2 refer to the same Java object, or are both `NULL`. Otherwise,
     /// returns false.
+    ///
+    /// Note that comparing the raw `jobject` pointers of two references is _not_ the same: a local
+    /// and a global reference to the same object have different pointers. See also `RefEq` for
+    /// comparing with `==`.
     pub fn is_same_object<'b, 'c, O, T>(&self, ref1: O, ref2: T) -> Result<bool>
     where
         O: Into<JObject<'b>>,
@@ -213,6 +317,33 @@ impl<'a> JNIEnv<'a> {
         ) == sys::JNI_TRUE)
     }
 
+    /// Get the kind of a reference, with `GetObjectRefType`: local, global or
+    /// weak global. This is meant for debugging reference leaks, e.g. to check
+    /// that an object kept across native calls isn't a local reference.
+    ///
+    /// `null` is reported as `RefType::Invalid`. The reference must not have
+    /// been deleted, or the behavior is undefined.
+    pub fn get_object_ref_type<'b, O>(&self, obj: O) -> Result<RefType>
+    where
+        O: Into<JObject<'b>>,
+    {
+        let obj = obj.into();
+        if obj.is_null() {
+            return Ok(RefType::Invalid);
+        }
+        Ok(jni_unchecked!(self.internal, GetObjectRefType, obj.into_inner()).into())
+    }
+
+    /// Compares two references by object identity, like `==` in java. This is
+    /// the same as `is_same_object`.
+    pub fn ref_equals<'b, 'c, O, T>(&self, ref1: O, ref2: T) -> Result<bool>
+    where
+        O: Into<JObject<'b>>,
+        T: Into<JObject<'c>>,
+    {
+        self.is_same_object(ref1, ref2)
+    }
+
     /// Raise an exception from an existing object. This will continue being
     /// thrown in java unless `exception_clear` is called.
     ///
@@ -221,11 +352,24 @@ impl<'a> JNIEnv<'a> {
     /// let _ = env.throw(("java/lang/Exception", "something bad happened"));
     /// ```
     ///
-    /// Defaulting to "java/lang/Exception":
+    /// Defaulting to "java/lang/RuntimeException":
     ///
     /// ```rust,ignore
     /// let _ = env.throw("something bad happened");
     /// ```
+    ///
+    /// Rethrowing an exception captured in an error:
+    ///
+    /// ```rust,ignore
+    /// if let Err(e) = env.capture_exception(res) {
+    ///     if let Some(exception) = e.java_exception() {
+    ///         env.throw(exception)?;
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Returns `Err` with the kind `ThrowFailed` if the exception can't be
+    /// thrown.
     pub fn throw<'e, E>(&self, obj: E) -> Result<()>
     where
         E: Desc<'a, JThrowable<'e>>,
@@ -261,6 +405,33 @@ impl<'a> JNIEnv<'a> {
         }
     }
 
+    /// Like `throw_new`, but also returns a global reference to the thrown
+    /// exception, e.g. to log it or to attach it as the cause of another one
+    /// later. This saves fetching it again with `exception_occurred`.
+    ///
+    /// The exception is created with its `(Ljava/lang/String;)V` constructor.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let exception = env.throw_new_returning("java/lang/Exception", "something bad happened")?;
+    /// ```
+    pub fn throw_new_returning<'c, S, T>(&self, class: T, msg: S) -> Result<GlobalRef>
+    where
+        S: Into<JNIString>,
+        T: Desc<'a, JClass<'c>>,
+    {
+        let class = class.lookup(self)?;
+        let msg = self.auto_local(self.new_string(msg)?);
+        let throwable = self.auto_local(self.new_object(
+            class,
+            "(Ljava/lang/String;)V",
+            &[msg.as_obj().into()],
+        )?);
+        let global = self.new_global_ref(&throwable)?;
+        self.throw(JThrowable::from(throwable.as_obj()))?;
+        Ok(global)
+    }
+
     /// Check whether or not an exception is currently in the process of being
     /// thrown. An exception is in this state from the time it gets thrown and
     /// not caught in a java function until `exception_clear` is called.
@@ -269,12 +440,27 @@ impl<'a> JNIEnv<'a> {
         Ok(JThrowable::from(throwable))
     }
 
-    /// Print exception information to the console.
+    /// Print the pending exception and its stack trace to stderr, for
+    /// debugging. Does nothing if no exception is pending.
+    ///
+    /// Note that this clears the exception, like `exception_clear` would. Use
+    /// `exception_describe_and_keep` to leave it pending.
     pub fn exception_describe(&self) -> Result<()> {
         jni_unchecked!(self.internal, ExceptionDescribe);
         Ok(())
     }
 
+    /// Print the pending exception and its stack trace to stderr, like
+    /// `exception_describe`, then throw it again so that it stays pending.
+    pub fn exception_describe_and_keep(&self) -> Result<()> {
+        let exception = self.auto_local(self.exception_occurred()?);
+        if exception.as_obj().is_null() {
+            return Ok(());
+        }
+        self.exception_describe()?;
+        self.throw(JThrowable::from(exception.as_obj()))
+    }
+
     /// Clear an exception in the process of being thrown. If this is never
     /// called, the exception will continue being thrown when control is
     /// returned to java.
@@ -284,7 +470,7 @@ impl<'a> JNIEnv<'a> {
     }
 
     /// Abort the JVM with an error message.
-    #[allow(unused_variables, unreachable_code)]
+    #[allow(unused_variables, unreachable_code, clippy::diverging_sub_expression)]
     pub fn fatal_error<S: Into<JNIString>>(&self, msg: S) -> ! {
         let msg = msg.into();
         let res: Result<()> = catch!({
@@ -295,6 +481,47 @@ impl<'a> JNIEnv<'a> {
         panic!("{:?}", res.unwrap_err());
     }
 
+    /// Runs a closure, catching a panic instead of letting it unwind into the
+    /// JVM, which is undefined behaviour. This is meant to wrap the body of a
+    /// native method:
+    ///
+    /// ```rust,ignore
+    /// #[no_mangle]
+    /// pub extern "system" fn Java_HelloWorld_hello(env: JNIEnv, _class: JClass, input: JString) -> jstring {
+    ///     env.catch_unwind(|| {
+    ///         let input: String = env.get_string(input).unwrap().into();
+    ///         env.new_string(format!("Hello, {}!", input)).unwrap().into_inner()
+    ///     })
+    /// }
+    /// ```
+    ///
+    /// On a panic, a `java.lang.RuntimeException` with the panic message is
+    /// thrown, unless an exception is already pending, and a placeholder
+    /// value like `0` or `null` is returned, which the JVM ignores. Use
+    /// `catch_unwind_throwing` to throw a different exception.
+    ///
+    /// The closure is assumed to be unwind safe, as the state it shares with
+    /// the caller is usually discarded once the exception is thrown.
+    pub fn catch_unwind<R, F>(&self, f: F) -> R
+    where
+        R: JniReturn,
+        F: FnOnce() -> R,
+    {
+        self.catch_unwind_throwing(DEFAULT_PANIC_EXCEPTION_CLASS, f)
+    }
+
+    /// Like `catch_unwind`, but throws an exception of the given class for a
+    /// panic. The class needs to have a constructor that takes the message as
+    /// a `String`.
+    pub fn catch_unwind_throwing<'c, T, R, F>(&self, class: T, f: F) -> R
+    where
+        T: Desc<'a, JClass<'c>>,
+        R: JniReturn,
+        F: FnOnce() -> R,
+    {
+        export::catch_unwind_throwing(self, class, f)
+    }
+
     /// Check to see if an exception is being thrown. This only differs from
     /// `exception_occurred` in that it doesn't return the actual thrown
     /// exception.
@@ -305,11 +532,30 @@ impl<'a> JNIEnv<'a> {
 
     /// Create a new instance of a direct java.nio.ByteBuffer.
     pub fn new_direct_byte_buffer(&self, data: &mut [u8]) -> Result<JByteBuffer<'a>> {
+        unsafe { self.new_direct_byte_buffer_raw(data.as_mut_ptr(), data.len()) }
+    }
+
+    /// Create a new instance of a direct java.nio.ByteBuffer over `len` bytes
+    /// of memory starting at `data`, such as memory from `mmap` or a custom
+    /// allocator that isn't borrowed from rust.
+    ///
+    /// # Safety
+    ///
+    /// `data` must be valid for reads and writes of `len` bytes for as long as
+    /// the buffer is reachable from java, which may be long after the returned
+    /// local reference is deleted. The JVM never frees the memory: the caller
+    /// stays responsible for it, and must make sure that java code doesn't use
+    /// the buffer anymore before releasing it.
+    pub unsafe fn new_direct_byte_buffer_raw(
+        &self,
+        data: *mut u8,
+        len: usize,
+    ) -> Result<JByteBuffer<'a>> {
         let obj: JObject = jni_non_null_call!(
             self.internal,
             NewDirectByteBuffer,
-            data.as_mut_ptr() as *mut c_void,
-            data.len() as jlong
+            data as *mut c_void,
+            len as jlong
         );
         Ok(JByteBuffer::from(obj))
     }
@@ -325,6 +571,24 @@ impl<'a> JNIEnv<'a> {
         unsafe { Ok(slice::from_raw_parts_mut(ptr as *mut u8, capacity as usize)) }
     }
 
+    /// Runs a closure with the memory of the direct java.nio.ByteBuffer, as a
+    /// slice of its full capacity, so that the slice can't be kept past the
+    /// closure.
+    ///
+    /// This is not any safer than `get_direct_buffer_address` though: nested
+    /// calls with the same buffer, or calls on other threads, get mutable
+    /// slices of the same memory, which must not be used at the same time.
+    /// Neither is java code prevented from accessing the buffer meanwhile.
+    ///
+    /// If the buffer is `null` or isn't a direct buffer, an `Error::NullPtr`
+    /// is returned without running the closure.
+    pub fn with_direct_buffer<F, R>(&self, buf: JByteBuffer, f: F) -> Result<R>
+    where
+        F: FnOnce(&mut [u8]) -> R,
+    {
+        self.get_direct_buffer_address(buf).map(f)
+    }
+
     /// Returns the capacity of the direct java.nio.ByteBuffer.
     pub fn get_direct_buffer_capacity(&self, buf: JByteBuffer) -> Result<jlong> {
         let capacity = jni_unchecked!(self.internal, GetDirectBufferCapacity, buf.into_inner());
@@ -347,6 +611,19 @@ impl<'a> JNIEnv<'a> {
         Ok(global)
     }
 
+    /// Creates a weak global ref to an object, which doesn't prevent it from
+    /// being garbage collected. See `WeakRef` for how to use the object.
+    pub fn new_weak_ref<O>(&self, obj: O) -> Result<WeakRef>
+    where
+        O: Into<JObject<'a>>,
+    {
+        let obj = obj.into();
+        non_null!(obj, "new_weak_ref obj argument");
+        let raw: JObject = jni_non_null_call!(self.internal, NewWeakGlobalRef, obj.into_inner());
+        let weak = unsafe { WeakRef::from_raw(self.get_java_vm()?, raw.into_inner()) };
+        Ok(weak)
+    }
+
     /// Create a new local ref to an object.
     ///
     /// Note that the object passed to this is *already* a local ref. This
@@ -357,6 +634,20 @@ impl<'a> JNIEnv<'a> {
         Ok(local)
     }
 
+    /// Creates a new local ref to an object, which is deleted when the
+    /// returned `AutoLocal` gets dropped. This is the leak-free way to get a
+    /// local reference to the object of a `GlobalRef` or an upgraded
+    /// `WeakRef`.
+    ///
+    /// A null object results in an `AutoLocal` of null.
+    pub fn new_auto_local_ref<O>(&self, obj: O) -> Result<AutoLocal<'a, '_>>
+    where
+        O: Into<JObject<'a>>,
+    {
+        let local = self.new_local_ref::<JObject>(obj.into())?;
+        Ok(self.auto_local(local))
+    }
+
     /// Creates a new auto-deleted local reference.
     ///
     /// See also [`with_local_frame`](struct.JNIEnv.html#method.with_local_frame) method that
@@ -388,8 +679,22 @@ impl<'a> JNIEnv<'a> {
     ///
     /// In most cases it is better to use `AutoLocal` (see `auto_local` method)
     /// or `with_local_frame` instead of direct `delete_local_ref` calls.
+    ///
+    /// With the `debug-refs` feature, debug builds keep track of the deleted
+    /// local references of each thread, and panic when a reference is deleted
+    /// twice (e.g. manually and then by an `AutoLocal`) or passed to another
+    /// JNI call after being deleted, instead of leaving it to undefined
+    /// behavior. The deletions are tracked in local frames, in native methods
+    /// written with `jni_export` or run through `catch_unwind`, and in threads
+    /// attached by this crate, until they end.
     pub fn delete_local_ref(&self, obj: JObject) -> Result<()> {
+        #[cfg(all(feature = "debug-refs", debug_assertions))]
+        super::debug_refs::check_delete_local_ref(obj.into_inner());
+
         jni_unchecked!(self.internal, DeleteLocalRef, obj.into_inner());
+
+        #[cfg(all(feature = "debug-refs", debug_assertions))]
+        super::debug_refs::deleted_local_ref(obj.into_inner());
         Ok(())
     }
 
@@ -406,7 +711,11 @@ impl<'a> JNIEnv<'a> {
     pub fn push_local_frame(&self, capacity: i32) -> Result<()> {
         // This method is safe to call in case of pending exceptions (see chapter 2 of the spec)
         let res = jni_unchecked!(self.internal, PushLocalFrame, capacity);
-        jni_error_code_to_result(res)
+        jni_error_code_to_result(res)?;
+
+        #[cfg(all(feature = "debug-refs", debug_assertions))]
+        super::debug_refs::push_frame();
+        Ok(())
     }
 
     /// Pops off the current local reference frame, frees all the local
@@ -416,7 +725,30 @@ impl<'a> JNIEnv<'a> {
     /// The resulting `JObject` will be `NULL` iff `result` is `NULL`.
     pub fn pop_local_frame(&self, result: JObject<'a>) -> Result<JObject<'a>> {
         // This method is safe to call in case of pending exceptions (see chapter 2 of the spec)
-        Ok(jni_unchecked!(self.internal, PopLocalFrame, result.into_inner()).into())
+        let result = jni_unchecked!(self.internal, PopLocalFrame, result.into_inner());
+
+        #[cfg(all(feature = "debug-refs", debug_assertions))]
+        super::debug_refs::pop_frame();
+        Ok(result.into())
+    }
+
+    /// Creates a new local reference frame like `push_local_frame`, and
+    /// returns a guard which pops it when dropped. This is convenient for
+    /// imperative code that creates many local references across several
+    /// statements:
+    ///
+    /// ```rust,ignore
+    /// let frame = env.push_auto_local_frame(16)?;
+    /// let name = env.new_string("name")?;
+    /// let value = env.call_method(map, "get", "(Ljava/lang/Object;)Ljava/lang/Object;", &[name.into()])?.l()?;
+    /// // `name` is deleted, `value` is kept in the enclosing frame
+    /// let value = frame.pop_with(value)?;
+    /// ```
+    ///
+    /// See `LocalFrame` for the restrictions on the references created in the frame.
+    pub fn push_auto_local_frame(&self, capacity: i32) -> Result<LocalFrame<'a, '_>> {
+        self.push_local_frame(capacity)?;
+        Ok(LocalFrame::new(self))
     }
 
     /// Executes the given function in a new local reference frame, in which at least a given number
@@ -442,6 +774,43 @@ impl<'a> JNIEnv<'a> {
         }
     }
 
+    /// Executes the given function with a pool of `count` pre-allocated
+    /// objects of a class, which the function can take objects from and give
+    /// them back for reuse. This reduces the allocation pressure of loops that
+    /// create and discard many objects of the same class. See `ObjectPool` for
+    /// the constraints on the pooled objects.
+    ///
+    /// The function runs in a new local reference frame, so all the pooled
+    /// objects, as well as any other local references created by the
+    /// function, are deleted once it returns. The function is given a
+    /// `JNIEnv` whose lifetime is limited to the frame, so the references
+    /// created through it (or taken from the pool) can't be returned.
+    ///
+    /// Returns `Err` with the kind `JniCall` if `count` is too large for the
+    /// capacity of a local frame.
+    pub fn with_object_pool<'c, T, F, R>(&self, class: T, count: usize, f: F) -> Result<R>
+    where
+        T: Desc<'a, JClass<'c>>,
+        F: for<'p> FnOnce(&JNIEnv<'p>, &ObjectPool<'p, '_>) -> Result<R>,
+    {
+        let capacity = i32::try_from(count)
+            .ok()
+            .and_then(|count| count.checked_add(16))
+            .ok_or(Error::JniCall(JniError::InvalidArguments))?;
+
+        let mut result = None;
+        self.with_local_frame(capacity, || {
+            let env = unsafe { JNIEnv::from_raw(self.internal)? };
+            // The class lives in the new frame
+            let class = JClass::from(class.lookup(self)?.into_inner());
+            let pool = ObjectPool::new(&env, class, count)?;
+            result = Some(f(&env, &pool));
+            Ok(JObject::null())
+        })?;
+
+        result.expect("The result should be Some or this line shouldn't be reached")
+    }
+
     /// Allocates a new object from a class descriptor without running a
     /// constructor.
     pub fn alloc_object<'c, T>(&self, class: T) -> Result<JObject<'a>>
@@ -545,11 +914,197 @@ impl<'a> JNIEnv<'a> {
         })
     }
 
-    /// Look up the field ID for a class/name/type combination.
+    /// Get the method id of a `java.lang.reflect.Method` or
+    /// `java.lang.reflect.Constructor` object, with `FromReflectedMethod`.
+    ///
+    /// For a static method, the id can be converted with
+    /// `JStaticMethodID::from(id.into_inner())`.
+    pub fn from_reflected_method(&self, method: JObject) -> Result<JMethodID<'a>> {
+        non_null!(method, "from_reflected_method method argument");
+        Ok(jni_non_null_call!(
+            self.internal,
+            FromReflectedMethod,
+            method.into_inner()
+        ))
+    }
+
+    /// Get the `java.lang.reflect.Method` object of a method id of the given
+    /// class, with `ToReflectedMethod`. Use `to_reflected_static_method` for
+    /// static methods.
+    pub fn to_reflected_method<'c, 'm, T>(
+        &self,
+        class: T,
+        method: JMethodID<'m>,
+    ) -> Result<JObject<'a>>
+    where
+        T: Desc<'a, JClass<'c>>,
+    {
+        self.reflected_method_impl(class, method.into_inner(), false)
+    }
+
+    /// Get the `java.lang.reflect.Method` object of a static method id of the
+    /// given class, with `ToReflectedMethod`.
+    pub fn to_reflected_static_method<'c, 'm, T>(
+        &self,
+        class: T,
+        method: JStaticMethodID<'m>,
+    ) -> Result<JObject<'a>>
+    where
+        T: Desc<'a, JClass<'c>>,
+    {
+        self.reflected_method_impl(class, method.into_inner(), true)
+    }
+
+    fn reflected_method_impl<'c, T>(
+        &self,
+        class: T,
+        method: jmethodID,
+        is_static: bool,
+    ) -> Result<JObject<'a>>
+    where
+        T: Desc<'a, JClass<'c>>,
+    {
+        let class = class.lookup(self)?;
+        Ok(jni_non_null_call!(
+            self.internal,
+            ToReflectedMethod,
+            class.into_inner(),
+            method,
+            is_static as jboolean
+        ))
+    }
+
+    /// Get the field id of a `java.lang.reflect.Field` object, with
+    /// `FromReflectedField`.
+    ///
+    /// For a static field, the id can be converted with
+    /// `JStaticFieldID::from(id.into_inner())`.
+    pub fn from_reflected_field(&self, field: JObject) -> Result<JFieldID<'a>> {
+        non_null!(field, "from_reflected_field field argument");
+        Ok(jni_non_null_call!(
+            self.internal,
+            FromReflectedField,
+            field.into_inner()
+        ))
+    }
+
+    /// Get the `java.lang.reflect.Field` object of a field id of the given
+    /// class, with `ToReflectedField`. Use `to_reflected_static_field` for
+    /// static fields.
+    pub fn to_reflected_field<'c, 'f, T>(
+        &self,
+        class: T,
+        field: JFieldID<'f>,
+    ) -> Result<JObject<'a>>
+    where
+        T: Desc<'a, JClass<'c>>,
+    {
+        self.reflected_field_impl(class, field.into_inner(), false)
+    }
+
+    /// Get the `java.lang.reflect.Field` object of a static field id of the
+    /// given class, with `ToReflectedField`.
+    pub fn to_reflected_static_field<'c, 'f, T>(
+        &self,
+        class: T,
+        field: JStaticFieldID<'f>,
+    ) -> Result<JObject<'a>>
+    where
+        T: Desc<'a, JClass<'c>>,
+    {
+        self.reflected_field_impl(class, field.into_inner(), true)
+    }
+
+    fn reflected_field_impl<'c, T>(
+        &self,
+        class: T,
+        field: jfieldID,
+        is_static: bool,
+    ) -> Result<JObject<'a>>
+    where
+        T: Desc<'a, JClass<'c>>,
+    {
+        let class = class.lookup(self)?;
+        Ok(jni_non_null_call!(
+            self.internal,
+            ToReflectedField,
+            class.into_inner(),
+            field,
+            is_static as jboolean
+        ))
+    }
+
+    /// Check that a class declares or inherits an object method with the
+    /// given name and signature, without calling it. The class reference
+    /// created for the lookup is deleted before returning.
+    ///
+    /// This is meant for validating the bindings of a library up front, so
+    /// that a missing method fails at startup rather than at its first call:
+    ///
+    /// ```rust,ignore
+    /// for (class, name, sig) in &[
+    ///     ("java/util/List", "size", "()I"),
+    ///     ("java/lang/Object", "hashCode", "()I"),
+    /// ] {
+    ///     env.validate_method(class, name, sig)?;
+    /// }
+    /// ```
+    ///
+    /// Returns `Err` with the kind `MethodNotFound` if either the class or the
+    /// method can't be found, with the class name prepended to the method
+    /// name. The `NoClassDefFoundError` or `NoSuchMethodError` thrown by the
+    /// lookup is cleared.
+    pub fn validate_method(&self, class: &str, name: &str, sig: &str) -> Result<()> {
+        self.validate_method_impl(class, name, sig, false)
+    }
+
+    /// Check that a class declares a static method with the given name and
+    /// signature, without calling it, like `validate_method`.
+    pub fn validate_static_method(&self, class: &str, name: &str, sig: &str) -> Result<()> {
+        self.validate_method_impl(class, name, sig, true)
+    }
+
+    fn validate_method_impl(
+        &self,
+        class: &str,
+        name: &str,
+        sig: &str,
+        is_static: bool,
+    ) -> Result<()> {
+        TypeSignature::from_str(sig)?;
+
+        let res = self.find_class(class).and_then(|class| {
+            let class = self.auto_local(class);
+            if is_static {
+                self.get_static_method_id(&class, name, sig).map(|_| ())
+            } else {
+                self.get_method_id(&class, name, sig).map(|_| ())
+            }
+        });
+
+        match res {
+            Err(Error::JavaException) => {
+                self.exception_clear()?;
+                Err(Error::MethodNotFound {
+                    name: format!("{}.{}", class, name),
+                    sig: sig.to_owned(),
+                })
+            }
+            res => res,
+        }
+    }
+
+    /// Look up the field ID for a class/name/type combination. The type can
+    /// be given as a descriptor string or as a `JavaType`.
     ///
     /// # Example
     /// ```rust,ignore
     /// let field_id = env.get_field_id("com/my/Class", "intField", "I");
+    /// let field_id = env.get_field_id(
+    ///     "com/my/Class",
+    ///     "intField",
+    ///     JavaType::Primitive(Primitive::Int),
+    /// );
     /// ```
     pub fn get_field_id<'c, T, U, V>(&self, class: T, name: U, sig: V) -> Result<JFieldID<'a>>
     where
@@ -584,11 +1139,17 @@ impl<'a> JNIEnv<'a> {
         }
     }
 
-    /// Look up the static field ID for a class/name/type combination.
+    /// Look up the static field ID for a class/name/type combination. The type can
+    /// be given as a descriptor string or as a `JavaType`.
     ///
     /// # Example
     /// ```rust,ignore
     /// let field_id = env.get_static_field_id("com/my/Class", "intField", "I");
+    /// let field_id = env.get_static_field_id(
+    ///     "com/my/Class",
+    ///     "intField",
+    ///     JavaType::Primitive(Primitive::Int),
+    /// );
     /// ```
     pub fn get_static_field_id<'c, T, U, V>(
         &self,
@@ -663,6 +1224,20 @@ impl<'a> JNIEnv<'a> {
         let args: Vec<jvalue> = args.iter().map(|v| v.to_jni()).collect();
         let jni_args = args.as_ptr();
 
+        let res = self.call_static_method_raw(class, method_id, ret, jni_args);
+        self.check_interrupted(res)
+    }
+
+    /// Calls `CallStatic<Type>MethodA`, apart from the lookups of
+    /// `call_static_method_unchecked` so that only the exceptions of the call
+    /// itself are checked for an `InterruptedException`.
+    fn call_static_method_raw(
+        &self,
+        class: sys::jclass,
+        method_id: jmethodID,
+        ret: JavaType,
+        jni_args: *const jvalue,
+    ) -> Result<JValue<'a>> {
         // TODO clean this up
         Ok(match ret {
             JavaType::Object(_) | JavaType::Array(_) => {
@@ -781,6 +1356,20 @@ impl<'a> JNIEnv<'a> {
         let args: Vec<jvalue> = args.iter().map(|v| v.to_jni()).collect();
         let jni_args = args.as_ptr();
 
+        let res = self.call_method_raw(obj, method_id, ret, jni_args);
+        self.check_interrupted(res)
+    }
+
+    /// Calls `Call<Type>MethodA`, apart from the lookups of
+    /// `call_method_unchecked` so that only the exceptions of the call itself
+    /// are checked for an `InterruptedException`.
+    fn call_method_raw(
+        &self,
+        obj: sys::jobject,
+        method_id: jmethodID,
+        ret: JavaType,
+        jni_args: *const jvalue,
+    ) -> Result<JValue<'a>> {
         // TODO clean this up
         Ok(match ret {
             JavaType::Object(_) | JavaType::Array(_) => {
@@ -832,77 +1421,946 @@ impl<'a> JNIEnv<'a> {
         }) // match parsed.ret
     }
 
-    /// Calls an object method safely. This comes with a number of
-    /// lookups/checks. It
-    ///
-    /// * Parses the type signature to find the number of arguments and return
-    ///   type
-    /// * Looks up the JClass for the given object.
-    /// * Looks up the JMethodID for the class/name/signature combination
-    /// * Ensures that the number of args matches the signature
-    /// * Calls `call_method_unchecked` with the verified safe arguments.
+    /// Call an object method of a specific class in an unsafe manner,
+    /// bypassing the dynamic dispatch. This does nothing to check whether the
+    /// method is valid to call on the object, whether the return type is
+    /// correct, or whether the number of args is valid for the method.
     ///
-    /// Note: this may cause a java exception if the arguments are the wrong
-    /// type, in addition to if the method itself throws.
-    pub fn call_method<O, S, T>(
+    /// Under the hood, this simply calls the `CallNonvirtual<Type>MethodA`
+    /// method with the provided arguments.
+    pub fn call_nonvirtual_method_unchecked<'c, 'm, O, T, U>(
         &self,
         obj: O,
-        name: S,
-        sig: T,
+        class: T,
+        method_id: U,
+        ret: JavaType,
         args: &[JValue],
     ) -> Result<JValue<'a>>
     where
         O: Into<JObject<'a>>,
-        S: Into<JNIString>,
-        T: Into<JNIString> + AsRef<str>,
+        T: Desc<'a, JClass<'c>>,
+        U: Desc<'a, JMethodID<'m>>,
     {
-        let obj = obj.into();
-        non_null!(obj, "call_method obj argument");
+        let class = class.lookup(self)?.into_inner();
+        let method_id = method_id.lookup(self)?.into_inner();
 
-        // parse the signature
-        let parsed = TypeSignature::from_str(sig.as_ref())?;
-        if parsed.args.len() != args.len() {
-            return Err(Error::InvalidArgList(parsed));
-        }
+        let obj = obj.into().into_inner();
 
-        let class = self.auto_local(self.get_object_class(obj)?);
+        let args: Vec<jvalue> = args.iter().map(|v| v.to_jni()).collect();
+        let jni_args = args.as_ptr();
+
+        let res = self.call_nonvirtual_method_raw(obj, class, method_id, ret, jni_args);
+        self.check_interrupted(res)
+    }
+
+    /// Calls `CallNonvirtual<Type>MethodA`, apart from the lookups of
+    /// `call_nonvirtual_method_unchecked` so that only the exceptions of the
+    /// call itself are checked for an `InterruptedException`.
+    fn call_nonvirtual_method_raw(
+        &self,
+        obj: sys::jobject,
+        class: sys::jclass,
+        method_id: jmethodID,
+        ret: JavaType,
+        jni_args: *const jvalue,
+    ) -> Result<JValue<'a>> {
+        Ok(match ret {
+            JavaType::Object(_) | JavaType::Array(_) => {
+                let obj: JObject = jni_non_void_call!(
+                    self.internal,
+                    CallNonvirtualObjectMethodA,
+                    obj,
+                    class,
+                    method_id,
+                    jni_args
+                )
+                .into();
+                obj.into()
+            }
+            JavaType::Method(_) => {
+                return Err(Error::WrongJValueType("Method", "see method signature"))
+            }
+            JavaType::Primitive(p) => match p {
+                Primitive::Boolean => jni_non_void_call!(
+                    self.internal,
+                    CallNonvirtualBooleanMethodA,
+                    obj,
+                    class,
+                    method_id,
+                    jni_args
+                )
+                .into(),
+                Primitive::Char => jni_non_void_call!(
+                    self.internal,
+                    CallNonvirtualCharMethodA,
+                    obj,
+                    class,
+                    method_id,
+                    jni_args
+                )
+                .into(),
+                Primitive::Short => jni_non_void_call!(
+                    self.internal,
+                    CallNonvirtualShortMethodA,
+                    obj,
+                    class,
+                    method_id,
+                    jni_args
+                )
+                .into(),
+                Primitive::Int => jni_non_void_call!(
+                    self.internal,
+                    CallNonvirtualIntMethodA,
+                    obj,
+                    class,
+                    method_id,
+                    jni_args
+                )
+                .into(),
+                Primitive::Long => jni_non_void_call!(
+                    self.internal,
+                    CallNonvirtualLongMethodA,
+                    obj,
+                    class,
+                    method_id,
+                    jni_args
+                )
+                .into(),
+                Primitive::Float => jni_non_void_call!(
+                    self.internal,
+                    CallNonvirtualFloatMethodA,
+                    obj,
+                    class,
+                    method_id,
+                    jni_args
+                )
+                .into(),
+                Primitive::Double => jni_non_void_call!(
+                    self.internal,
+                    CallNonvirtualDoubleMethodA,
+                    obj,
+                    class,
+                    method_id,
+                    jni_args
+                )
+                .into(),
+                Primitive::Byte => jni_non_void_call!(
+                    self.internal,
+                    CallNonvirtualByteMethodA,
+                    obj,
+                    class,
+                    method_id,
+                    jni_args
+                )
+                .into(),
+                Primitive::Void => {
+                    jni_void_call!(
+                        self.internal,
+                        CallNonvirtualVoidMethodA,
+                        obj,
+                        class,
+                        method_id,
+                        jni_args
+                    );
+                    return Ok(JValue::Void);
+                }
+            },
+        })
+    }
+
+    /// Calls an object method safely. This comes with a number of
+    /// lookups/checks. It
+    ///
+    /// * Parses the type signature to find the number of arguments and return
+    ///   type
+    /// * Looks up the JClass for the given object.
+    /// * Looks up the JMethodID for the class/name/signature combination
+    /// * Ensures that the number of args matches the signature
+    /// * Calls `call_method_unchecked` with the verified safe arguments.
+    ///
+    /// Note: this may cause a java exception if the arguments are the wrong
+    /// type, in addition to if the method itself throws.
+    pub fn call_method<O, S, T>(
+        &self,
+        obj: O,
+        name: S,
+        sig: T,
+        args: &[JValue],
+    ) -> Result<JValue<'a>>
+    where
+        O: Into<JObject<'a>>,
+        S: Into<JNIString>,
+        T: Into<JNIString> + AsRef<str>,
+    {
+        let obj = obj.into();
+        non_null!(obj, "call_method obj argument");
+
+        // parse the signature
+        let parsed = TypeSignature::from_str(sig.as_ref())?;
+        if parsed.args.len() != args.len() {
+            return Err(Error::InvalidArgList(parsed));
+        }
+
+        let class = self.auto_local(self.get_object_class(obj)?);
+
+        #[cfg(feature = "metrics")]
+        let (name, _timer) = {
+            let name = name.into();
+            let timer = CallTimer::start(self.metrics_class_name(class.as_obj())?, &name);
+            (name, timer)
+        };
 
         self.call_method_unchecked(obj, (&class, name, sig), parsed.ret, args)
     }
 
-    /// Calls a static method safely. This comes with a number of
-    /// lookups/checks. It
-    ///
-    /// * Parses the type signature to find the number of arguments and return
-    ///   type
-    /// * Looks up the JMethodID for the class/name/signature combination
-    /// * Ensures that the number of args matches the signature
-    /// * Calls `call_method_unchecked` with the verified safe arguments.
-    ///
-    /// Note: this may cause a java exception if the arguments are the wrong
-    /// type, in addition to if the method itself throws.
-    pub fn call_static_method<'c, T, U, V>(
+    /// Calls an object method that returns `void`. This is the same as
+    /// `call_method`, but checks up front that the signature has a `void`
+    /// return type, and so there's no result to unwrap.
+    ///
+    /// Returns `Err` with the kind `NonVoidMethod` if the method returns a
+    /// value, without calling it.
+    pub fn call_void<O, S, T>(&self, obj: O, name: S, sig: T, args: &[JValue]) -> Result<()>
+    where
+        O: Into<JObject<'a>>,
+        S: Into<JNIString>,
+        T: Into<JNIString> + AsRef<str>,
+    {
+        ensure_void_return(sig.as_ref())?;
+        self.call_method(obj, name, sig, args)?.v()
+    }
+
+    /// Calls an object method that returns `boolean`, like `String#isEmpty`,
+    /// and converts the result to a rust `bool`. This is the same as
+    /// `call_method`, but checks up front that the signature has a `boolean`
+    /// return type. Any non-zero `jboolean` returned by native code is
+    /// treated as `true`.
+    ///
+    /// Returns `Err` with the kind `WrongJValueType` if the method returns
+    /// another type, without calling it.
+    pub fn call_bool_method<O, S, T>(
+        &self,
+        obj: O,
+        name: S,
+        sig: T,
+        args: &[JValue],
+    ) -> Result<bool>
+    where
+        O: Into<JObject<'a>>,
+        S: Into<JNIString>,
+        T: Into<JNIString> + AsRef<str>,
+    {
+        ensure_bool_return(sig.as_ref())?;
+        bool_result(self.call_method(obj, name, sig, args)?)
+    }
+
+    /// Calls the implementation of an object method declared in the given
+    /// class, bypassing the dynamic dispatch, like `super.method()` in java.
+    /// This does the same checks as `call_method`, and looks up the method in
+    /// `class` instead of the object class.
+    ///
+    /// The class may also be an interface, in which case its default method
+    /// is invoked, even if the object class overrides it.
+    ///
+    /// Returns `Err` with the kind `WrongObjectClass` if `obj` is not an
+    /// instance of `class`.
+    pub fn call_nonvirtual_method<'c, O, T, S, U>(
+        &self,
+        obj: O,
+        class: T,
+        name: S,
+        sig: U,
+        args: &[JValue],
+    ) -> Result<JValue<'a>>
+    where
+        O: Into<JObject<'a>>,
+        T: Desc<'a, JClass<'c>>,
+        S: Into<JNIString>,
+        U: Into<JNIString> + AsRef<str>,
+    {
+        let obj = obj.into();
+        non_null!(obj, "call_nonvirtual_method obj argument");
+
+        let parsed = TypeSignature::from_str(sig.as_ref())?;
+        if parsed.args.len() != args.len() {
+            return Err(Error::InvalidArgList(parsed));
+        }
+
+        let class = class.lookup(self)?;
+        if !self.is_instance_of(obj, class)? {
+            return Err(Error::WrongObjectClass(
+                "call_nonvirtual_method obj argument",
+            ));
+        }
+
+        self.call_nonvirtual_method_unchecked(obj, class, (class, name, sig), parsed.ret, args)
+    }
+
+    /// Calls an object method that returns an exception as a value, rather
+    /// than throwing it, like `CompletableFuture#exceptionNow`. This does the
+    /// same checks as `call_method`.
+    ///
+    /// Returns `Ok(None)` if the method returns `null`, and `Err` with the
+    /// kind `WrongObjectClass` if it returns an object which is not a
+    /// `Throwable`. An exception thrown by the method is still reported as a
+    /// `JavaException` error.
+    pub fn call_method_throwable<O, S, T>(
+        &self,
+        obj: O,
+        name: S,
+        sig: T,
+        args: &[JValue],
+    ) -> Result<Option<JThrowable<'a>>>
+    where
+        O: Into<JObject<'a>>,
+        S: Into<JNIString>,
+        T: Into<JNIString> + AsRef<str>,
+    {
+        let result = self.call_method(obj, name, sig, args)?.l()?;
+        if result.is_null() {
+            return Ok(None);
+        }
+        if !self.is_instance_of(result, "java/lang/Throwable")? {
+            return Err(Error::WrongObjectClass("call_method_throwable result"));
+        }
+        Ok(Some(result.into()))
+    }
+
+    /// Calls an object method safely, like `call_method`, but clears the
+    /// exception if the method throws one.
+    ///
+    /// The thrown exception is returned in an `Err` with the kind
+    /// `JavaExceptionCleared`, so there is no pending exception left behind
+    /// that would make the subsequent JNI calls fail.
+    pub fn call_method_clearing<O, S, T>(
+        &self,
+        obj: O,
+        name: S,
+        sig: T,
+        args: &[JValue],
+    ) -> Result<JValue<'a>>
+    where
+        O: Into<JObject<'a>>,
+        S: Into<JNIString>,
+        T: Into<JNIString> + AsRef<str>,
+    {
+        let res = self.call_method(obj, name, sig, args);
+        self.capture_exception(res)
+    }
+
+    /// Turns a `JavaException` or `Interrupted` error into a
+    /// `JavaExceptionCleared` one, which captures the pending exception
+    /// together with its class name and message, and clears it. Other results
+    /// are returned unchanged.
+    ///
+    /// This can be used with any method of `JNIEnv` to handle exceptions as
+    /// Rust errors, instead of through `exception_occurred` and
+    /// `exception_clear`:
+    ///
+    /// ```rust,ignore
+    /// let res = env.capture_exception(env.find_class("com/example/Missing"));
+    /// if let Err(e) = res {
+    ///     // The NoClassDefFoundError is no longer pending
+    ///     let exception = e.java_exception();
+    /// }
+    /// ```
+    pub fn capture_exception<R>(&self, res: Result<R>) -> Result<R> {
+        match res {
+            Err(e @ Error::JavaException) | Err(e @ Error::Interrupted) => {
+                Err(self.take_pending_exception()?.unwrap_or(e))
+            }
+            res => res,
+        }
+    }
+
+    /// Runs a closure with the pending exception, if any, stashed away, and
+    /// throws it again afterwards. Most JNI functions can't be called while an
+    /// exception is pending, so this is the way to run cleanup code that calls
+    /// into java on an error path:
+    ///
+    /// ```rust,ignore
+    /// env.with_pending_exception_preserved(|| {
+    ///     let _ = env.call_method(stream, "close", "()V", &[]);
+    /// })?;
+    /// ```
+    ///
+    /// If the closure leaves an exception of its own pending, it is added to
+    /// the stashed exception as suppressed, like java's try-with-resources
+    /// does for exceptions thrown by `close`. If it can't be added, it is
+    /// dropped, so the stashed exception is always the one thrown again.
+    /// Without a pending exception, the closure is just called.
+    pub fn with_pending_exception_preserved<F, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce() -> R,
+    {
+        let pending = match self.clear_pending_exception()? {
+            Some(pending) => pending,
+            None => return Ok(f()),
+        };
+
+        let res = f();
+
+        if let Some(thrown) = self.clear_pending_exception()? {
+            let suppressed = self.call_method(
+                pending.as_obj(),
+                "addSuppressed",
+                "(Ljava/lang/Throwable;)V",
+                &[thrown.as_obj().into()],
+            );
+            if suppressed.is_err() {
+                // E.g. the closure threw the stashed exception again, which
+                // can't suppress itself; the stashed one is thrown anyway.
+                self.exception_clear()?;
+            }
+        }
+        self.throw(JThrowable::from(pending.as_obj()))?;
+        Ok(res)
+    }
+
+    /// Runs a closure, then clears the exception it left pending, if any, and
+    /// returns it as a `JavaExceptionCleared` error, like `capture_exception`
+    /// does, along with the closure's result:
+    ///
+    /// ```rust,ignore
+    /// let (res, exception) = env.with_cleared_exception(|| {
+    ///     env.call_method(obj, "close", "()V", &[])
+    /// })?;
+    /// if let Some(exception) = exception {
+    ///     // Inspect the exception, it's not pending anymore
+    /// }
+    /// ```
+    ///
+    /// An error is only returned if the exception can't be captured, in which
+    /// case it's left pending.
+    pub fn with_cleared_exception<F, R>(&self, f: F) -> Result<(R, Option<Error>)>
+    where
+        F: FnOnce() -> R,
+    {
+        let res = f();
+        Ok((res, self.take_pending_exception()?))
+    }
+
+    /// Turns a `JavaException` error into an `Interrupted` one if the pending
+    /// exception is an `InterruptedException`. The exception is left pending
+    /// in both cases.
+    ///
+    /// This is only done once per call, by the `*_unchecked` calls, as it
+    /// takes a few JNI calls of its own, although that's little next to
+    /// throwing the exception.
+    fn check_interrupted<T>(&self, res: Result<T>) -> Result<T> {
+        if !matches!(res, Err(Error::JavaException)) {
+            return res;
+        }
+
+        // `IsInstanceOf` can't be called with a pending exception, so it has to
+        // be cleared for the type check and then thrown again.
+        let throwable = self.auto_local(self.exception_occurred()?);
+        self.exception_clear()?;
+        let interrupted = self
+            .interrupted_exception_class()
+            .and_then(|class| self.is_instance_of(throwable.as_obj(), &class));
+        if interrupted.is_err() {
+            // Throw the original exception rather than the one of the check
+            self.exception_clear()?;
+        }
+        self.throw(JThrowable::from(throwable.as_obj().into_inner()))?;
+
+        if interrupted? {
+            Err(Error::Interrupted)
+        } else {
+            Err(Error::JavaException)
+        }
+    }
+
+    fn interrupted_exception_class(&self) -> Result<GlobalRef> {
+        let mut class = INTERRUPTED_EXCEPTION_CLASS.lock().unwrap();
+        if let Some(class) = class.as_ref() {
+            return Ok(class.clone());
+        }
+
+        let local = self.auto_local(self.find_class("java/lang/InterruptedException")?);
+        let global = self.new_global_ref(&local)?;
+        *class = Some(global.clone());
+        Ok(global)
+    }
+
+    /// Clears the pending exception, if any, and returns it.
+    pub(crate) fn clear_pending_exception(&self) -> Result<Option<AutoLocal<'a, '_>>> {
+        let throwable = self.exception_occurred()?;
+        if throwable.is_null() {
+            return Ok(None);
+        }
+        let throwable = self.auto_local(throwable);
+        self.exception_clear()?;
+        Ok(Some(throwable))
+    }
+
+    /// Clears the pending exception, if any, and returns it as a
+    /// `JavaExceptionCleared` error. If the exception can't be captured, it is
+    /// thrown again and the error of the capture is returned.
+    fn take_pending_exception(&self) -> Result<Option<Error>> {
+        let throwable = match self.clear_pending_exception()? {
+            Some(throwable) => throwable,
+            None => return Ok(None),
+        };
+
+        let captured = (|| {
+            let class = self.get_object_class_name(throwable.as_obj())?;
+            let message = match JThrowable::from(throwable.as_obj()).get_message(self)? {
+                Some(message) => {
+                    let message = self.auto_local(message);
+                    Some(self.get_string(message.as_obj().into())?.into())
+                }
+                None => None,
+            };
+            let exception = self.new_global_ref(&throwable)?;
+
+            Ok(Error::JavaExceptionCleared {
+                exception,
+                class,
+                message,
+            })
+        })();
+
+        match captured {
+            Ok(error) => Ok(Some(error)),
+            Err(e) => {
+                // Throw the original exception rather than the one of the capture
+                self.exception_clear()?;
+                self.throw(JThrowable::from(throwable.as_obj()))?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Calls an object method that returns a direct `java.nio.ByteBuffer` and
+    /// gives access to the memory of the returned buffer. This does the same
+    /// checks as `call_method`.
+    ///
+    /// The returned buffer is pinned with a global reference for as long as the
+    /// `DirectBufferGuard` lives, so it can't get collected while its contents
+    /// are being read.
+    ///
+    /// Returns `Err` with the kind `NullPtr` if the method returns `null` or
+    /// a buffer that is not direct.
+    pub fn call_method_direct_buffer<O, S, T>(
+        &self,
+        obj: O,
+        name: S,
+        sig: T,
+        args: &[JValue],
+    ) -> Result<DirectBufferGuard>
+    where
+        O: Into<JObject<'a>>,
+        S: Into<JNIString>,
+        T: Into<JNIString> + AsRef<str>,
+    {
+        let buf = self.call_method(obj, name, sig, args)?.l()?;
+        non_null!(buf, "call_method_direct_buffer return value");
+        let buf = self.auto_local(buf);
+
+        let global = self.new_global_ref(&buf)?;
+        let data = self.get_direct_buffer_address(JByteBuffer::from(global.as_obj()))?;
+        Ok(DirectBufferGuard::new(
+            global,
+            data.as_mut_ptr(),
+            data.len(),
+        ))
+    }
+
+    /// Calls an object method that uses single-element arrays as out-params,
+    /// and reads the values the method stored in them.
+    ///
+    /// `out_spec` lists the indices of the out-params in the method signature.
+    /// Each of them must have a (single-dimensional or nested) array type; a
+    /// one-element array of that type is allocated and passed in their place.
+    /// The remaining parameters are taken from `in_args`, in order. This does
+    /// the same checks as `call_method`.
+    ///
+    /// Returns the method result along with the first element of each out
+    /// array, in the order of `out_spec`. Object out values are new local
+    /// references.
+    ///
+    /// This only covers the common out-param idiom of a fresh one-element
+    /// array that the callee writes to. Methods that read the array contents,
+    /// require a specific length, or keep a reference to the array, need the
+    /// arrays to be managed manually.
+    ///
+    /// Returns `Err` with the kind `InvalidOutParam` if an index in `out_spec`
+    /// is repeated or doesn't refer to an array parameter, and with the kind
+    /// `InvalidArgList` if the number of arguments doesn't match the signature.
+    pub fn call_with_out_array<O, S, T>(
+        &self,
+        obj: O,
+        name: S,
+        sig: T,
+        in_args: &[JValue],
+        out_spec: &[usize],
+    ) -> Result<(JValue<'a>, Vec<JValue<'a>>)>
+    where
+        O: Into<JObject<'a>>,
+        S: Into<JNIString>,
+        T: Into<JNIString> + AsRef<str>,
+    {
+        let parsed = TypeSignature::from_str(sig.as_ref())?;
+        if in_args.len() + out_spec.len() != parsed.args.len() {
+            return Err(Error::InvalidArgList(parsed));
+        }
+
+        let mut out_arrays = Vec::with_capacity(out_spec.len());
+        for (i, &index) in out_spec.iter().enumerate() {
+            if out_spec[..i].contains(&index) {
+                return Err(Error::InvalidOutParam(index));
+            }
+            let elem = match parsed.args.get(index) {
+                Some(JavaType::Array(elem)) => elem,
+                _ => return Err(Error::InvalidOutParam(index)),
+            };
+            let array = self.auto_local(self.new_out_array(index, elem)?);
+            out_arrays.push((index, elem, array));
+        }
+
+        let mut in_args = in_args.iter();
+        let args: Vec<JValue> = (0..parsed.args.len())
+            .map(
+                |i| match out_arrays.iter().find(|(index, ..)| *index == i) {
+                    Some((_, _, array)) => JValue::Object(array.as_obj()),
+                    // The number of in args was checked above
+                    None => *in_args.next().unwrap(),
+                },
+            )
+            .collect();
+
+        let result = self.call_method(obj, name, sig, &args)?;
+        let out = out_arrays
+            .iter()
+            .map(|(_, elem, array)| self.get_out_value(array.as_obj().into_inner(), elem))
+            .collect::<Result<_>>()?;
+        Ok((result, out))
+    }
+
+    /// Allocates a one-element array to be used as an out-param.
+    fn new_out_array(&self, index: usize, elem: &JavaType) -> Result<JObject<'a>> {
+        use JavaType::Primitive as JP;
+
+        let array = match elem {
+            JP(Primitive::Boolean) => self.new_boolean_array(1)?,
+            JP(Primitive::Byte) => self.new_byte_array(1)?,
+            JP(Primitive::Char) => self.new_char_array(1)?,
+            JP(Primitive::Short) => self.new_short_array(1)?,
+            JP(Primitive::Int) => self.new_int_array(1)?,
+            JP(Primitive::Long) => self.new_long_array(1)?,
+            JP(Primitive::Float) => self.new_float_array(1)?,
+            JP(Primitive::Double) => self.new_double_array(1)?,
+            JavaType::Object(name) => self.new_object_array(1, name.as_str(), JObject::null())?,
+            JavaType::Array(_) => self.new_object_array(1, elem.to_string(), JObject::null())?,
+            JP(Primitive::Void) | JavaType::Method(_) => return Err(Error::InvalidOutParam(index)),
+        };
+        Ok(array.into())
+    }
+
+    /// Reads the first element of an out-param array.
+    fn get_out_value(&self, array: jarray, elem: &JavaType) -> Result<JValue<'a>> {
+        use JavaType::Primitive as JP;
+
+        let value = match elem {
+            JP(Primitive::Boolean) => {
+                let mut buf = [0; 1];
+                self.get_boolean_array_region(array, 0, &mut buf)?;
+                JValue::Bool(buf[0])
+            }
+            JP(Primitive::Byte) => {
+                let mut buf = [0; 1];
+                self.get_byte_array_region(array, 0, &mut buf)?;
+                JValue::Byte(buf[0])
+            }
+            JP(Primitive::Char) => {
+                let mut buf = [0; 1];
+                self.get_char_array_region(array, 0, &mut buf)?;
+                JValue::Char(buf[0])
+            }
+            JP(Primitive::Short) => {
+                let mut buf = [0; 1];
+                self.get_short_array_region(array, 0, &mut buf)?;
+                JValue::Short(buf[0])
+            }
+            JP(Primitive::Int) => {
+                let mut buf = [0; 1];
+                self.get_int_array_region(array, 0, &mut buf)?;
+                JValue::Int(buf[0])
+            }
+            JP(Primitive::Long) => {
+                let mut buf = [0; 1];
+                self.get_long_array_region(array, 0, &mut buf)?;
+                JValue::Long(buf[0])
+            }
+            JP(Primitive::Float) => {
+                let mut buf = [0.0; 1];
+                self.get_float_array_region(array, 0, &mut buf)?;
+                JValue::Float(buf[0])
+            }
+            JP(Primitive::Double) => {
+                let mut buf = [0.0; 1];
+                self.get_double_array_region(array, 0, &mut buf)?;
+                JValue::Double(buf[0])
+            }
+            _ => JValue::Object(self.get_object_array_element(array, 0)?),
+        };
+        Ok(value)
+    }
+
+    /// Calls a static method safely. This comes with a number of
+    /// lookups/checks. It
+    ///
+    /// * Parses the type signature to find the number of arguments and return
+    ///   type
+    /// * Looks up the JMethodID for the class/name/signature combination
+    /// * Ensures that the number of args matches the signature
+    /// * Calls `call_method_unchecked` with the verified safe arguments.
+    ///
+    /// Note: this may cause a java exception if the arguments are the wrong
+    /// type, in addition to if the method itself throws.
+    pub fn call_static_method<'c, T, U, V>(
+        &self,
+        class: T,
+        name: U,
+        sig: V,
+        args: &[JValue],
+    ) -> Result<JValue<'a>>
+    where
+        T: Desc<'a, JClass<'c>>,
+        U: Into<JNIString>,
+        V: Into<JNIString> + AsRef<str>,
+    {
+        let parsed = TypeSignature::from_str(&sig)?;
+        if parsed.args.len() != args.len() {
+            return Err(Error::InvalidArgList(parsed));
+        }
+
+        // go ahead and look up the class since it's already Copy,
+        // and we'll need that for the next call.
+        let class = class.lookup(self)?;
+
+        #[cfg(feature = "metrics")]
+        let (name, _timer) = {
+            let name = name.into();
+            let timer = CallTimer::start(self.metrics_class_name(class.into())?, &name);
+            (name, timer)
+        };
+
+        self.call_static_method_unchecked(class, (class, name, sig), parsed.ret, args)
+    }
+
+    /// Calls a static method that returns `void`, like `Thread.sleep`. This is
+    /// the same as `call_static_method`, but checks up front that the
+    /// signature has a `void` return type, and so there's no result to unwrap.
+    ///
+    /// Returns `Err` with the kind `NonVoidMethod` if the method returns a
+    /// value, without calling it.
+    pub fn call_static_void<'c, T, U, V>(
+        &self,
+        class: T,
+        name: U,
+        sig: V,
+        args: &[JValue],
+    ) -> Result<()>
+    where
+        T: Desc<'a, JClass<'c>>,
+        U: Into<JNIString>,
+        V: Into<JNIString> + AsRef<str>,
+    {
+        ensure_void_return(sig.as_ref())?;
+        self.call_static_method(class, name, sig, args)?.v()
+    }
+
+    /// Calls a static method that returns `boolean`, like `call_bool_method`.
+    pub fn call_static_bool_method<'c, T, U, V>(
+        &self,
+        class: T,
+        name: U,
+        sig: V,
+        args: &[JValue],
+    ) -> Result<bool>
+    where
+        T: Desc<'a, JClass<'c>>,
+        U: Into<JNIString>,
+        V: Into<JNIString> + AsRef<str>,
+    {
+        ensure_bool_return(sig.as_ref())?;
+        bool_result(self.call_static_method(class, name, sig, args)?)
+    }
+
+    /// Resolve a `MethodHandle` for a public object method, which can then be
+    /// invoked with the object as the first argument. See `MethodHandle` for
+    /// the performance characteristics.
+    ///
+    /// Returns `Err` with a pending exception if the method can't be found or
+    /// accessed.
+    pub fn bind_method_handle<'c, T>(&self, class: T, name: &str, sig: &str) -> Result<MethodHandle>
+    where
+        T: Desc<'a, JClass<'c>>,
+    {
+        let class = class.lookup(self)?;
+        MethodHandle::bind(self, class, name, sig, false)
+    }
+
+    /// Resolve a `MethodHandle` for a public static method, like
+    /// `bind_method_handle`.
+    pub fn bind_static_method_handle<'c, T>(
+        &self,
+        class: T,
+        name: &str,
+        sig: &str,
+    ) -> Result<MethodHandle>
+    where
+        T: Desc<'a, JClass<'c>>,
+    {
+        let class = class.lookup(self)?;
+        MethodHandle::bind(self, class, name, sig, true)
+    }
+
+    /// Look up the id of an object method through a `MethodIdCache`, which
+    /// only does the lookup on the first call for a given class, name and
+    /// signature.
+    pub fn get_cached_method_id(
+        &self,
+        cache: &MethodIdCache,
+        class: &str,
+        name: &str,
+        sig: &str,
+    ) -> Result<JMethodID<'a>> {
+        let (_, id) = self.lookup_cached_method(cache, class, name, sig, false)?;
+        Ok(id.into())
+    }
+
+    /// Look up the id of a static method through a `MethodIdCache`, like
+    /// `get_cached_method_id`.
+    pub fn get_cached_static_method_id(
+        &self,
+        cache: &MethodIdCache,
+        class: &str,
+        name: &str,
+        sig: &str,
+    ) -> Result<JStaticMethodID<'a>> {
+        let (_, id) = self.lookup_cached_method(cache, class, name, sig, true)?;
+        Ok(id.into())
+    }
+
+    pub(crate) fn lookup_cached_method(
+        &self,
+        cache: &MethodIdCache,
+        class: &str,
+        name: &str,
+        sig: &str,
+        is_static: bool,
+    ) -> Result<(GlobalRef, jmethodID)> {
+        cache.get_or_lookup(class, name, sig, is_static, || {
+            let local = self.auto_local(self.find_class(class)?);
+            let id = if is_static {
+                self.get_static_method_id(&local, name, sig)?.into_inner()
+            } else {
+                self.get_method_id(&local, name, sig)?.into_inner()
+            };
+            Ok((self.new_global_ref(&local)?, id))
+        })
+    }
+
+    /// Calls an object method declared in the given class or interface, like
+    /// `call_method`, but looks up the method id through a `MethodIdCache`.
+    /// The call is still dispatched to the implementation in the runtime class
+    /// of the object.
+    ///
+    /// Returns `Err` with the kind `WrongObjectClass` if `obj` is not an
+    /// instance of `class`.
+    pub fn call_method_cached<O>(
+        &self,
+        cache: &MethodIdCache,
+        obj: O,
+        class: &str,
+        name: &str,
+        sig: &str,
+        args: &[JValue],
+    ) -> Result<JValue<'a>>
+    where
+        O: Into<JObject<'a>>,
+    {
+        let obj = obj.into();
+        non_null!(obj, "call_method_cached obj argument");
+
+        let parsed = TypeSignature::from_str(sig)?;
+        if parsed.args.len() != args.len() {
+            return Err(Error::InvalidArgList(parsed));
+        }
+
+        let (class, id) = self.lookup_cached_method(cache, class, name, sig, false)?;
+        if !self.is_instance_of(obj, &class)? {
+            return Err(Error::WrongObjectClass("call_method_cached obj argument"));
+        }
+
+        self.call_method_unchecked(obj, JMethodID::from(id), parsed.ret, args)
+    }
+
+    /// Calls `hashCode()` on an object, for using java objects as keys of
+    /// rust maps. The method id is only looked up once.
+    ///
+    /// Returns `Err` with the kind `NullPtr` if `obj` is `null`.
+    pub fn object_hash_code<O>(&self, obj: O) -> Result<jint>
+    where
+        O: Into<JObject<'a>>,
+    {
+        let obj = obj.into();
+        non_null!(obj, "object_hash_code obj argument");
+        self.call_method_cached(
+            &OBJECT_METHODS,
+            obj,
+            "java/lang/Object",
+            "hashCode",
+            "()I",
+            &[],
+        )?
+        .i()
+    }
+
+    /// Calls `equals(Object)` on an object with another one, which may be
+    /// `null`. The method id is only looked up once.
+    ///
+    /// Returns `Err` with the kind `NullPtr` if `obj` is `null`.
+    pub fn objects_equal<O, T>(&self, obj: O, other: T) -> Result<bool>
+    where
+        O: Into<JObject<'a>>,
+        T: Into<JObject<'a>>,
+    {
+        let obj = obj.into();
+        non_null!(obj, "objects_equal obj argument");
+        self.call_method_cached(
+            &OBJECT_METHODS,
+            obj,
+            "java/lang/Object",
+            "equals",
+            "(Ljava/lang/Object;)Z",
+            &[other.into().into()],
+        )?
+        .z()
+    }
+
+    /// Calls a static method like `call_static_method`, but looks up the
+    /// class and the method id through a `MethodIdCache`.
+    pub fn call_static_method_cached(
         &self,
-        class: T,
-        name: U,
-        sig: V,
+        cache: &MethodIdCache,
+        class: &str,
+        name: &str,
+        sig: &str,
         args: &[JValue],
-    ) -> Result<JValue<'a>>
-    where
-        T: Desc<'a, JClass<'c>>,
-        U: Into<JNIString>,
-        V: Into<JNIString> + AsRef<str>,
-    {
-        let parsed = TypeSignature::from_str(&sig)?;
+    ) -> Result<JValue<'a>> {
+        let parsed = TypeSignature::from_str(sig)?;
         if parsed.args.len() != args.len() {
             return Err(Error::InvalidArgList(parsed));
         }
 
-        // go ahead and look up the class since it's already Copy,
-        // and we'll need that for the next call.
-        let class = class.lookup(self)?;
-
-        self.call_static_method_unchecked(class, (class, name, sig), parsed.ret, args)
+        let (class, id) = self.lookup_cached_method(cache, class, name, sig, true)?;
+        // The class is pinned by the global reference for the whole call
+        let class = JClass::from(class.as_obj().into_inner());
+        self.call_static_method_unchecked(class, JStaticMethodID::from(id), parsed.ret, args)
     }
 
     /// Create a new object using a constructor. This is done safely using
@@ -936,6 +2394,182 @@ impl<'a> JNIEnv<'a> {
         self.new_object_unchecked(class, method_id, ctor_args)
     }
 
+    /// Resolve a constructor, which can then be used to create objects from
+    /// any thread without looking it up again. This does the same checks on
+    /// the signature as `new_object`.
+    pub fn get_constructor<'c, T, U>(&self, class: T, ctor_sig: U) -> Result<Constructor>
+    where
+        T: Desc<'a, JClass<'c>>,
+        U: Into<JNIString> + AsRef<str>,
+    {
+        let parsed = TypeSignature::from_str(&ctor_sig)?;
+        if parsed.ret != JavaType::Primitive(Primitive::Void) {
+            return Err(Error::InvalidCtorReturn);
+        }
+
+        let class = class.lookup(self)?;
+        let method_id: JMethodID = (class, ctor_sig).lookup(self)?;
+        let class = self.new_global_ref(class)?;
+        Ok(Constructor::new(class, method_id.into_inner(), parsed))
+    }
+
+    /// Create a new object using a constructor, like `new_object`, and return
+    /// a weak global ref to it instead of a local ref.
+    ///
+    /// As the weak ref doesn't keep the object alive, the object may be
+    /// collected right away if the constructor didn't store a strong reference
+    /// to it anywhere, in which case `WeakRef::upgrade_local` returns `None`.
+    pub fn new_object_weak<'c, T, U>(
+        &self,
+        class: T,
+        ctor_sig: U,
+        ctor_args: &[JValue],
+    ) -> Result<WeakRef>
+    where
+        T: Desc<'a, JClass<'c>>,
+        U: Into<JNIString> + AsRef<str>,
+    {
+        let obj = self.auto_local(self.new_object(class, ctor_sig, ctor_args)?);
+        self.new_weak_ref(obj.as_obj())
+    }
+
+    /// Create a new object using a constructor taking a single `Object[]`
+    /// (or `Object...`) parameter. The arguments are packed into a new
+    /// `Object[]`, with the primitive ones boxed with `box_value`.
+    pub fn new_object_boxed<'c, T>(&self, class: T, args: &[JValue]) -> Result<JObject<'a>>
+    where
+        T: Desc<'a, JClass<'c>>,
+    {
+        let array =
+            self.new_object_array(args.len() as jsize, "java/lang/Object", JObject::null())?;
+        let array = self.auto_local(JObject::from(array));
+        for (i, arg) in args.iter().enumerate() {
+            let element = self.auto_local(self.box_value(*arg)?);
+            self.set_object_array_element(
+                array.as_obj().into_inner(),
+                i as jsize,
+                element.as_obj(),
+            )?;
+        }
+
+        self.new_object(class, "([Ljava/lang/Object;)V", &[array.as_obj().into()])
+    }
+
+    /// Converts a value to an object, boxing primitives into their wrapper
+    /// classes with `valueOf` (e.g. `Int` into a `java.lang.Integer`). Objects
+    /// are returned as a new local reference.
+    pub fn box_value(&self, val: JValue) -> Result<JObject<'a>> {
+        let (class, sig) = match val {
+            JValue::Object(obj) => return self.new_local_ref::<JObject>(obj.into_inner().into()),
+            JValue::Bool(_) => ("java/lang/Boolean", "(Z)Ljava/lang/Boolean;"),
+            JValue::Byte(_) => ("java/lang/Byte", "(B)Ljava/lang/Byte;"),
+            JValue::Char(_) => ("java/lang/Character", "(C)Ljava/lang/Character;"),
+            JValue::Short(_) => ("java/lang/Short", "(S)Ljava/lang/Short;"),
+            JValue::Int(_) => ("java/lang/Integer", "(I)Ljava/lang/Integer;"),
+            JValue::Long(_) => ("java/lang/Long", "(J)Ljava/lang/Long;"),
+            JValue::Float(_) => ("java/lang/Float", "(F)Ljava/lang/Float;"),
+            JValue::Double(_) => ("java/lang/Double", "(D)Ljava/lang/Double;"),
+            JValue::Void => return Err(Error::WrongJValueType("void", "see box_value")),
+        };
+
+        let class = self.auto_local(self.find_class(class)?);
+        self.call_static_method(&class, "valueOf", sig, &[val])?.l()
+    }
+
+    /// Converts a Scala `scala.util.Try` into a rust `Result`: a `Success`
+    /// gives `Ok` with its value, and a `Failure` gives `Err` with its
+    /// exception.
+    ///
+    /// Returns `Err` with the kind `WrongObjectClass` if `obj` is neither a
+    /// `Success` nor a `Failure`.
+    pub fn scala_try<O>(&self, obj: O) -> Result<::std::result::Result<JObject<'a>, JThrowable<'a>>>
+    where
+        O: Into<JObject<'a>>,
+    {
+        let obj = obj.into();
+        non_null!(obj, "scala_try obj argument");
+        match self.get_object_class_name(obj)?.as_str() {
+            "scala.util.Success" => {
+                let value = self.call_method(obj, "value", "()Ljava/lang/Object;", &[])?;
+                Ok(Ok(value.l()?))
+            }
+            "scala.util.Failure" => {
+                let exception =
+                    self.call_method(obj, "exception", "()Ljava/lang/Throwable;", &[])?;
+                Ok(Err(exception.l()?.into()))
+            }
+            _ => Err(Error::WrongObjectClass("scala_try")),
+        }
+    }
+
+    /// Converts a Scala `scala.util.Either` into a rust `Result`. Following the
+    /// Scala convention of `Right` holding the successful value, a `Right`
+    /// gives `Ok` and a `Left` gives `Err`, both with the held value.
+    ///
+    /// Returns `Err` with the kind `WrongObjectClass` if `obj` is neither a
+    /// `Left` nor a `Right`.
+    pub fn scala_either<O>(&self, obj: O) -> Result<::std::result::Result<JObject<'a>, JObject<'a>>>
+    where
+        O: Into<JObject<'a>>,
+    {
+        let obj = obj.into();
+        non_null!(obj, "scala_either obj argument");
+        let right = match self.get_object_class_name(obj)?.as_str() {
+            "scala.util.Right" => true,
+            "scala.util.Left" => false,
+            _ => return Err(Error::WrongObjectClass("scala_either")),
+        };
+
+        let value = self
+            .call_method(obj, "value", "()Ljava/lang/Object;", &[])?
+            .l()?;
+        Ok(if right { Ok(value) } else { Err(value) })
+    }
+
+    /// Returns the binary name of the runtime class of an object.
+    fn get_object_class_name(&self, obj: JObject) -> Result<String> {
+        let class = self.auto_local(self.get_object_class(obj)?);
+        self.get_class_name(class.as_obj())
+    }
+
+    /// Describe an object for diagnostics, as `ClassName@hash: toString()`,
+    /// where the hash is the hexadecimal identity hash code, like the default
+    /// `Object#toString`. Returns `"null"` for `null`.
+    ///
+    /// If `toString()` throws, the exception is cleared and only
+    /// `ClassName@hash` is returned. An `InterruptedException` is not cleared
+    /// though, and is returned as an `Interrupted` error, like `call_method`
+    /// does, so the interruption isn't lost.
+    pub fn describe_object<O>(&self, obj: O) -> Result<String>
+    where
+        O: Into<JObject<'a>>,
+    {
+        let obj = obj.into();
+        if obj.is_null() {
+            return Ok("null".to_owned());
+        }
+
+        let class_name = self.get_object_class_name(obj)?;
+        let hash = self.identity_hash_code(obj)?;
+        let identity = format!("{}@{:x}", class_name, hash);
+
+        // `call_method` reports an `InterruptedException` as `Interrupted`,
+        // which is passed on with the exception still pending
+        let string = match self.call_method(obj, "toString", "()Ljava/lang/String;", &[]) {
+            Ok(string) => self.auto_local(string.l()?),
+            Err(Error::JavaException) => {
+                self.exception_clear()?;
+                return Ok(identity);
+            }
+            Err(e) => return Err(e),
+        };
+        if string.as_obj().is_null() {
+            return Ok(format!("{}: null", identity));
+        }
+        let string: String = self.get_string(string.as_obj().into())?.into();
+        Ok(format!("{}: {}", identity, string))
+    }
+
     /// Create a new object using a constructor. Arguments aren't checked
     /// because
     /// of the `JMethodID` usage.
@@ -953,15 +2587,198 @@ impl<'a> JNIEnv<'a> {
         let jni_args: Vec<jvalue> = ctor_args.iter().map(|v| v.to_jni()).collect();
         let jni_args = jni_args.as_ptr();
 
+        let res = self.new_object_raw(class.into_inner(), ctor_id.into_inner(), jni_args);
+        self.check_interrupted(res)
+    }
+
+    /// Calls `NewObjectA`, apart from the lookup of `new_object_unchecked` so
+    /// that only the exceptions of the call itself are checked for an
+    /// `InterruptedException`.
+    fn new_object_raw(
+        &self,
+        class: sys::jclass,
+        ctor_id: jmethodID,
+        jni_args: *const jvalue,
+    ) -> Result<JObject<'a>> {
         Ok(jni_non_null_call!(
             self.internal,
             NewObjectA,
-            class.into_inner(),
-            ctor_id.into_inner(),
+            class,
+            ctor_id,
             jni_args
         ))
     }
 
+    /// Create a new instance of a record class (Java 16+) using its canonical
+    /// constructor.
+    ///
+    /// The constructor signature is built from the types of the record
+    /// components, which are looked up through reflection
+    /// (`Class#getRecordComponents`). The `components` must be given in
+    /// declaration order, and their number must match the number of record
+    /// components.
+    ///
+    /// Returns `Err` with the kind `NotRecord` if the class is not a record.
+    pub fn new_record<'c, T>(&self, class: T, components: &[JValue]) -> Result<JObject<'a>>
+    where
+        T: Desc<'a, JClass<'c>>,
+    {
+        let class = class.lookup(self)?;
+        let ctor_sig = format!(
+            "({})V",
+            self.get_record_components(class)?
+                .into_iter()
+                .map(|(_, sig)| sig)
+                .collect::<String>()
+        );
+
+        self.new_object(class, ctor_sig, components)
+    }
+
+    /// Read all the components of a record (Java 16+) by calling their
+    /// accessor methods. The values are returned in declaration order.
+    ///
+    /// Returns `Err` with the kind `NotRecord` if the object is not an
+    /// instance of a record class.
+    pub fn record_components<O>(&self, obj: O) -> Result<Vec<JValue<'a>>>
+    where
+        O: Into<JObject<'a>>,
+    {
+        let obj = obj.into();
+        non_null!(obj, "record_components obj argument");
+        let class = self.auto_local(self.get_object_class(obj)?);
+
+        self.get_record_components(JClass::from(class.as_obj()))?
+            .into_iter()
+            .map(|(name, sig)| self.call_method(obj, name, format!("(){}", sig), &[]))
+            .collect()
+    }
+
+    /// Returns the names and type signatures of the components of a record
+    /// class.
+    fn get_record_components(&self, class: JClass) -> Result<Vec<(String, String)>> {
+        let is_record = self.call_method(class, "isRecord", "()Z", &[])?.z()?;
+        if !is_record {
+            return Err(Error::NotRecord(self.get_class_name(class.into())?));
+        }
+
+        let components = self
+            .call_method(
+                class,
+                "getRecordComponents",
+                "()[Ljava/lang/reflect/RecordComponent;",
+                &[],
+            )?
+            .l()?;
+        let components = self.auto_local(components);
+        let len = self.get_array_length(components.as_obj().into_inner())?;
+
+        (0..len)
+            .map(|i| {
+                let component =
+                    self.get_object_array_element(components.as_obj().into_inner(), i)?;
+                let component = self.auto_local(component);
+
+                let name = self
+                    .call_method(component.as_obj(), "getName", "()Ljava/lang/String;", &[])?
+                    .l()?;
+                let name = self.auto_local(name);
+                let name: String = self.get_string(name.as_obj().into())?.into();
+
+                let ty = self
+                    .call_method(component.as_obj(), "getType", "()Ljava/lang/Class;", &[])?
+                    .l()?;
+                let ty = self.auto_local(ty);
+                let sig = class_name_to_signature(&self.get_class_name(ty.as_obj())?);
+
+                Ok((name, sig))
+            })
+            .collect()
+    }
+
+    /// Returns the reflective `java.lang.reflect.Field` objects for all the
+    /// fields declared by the class, as returned by `Class#getDeclaredFields`.
+    /// Inherited fields are not included.
+    ///
+    /// Fields having any of the `skip_modifiers` are left out, e.g.
+    /// `Modifiers::STATIC | Modifiers::TRANSIENT` to get only the fields that
+    /// make up the serializable state of an object. Pass `Modifiers::NONE` to
+    /// get all of them.
+    ///
+    /// Each returned field is a new local reference. Enough capacity for them
+    /// is ensured with `ensure_local_capacity`, and the references to the
+    /// skipped fields are deleted right away.
+    pub fn get_declared_fields<'c, T>(
+        &self,
+        class: T,
+        skip_modifiers: Modifiers,
+    ) -> Result<Vec<JObject<'a>>>
+    where
+        T: Desc<'a, JClass<'c>>,
+    {
+        self.get_declared_members(
+            class,
+            "getDeclaredFields",
+            "()[Ljava/lang/reflect/Field;",
+            skip_modifiers,
+        )
+    }
+
+    /// Returns the reflective `java.lang.reflect.Method` objects for all the
+    /// methods declared by the class, as returned by
+    /// `Class#getDeclaredMethods`. Inherited methods and constructors are not
+    /// included.
+    ///
+    /// Methods having any of the `skip_modifiers` are left out, see
+    /// `get_declared_fields`.
+    pub fn get_declared_methods<'c, T>(
+        &self,
+        class: T,
+        skip_modifiers: Modifiers,
+    ) -> Result<Vec<JObject<'a>>>
+    where
+        T: Desc<'a, JClass<'c>>,
+    {
+        self.get_declared_members(
+            class,
+            "getDeclaredMethods",
+            "()[Ljava/lang/reflect/Method;",
+            skip_modifiers,
+        )
+    }
+
+    /// Calls the reflection method returning an array of class members and
+    /// filters them by their modifiers.
+    fn get_declared_members<'c, T>(
+        &self,
+        class: T,
+        method: &str,
+        sig: &str,
+        skip_modifiers: Modifiers,
+    ) -> Result<Vec<JObject<'a>>>
+    where
+        T: Desc<'a, JClass<'c>>,
+    {
+        let class = class.lookup(self)?;
+        let members = self.call_method(class, method, sig, &[])?.l()?;
+        let members = self.auto_local(members);
+        let len = self.get_array_length(members.as_obj().into_inner())?;
+        self.ensure_local_capacity(len)?;
+
+        let mut result = Vec::with_capacity(len as usize);
+        for i in 0..len {
+            let member = self.get_object_array_element(members.as_obj().into_inner(), i)?;
+            let member = self.auto_local(member);
+            let modifiers = self
+                .call_method(member.as_obj(), "getModifiers", "()I", &[])?
+                .i()?;
+            if !Modifiers::from_bits(modifiers).intersects(skip_modifiers) {
+                result.push(member.forget());
+            }
+        }
+        Ok(result)
+    }
+
     /// Cast a JObject to a `JList`. This won't throw exceptions or return errors
     /// in the event that the object isn't actually a list, but the methods on
     /// the resulting map object will.
@@ -978,16 +2795,135 @@ impl<'a> JNIEnv<'a> {
         JMap::from_env(self, obj)
     }
 
+    /// Collect the elements of a `java.util.stream.Stream` into a vector, with
+    /// `Stream#collect(Collectors.toList())`. The stream is consumed.
+    ///
+    /// This materializes the whole stream, so it must only be used with
+    /// bounded streams: an infinite stream never returns. Each element is a
+    /// new local reference, so the number of elements must be taken into
+    /// account when reserving local capacity.
+    pub fn stream_to_vec<O>(&self, stream: O) -> Result<Vec<JObject<'a>>>
+    where
+        O: Into<JObject<'a>>,
+    {
+        let stream = stream.into();
+        non_null!(stream, "stream_to_vec stream argument");
+
+        let collector = self
+            .call_static_method(
+                "java/util/stream/Collectors",
+                "toList",
+                "()Ljava/util/stream/Collector;",
+                &[],
+            )?
+            .l()?;
+        let collector = self.auto_local(collector);
+        let list = self
+            .call_method(
+                stream,
+                "collect",
+                "(Ljava/util/stream/Collector;)Ljava/lang/Object;",
+                &[collector.as_obj().into()],
+            )?
+            .l()?;
+        let list = self.auto_local(list);
+        let array = self
+            .call_method(list.as_obj(), "toArray", "()[Ljava/lang/Object;", &[])?
+            .l()?;
+        let array = self.auto_local(array);
+
+        let raw = array.as_obj().into_inner();
+        let len = self.get_array_length(raw)?;
+        (0..len)
+            .map(|i| self.get_object_array_element(raw, i))
+            .collect()
+    }
+
     /// Get a JavaStr from a JString. This allows conversions from java string
     /// objects to rust strings.
     ///
     /// This entails a call to `GetStringUTFChars` and only decodes java's
     /// modified UTF-8 format on conversion to a rust-compatible string.
+    ///
+    /// The conversions to `String` and `Cow<str>` decode NUL characters, which
+    /// are encoded as `0xC0 0x80`, and supplementary characters, which are
+    /// encoded as separate surrogates. The `CStr` methods reachable through
+    /// `Deref`, like `to_str`, expect standard UTF-8 and don't.
+    ///
+    /// The string can also be passed on to a native library expecting a
+    /// `const char*` without copying it, with `JavaStr::get_raw`, as long as
+    /// the library accepts modified UTF-8. Use `JavaStr::to_utf8_cstring` to
+    /// get a standard UTF-8 copy otherwise.
     pub fn get_string(&self, obj: JString<'a>) -> Result<JavaStr<'a, '_>> {
         non_null!(obj, "get_string obj argument");
         JavaStr::from_env(self, obj)
     }
 
+    /// Get the contents of a JString as a rust string, failing instead of
+    /// replacing malformed characters like the conversions of `JavaStr` do.
+    ///
+    /// Returns `Err` with the kind `InvalidUtf8` and the offset into the
+    /// modified UTF-8 encoding of the string if it can't be decoded, such as
+    /// when it contains an unpaired surrogate.
+    pub fn get_string_checked(&self, obj: JString<'a>) -> Result<String> {
+        let java_str = self.get_string(obj)?;
+        let decoded = java_str.try_to_str()?;
+        Ok(decoded.into_owned())
+    }
+
+    /// Get the contents of a JString as a shared rust string, reusing the
+    /// result of a previous decoding of the same string object.
+    ///
+    /// The cache is keyed by the object identity (`System#identityHashCode`
+    /// followed by an `is_same_object` check), not by the contents, so it only
+    /// helps when the very same string object is decoded over and over, such
+    /// as a label or a key held by java code. Since java strings are
+    /// immutable, a cached value can never go stale.
+    ///
+    /// The cache is shared by all threads and holds at most 256 strings; the
+    /// oldest entries are evicted first. Each cached string is kept alive by a
+    /// global reference until it's evicted, so the memory of both the java and
+    /// the rust string stays in use for that long. Prefer `get_string` for
+    /// strings that are only decoded once.
+    pub fn get_string_cached(&self, obj: JString<'a>) -> Result<Arc<str>> {
+        non_null!(obj, "get_string_cached obj argument");
+        let key = (self.identity_hash_code(*obj)?, ());
+        if let Some(string) = STRING_CACHE.lock().unwrap().get(self, &key, *obj)? {
+            return Ok(string);
+        }
+
+        let string: Arc<str> = Arc::from(String::from(self.get_string(obj)?));
+        let global = self.new_global_ref(obj)?;
+
+        let mut cache = STRING_CACHE.lock().unwrap();
+        // Another thread may have decoded the same string in the meantime
+        if let Some(cached) = cache.get(self, &key, *obj)? {
+            return Ok(cached);
+        }
+        cache.insert(key, global, string.clone());
+        Ok(string)
+    }
+
+    /// Returns `System#identityHashCode` of an object, with the class and the
+    /// method id only looked up once.
+    pub(crate) fn identity_hash_code(&self, obj: JObject) -> Result<jint> {
+        let (class, id) = self.lookup_cached_method(
+            &SYSTEM_METHODS,
+            "java/lang/System",
+            "identityHashCode",
+            "(Ljava/lang/Object;)I",
+            true,
+        )?;
+        let class = JClass::from(class.as_obj().into_inner());
+        self.call_static_method_unchecked(
+            class,
+            JStaticMethodID::from(id),
+            JavaType::Primitive(Primitive::Int),
+            &[obj.into()],
+        )?
+        .i()
+    }
+
     /// Get a pointer to the character array beneath a JString.
     ///
     /// Array contains Java's modified UTF-8.
@@ -1018,6 +2954,9 @@ impl<'a> JNIEnv<'a> {
     /// Create a new java string object from a rust string. This requires a
     /// re-encoding of rusts *real* UTF-8 strings to java's modified UTF-8
     /// format.
+    ///
+    /// NUL characters are encoded as `0xC0 0x80`, so a string containing them
+    /// is passed to java in full rather than cut off at the first one.
     pub fn new_string<S: Into<JNIString>>(&self, from: S) -> Result<JString<'a>> {
         let ffi_str = from.into();
         Ok(jni_non_null_call!(
@@ -1027,7 +2966,212 @@ impl<'a> JNIEnv<'a> {
         ))
     }
 
-    /// Get the length of a java array
+    /// Create a new java string object from UTF-16 code units, as used by
+    /// java strings internally. No re-encoding takes place, and unpaired
+    /// surrogates are kept as they are.
+    pub fn new_string_utf16(&self, chars: &[jchar]) -> Result<JString<'a>> {
+        Ok(jni_non_null_call!(
+            self.internal,
+            NewString,
+            chars.as_ptr(),
+            chars.len() as jsize
+        ))
+    }
+
+    /// Create a new java string object from an OS string, such as a path,
+    /// decoding it the same way as the JVM decodes the strings it gets from
+    /// the OS, e.g. the file names listed by `File#list`. This preserves paths
+    /// that are not valid UTF-8 and so can't be converted to a rust string
+    /// without losing data.
+    ///
+    /// The conversion depends on the platform:
+    ///
+    /// * On Unix, the bytes of the string are decoded with the charset of the
+    ///   `sun.jnu.encoding` system property, which depends on the locale the
+    ///   JVM is started with. Bytes that are not valid in that charset are
+    ///   replaced, as with `new String(bytes, charset)`.
+    /// * On Windows, the UTF-16 code units of the string are used as they are
+    ///   with `new_string_utf16`.
+    /// * On other platforms, the string is converted lossily to UTF-8 and
+    ///   passed to `new_string`.
+    pub fn new_string_os<S: AsRef<OsStr>>(&self, from: S) -> Result<JString<'a>> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+
+            let bytes = from.as_ref().as_bytes();
+            let bytes = self.auto_local(JObject::from(self.byte_array_from_slice(bytes)?));
+            let key = self.auto_local(JObject::from(self.new_string("sun.jnu.encoding")?));
+            let charset = self
+                .call_static_method(
+                    "java/lang/System",
+                    "getProperty",
+                    "(Ljava/lang/String;)Ljava/lang/String;",
+                    &[key.as_obj().into()],
+                )?
+                .l()?;
+            let charset = self.auto_local(charset);
+
+            let string = if charset.as_obj().is_null() {
+                self.new_object("java/lang/String", "([B)V", &[bytes.as_obj().into()])?
+            } else {
+                self.new_object(
+                    "java/lang/String",
+                    "([BLjava/lang/String;)V",
+                    &[bytes.as_obj().into(), charset.as_obj().into()],
+                )?
+            };
+            Ok(string.into())
+        }
+
+        #[cfg(windows)]
+        {
+            use std::os::windows::ffi::OsStrExt;
+
+            let chars: Vec<jchar> = from.as_ref().encode_wide().collect();
+            self.new_string_utf16(&chars)
+        }
+
+        #[cfg(not(any(unix, windows)))]
+        {
+            self.new_string(from.as_ref().to_string_lossy())
+        }
+    }
+
+    /// Get the length of a java string in UTF-16 code units, like
+    /// `String#length`, without copying its contents.
+    pub fn get_string_length(&self, obj: JString) -> Result<jsize> {
+        non_null!(obj, "get_string_length obj argument");
+        Ok(jni_unchecked!(
+            self.internal,
+            GetStringLength,
+            obj.into_inner()
+        ))
+    }
+
+    /// Get the length of a java string in bytes of modified UTF-8, not
+    /// counting the terminating NUL, without copying its contents. This is the
+    /// length of the string returned by `get_string`.
+    pub fn get_string_utf_length(&self, obj: JString) -> Result<jsize> {
+        non_null!(obj, "get_string_utf_length obj argument");
+        Ok(jni_unchecked!(
+            self.internal,
+            GetStringUTFLength,
+            obj.into_inner()
+        ))
+    }
+
+    /// Get the UTF-16 code units of a java string. Unlike `get_string`, this
+    /// doesn't decode the string, so it can't fail on malformed contents.
+    pub fn get_string_utf16(&self, obj: JString) -> Result<Vec<jchar>> {
+        non_null!(obj, "get_string_utf16 obj argument");
+        let len = self.get_string_length(obj)?;
+        let mut chars = vec![0; len as usize];
+        self.get_string_region(obj, 0, &mut chars)?;
+        Ok(chars)
+    }
+
+    /// Get the UTF-16 code units of a java string without copying them, if
+    /// the JVM allows it, through `GetStringCritical`. This is meant for very
+    /// large strings, where `get_string_utf16` would be wasteful.
+    ///
+    /// No other JNI functions may be called while the returned
+    /// `AutoStringChars` is alive, just like with
+    /// `get_primitive_array_critical`. Keep the critical region as short as
+    /// possible, and drop the characters explicitly or in a nested scope.
+    ///
+    /// If the given string is `null`, an `Error::NullPtr` is returned.
+    pub fn get_string_critical(&self, obj: JString<'a>) -> Result<AutoStringChars<'a, '_>> {
+        non_null!(obj, "get_string_critical obj argument");
+        // No JNI calls are allowed in the critical region, so read the length first
+        let len = self.get_string_length(obj)?;
+        let mut is_copy: jboolean = 0xff;
+        let ptr = jni_unchecked!(
+            self.internal,
+            GetStringCritical,
+            obj.into_inner(),
+            &mut is_copy
+        );
+        AutoStringChars::new(self, obj, ptr, len, is_copy == sys::JNI_TRUE)
+    }
+
+    /// Copy the UTF-16 code units of a java string, starting at `start`, into
+    /// the given buffer, which determines the number of code units to copy.
+    ///
+    /// Returns `Err` with a pending `StringIndexOutOfBoundsException` if the
+    /// region is out of the bounds of the string.
+    pub fn get_string_region(&self, obj: JString, start: jsize, buf: &mut [jchar]) -> Result<()> {
+        non_null!(obj, "get_string_region obj argument");
+        jni_void_call!(
+            self.internal,
+            GetStringRegion,
+            obj.into_inner(),
+            start,
+            buf.len() as jsize,
+            buf.as_mut_ptr()
+        );
+        Ok(())
+    }
+
+    /// Copy `len` UTF-16 code units of a java string, starting at `start`,
+    /// into the given buffer, encoded in java's modified UTF-8. Returns the
+    /// number of bytes written.
+    ///
+    /// As a code unit takes up to three bytes, and JNI adds a NUL terminator
+    /// which isn't included in the returned length, the buffer must hold at
+    /// least `3 * len + 1` bytes, or `Err` with the kind `BufferTooSmall` is
+    /// returned.
+    ///
+    /// Returns `Err` with a pending `StringIndexOutOfBoundsException` if the
+    /// region is out of the bounds of the string.
+    pub fn get_string_utf_region(
+        &self,
+        obj: JString,
+        start: jsize,
+        len: jsize,
+        buf: &mut [u8],
+    ) -> Result<usize> {
+        non_null!(obj, "get_string_utf_region obj argument");
+        let required = 3 * len.max(0) as usize + 1;
+        if buf.len() < required {
+            return Err(Error::BufferTooSmall {
+                required,
+                actual: buf.len(),
+            });
+        }
+
+        jni_void_call!(
+            self.internal,
+            GetStringUTFRegion,
+            obj.into_inner(),
+            start,
+            len,
+            buf.as_mut_ptr() as *mut c_char
+        );
+        // Modified UTF-8 never contains a NUL byte, apart from the terminator
+        Ok(buf.iter().position(|&b| b == 0).unwrap_or(buf.len()))
+    }
+
+    /// Transform the UTF-16 code units of a java string with the given
+    /// function, and create a new string from the result. As java strings are
+    /// immutable, the original string is left unchanged.
+    ///
+    /// This is a shorthand for `get_string_utf16` followed by
+    /// `new_string_utf16`, and avoids decoding the string into a rust string
+    /// and encoding it back.
+    pub fn map_string<F>(&self, obj: JString, f: F) -> Result<JString<'a>>
+    where
+        F: FnOnce(&mut Vec<jchar>),
+    {
+        let mut chars = self.get_string_utf16(obj)?;
+        f(&mut chars);
+        self.new_string_utf16(&chars)
+    }
+
+    /// Get the length of a java array. Works with arrays of any type, both
+    /// primitive and object arrays.
+    ///
+    /// Returns `Err` with the kind `NullPtr` if `array` is `null`.
     pub fn get_array_length(&self, array: jarray) -> Result<jsize> {
         non_null!(array, "get_array_length array argument");
         let len: jsize = jni_unchecked!(self.internal, GetArrayLength, array);
@@ -1037,6 +3181,10 @@ impl<'a> JNIEnv<'a> {
     /// Construct a new array holding objects in class `element_class`.
     /// All elements are initially set to `initial_element`.
     ///
+    /// Passing a primitive type descriptor such as `"I"` as the
+    /// `element_class` returns `Err` with the kind `PrimitiveClassDesc`; use
+    /// the typed methods such as `new_int_array` for primitive arrays instead.
+    ///
     /// This function returns a local reference, that must not be allocated
     /// excessively.
     /// See [Java documentation][1] for details.
@@ -1052,7 +3200,10 @@ impl<'a> JNIEnv<'a> {
         T: Desc<'a, JClass<'c>>,
         U: Into<JObject<'a>>,
     {
-        let class = element_class.lookup(self)?;
+        let class = match element_class.lookup(self) {
+            Err(Error::JavaException) => return Err(self.primitive_element_class_error()?),
+            res => res?,
+        };
         Ok(jni_non_null_call!(
             self.internal,
             NewObjectArray,
@@ -1062,6 +3213,46 @@ impl<'a> JNIEnv<'a> {
         ))
     }
 
+    /// Checks whether the element class of `new_object_array` couldn't be
+    /// found because it's a primitive type descriptor such as `"I"`, which
+    /// `FindClass` reports with a `NoClassDefFoundError` naming the descriptor.
+    /// That exception is cleared and replaced by a `PrimitiveClassDesc` error,
+    /// while any other exception is left pending.
+    ///
+    /// The descriptor is only inspected once the lookup failed, so a class in
+    /// the default package named like a primitive type is still found.
+    fn primitive_element_class_error(&self) -> Result<Error> {
+        let exception = self.auto_local(self.exception_occurred()?);
+        self.exception_clear()?;
+
+        let class = self.auto_local(self.find_class("java/lang/NoClassDefFoundError")?);
+        let mut desc = None;
+        if self.is_instance_of(exception.as_obj(), &class)? {
+            let message = self
+                .call_method(
+                    exception.as_obj(),
+                    "getMessage",
+                    "()Ljava/lang/String;",
+                    &[],
+                )?
+                .l()?;
+            if !message.is_null() {
+                let message = self.auto_local(message);
+                desc = Some(String::from(self.get_string(message.as_obj().into())?));
+            }
+        }
+
+        match desc {
+            Some(desc) if desc.len() == 1 && "ZBCSIJFDV".contains(&desc) => {
+                Ok(Error::PrimitiveClassDesc(desc))
+            }
+            _ => {
+                self.throw(JThrowable::from(exception.as_obj()))?;
+                Ok(Error::JavaException)
+            }
+        }
+    }
+
     /// Returns an element of the `jobjectArray` array.
     pub fn get_object_array_element(
         &self,
@@ -1072,6 +3263,24 @@ impl<'a> JNIEnv<'a> {
         Ok(jni_non_void_call!(self.internal, GetObjectArrayElement, array, index).into())
     }
 
+    /// Returns an iterator over the elements of the `jobjectArray` array. The
+    /// length of the array is only read once. See `ObjectArrayIter` for the
+    /// handling of the returned local references.
+    pub fn object_array_iter(&self, array: jobjectArray) -> Result<ObjectArrayIter<'a, '_>> {
+        let len = self.get_array_length(array)?;
+        Ok(ObjectArrayIter::new(self, array, len))
+    }
+
+    /// Like `object_array_iter`, but wraps each element in an `AutoLocal`, so
+    /// its local reference is deleted once the consumer drops it. This keeps
+    /// iterating large arrays from running out of local references.
+    pub fn object_array_iter_auto(
+        &self,
+        array: jobjectArray,
+    ) -> Result<ObjectArrayAutoIter<'a, '_>> {
+        Ok(ObjectArrayAutoIter::new(self.object_array_iter(array)?))
+    }
+
     /// Sets an element of the `jobjectArray` array.
     pub fn set_object_array_element<O>(
         &self,
@@ -1093,6 +3302,40 @@ impl<'a> JNIEnv<'a> {
         Ok(())
     }
 
+    /// Create a new object array, and fill each element with the object
+    /// returned by the given function for its index.
+    ///
+    /// The function is called in a new local reference frame for every
+    /// element, so any local references it creates, including the returned
+    /// object, are freed once the element is set.
+    ///
+    /// If the function returns an error, no further elements are created, the
+    /// array is deleted and the error is returned.
+    pub fn object_array_init<'c, T, F>(
+        &self,
+        length: jsize,
+        element_class: T,
+        mut f: F,
+    ) -> Result<jobjectArray>
+    where
+        T: Desc<'a, JClass<'c>>,
+        F: FnMut(jsize) -> Result<JObject<'a>>,
+    {
+        let array = self.new_object_array(length, element_class, JObject::null())?;
+        for index in 0..length {
+            let res = self.with_local_frame(4, || {
+                let element = f(index)?;
+                self.set_object_array_element(array, index, element)?;
+                Ok(JObject::null())
+            });
+            if let Err(e) = res {
+                self.delete_local_ref(array.into())?;
+                return Err(e);
+            }
+        }
+        Ok(array)
+    }
+
     /// Create a new java byte array from a rust byte slice.
     pub fn byte_array_from_slice(&self, buf: &[u8]) -> Result<jbyteArray> {
         let length = buf.len() as i32;
@@ -1111,7 +3354,7 @@ impl<'a> JNIEnv<'a> {
     /// Converts a java byte array to a rust vector of bytes.
     pub fn convert_byte_array(&self, array: jbyteArray) -> Result<Vec<u8>> {
         non_null!(array, "convert_byte_array array argument");
-        let length = jni_non_void_call!(self.internal, GetArrayLength, array);
+        let length = self.get_array_length(array)?;
         let mut vec = vec![0u8; length as usize];
         jni_unchecked!(
             self.internal,
@@ -1124,6 +3367,132 @@ impl<'a> JNIEnv<'a> {
         Ok(vec)
     }
 
+    /// Converts a `java.math.BigInteger` to its two's-complement big-endian
+    /// byte representation, as returned by `BigInteger#toByteArray`. The
+    /// result always contains at least one byte (the sign bit).
+    pub fn bigint_to_bytes<O>(&self, obj: O) -> Result<Vec<u8>>
+    where
+        O: Into<JObject<'a>>,
+    {
+        let obj = obj.into();
+        non_null!(obj, "bigint_to_bytes obj argument");
+        let bytes = self.call_method(obj, "toByteArray", "()[B", &[])?.l()?;
+        let bytes = self.auto_local(bytes);
+        self.convert_byte_array(bytes.as_obj().into_inner())
+    }
+
+    /// Creates a new `java.math.BigInteger` from its two's-complement
+    /// big-endian byte representation, using the `BigInteger(byte[])`
+    /// constructor. An empty slice is treated as zero.
+    pub fn bigint_from_bytes(&self, bytes: &[u8]) -> Result<JObject<'a>> {
+        let bytes = if bytes.is_empty() { &[0] } else { bytes };
+        let array = self.auto_local(JObject::from(self.byte_array_from_slice(bytes)?));
+        self.new_object("java/math/BigInteger", "([B)V", &[array.as_obj().into()])
+    }
+
+    /// Converts a `java.math.BigDecimal` to a string using
+    /// `BigDecimal#toString`. The result may use scientific notation, and can be
+    /// converted back with `bigdecimal_from_str` without losing the scale.
+    pub fn bigdecimal_to_string<O>(&self, obj: O) -> Result<String>
+    where
+        O: Into<JObject<'a>>,
+    {
+        let obj = obj.into();
+        non_null!(obj, "bigdecimal_to_string obj argument");
+        let string = self
+            .call_method(obj, "toString", "()Ljava/lang/String;", &[])?
+            .l()?;
+        let string = self.auto_local(string);
+        let string = self.get_string(string.as_obj().into())?.into();
+        Ok(string)
+    }
+
+    /// Creates a new `java.math.BigDecimal` from its string representation,
+    /// using the `BigDecimal(String)` constructor.
+    ///
+    /// If the string is not a valid number, a `NumberFormatException` is
+    /// thrown and `Err` is returned.
+    pub fn bigdecimal_from_str(&self, value: &str) -> Result<JObject<'a>> {
+        let value = self.auto_local(self.new_string(value)?);
+        self.new_object(
+            "java/math/BigDecimal",
+            "(Ljava/lang/String;)V",
+            &[value.as_obj().into()],
+        )
+    }
+
+    /// Converts a `java.math.BigInteger` to a `num_bigint::BigInt`.
+    #[cfg(feature = "num-bigint")]
+    pub fn get_bigint<O>(&self, obj: O) -> Result<num_bigint::BigInt>
+    where
+        O: Into<JObject<'a>>,
+    {
+        let bytes = self.bigint_to_bytes(obj)?;
+        Ok(num_bigint::BigInt::from_signed_bytes_be(&bytes))
+    }
+
+    /// Creates a new `java.math.BigInteger` from a `num_bigint::BigInt`.
+    #[cfg(feature = "num-bigint")]
+    pub fn new_bigint(&self, value: &num_bigint::BigInt) -> Result<JObject<'a>> {
+        self.bigint_from_bytes(&value.to_signed_bytes_be())
+    }
+
+    /// Converts a `java.util.UUID` to its 16 byte big-endian representation,
+    /// i.e. the bytes of `getMostSignificantBits` followed by those of
+    /// `getLeastSignificantBits`. This is the layout used by RFC 4122 and by
+    /// the `uuid` crate.
+    pub fn uuid_to_bytes<O>(&self, obj: O) -> Result<[u8; 16]>
+    where
+        O: Into<JObject<'a>>,
+    {
+        let obj = obj.into();
+        non_null!(obj, "uuid_to_bytes obj argument");
+        let msb = self
+            .call_method(obj, "getMostSignificantBits", "()J", &[])?
+            .j()?;
+        let lsb = self
+            .call_method(obj, "getLeastSignificantBits", "()J", &[])?
+            .j()?;
+
+        let mut bytes = [0; 16];
+        bytes[..8].copy_from_slice(&msb.to_be_bytes());
+        bytes[8..].copy_from_slice(&lsb.to_be_bytes());
+        Ok(bytes)
+    }
+
+    /// Creates a new `java.util.UUID` from its 16 byte big-endian
+    /// representation, using the `UUID(long, long)` constructor. See
+    /// `uuid_to_bytes` for the layout.
+    pub fn uuid_from_bytes(&self, bytes: [u8; 16]) -> Result<JObject<'a>> {
+        let mut msb = [0; 8];
+        let mut lsb = [0; 8];
+        msb.copy_from_slice(&bytes[..8]);
+        lsb.copy_from_slice(&bytes[8..]);
+        self.new_object(
+            "java/util/UUID",
+            "(JJ)V",
+            &[
+                jlong::from_be_bytes(msb).into(),
+                jlong::from_be_bytes(lsb).into(),
+            ],
+        )
+    }
+
+    /// Converts a `java.util.UUID` to a `uuid::Uuid`.
+    #[cfg(feature = "uuid")]
+    pub fn get_uuid<O>(&self, obj: O) -> Result<uuid::Uuid>
+    where
+        O: Into<JObject<'a>>,
+    {
+        Ok(uuid::Uuid::from_bytes(self.uuid_to_bytes(obj)?))
+    }
+
+    /// Creates a new `java.util.UUID` from a `uuid::Uuid`.
+    #[cfg(feature = "uuid")]
+    pub fn new_uuid(&self, value: &uuid::Uuid) -> Result<JObject<'a>> {
+        self.uuid_from_bytes(*value.as_bytes())
+    }
+
     /// Create a new java boolean array of supplied length.
     pub fn new_boolean_array(&self, length: jsize) -> Result<jbooleanArray> {
         let array: jbooleanArray = jni_non_null_call!(self.internal, NewBooleanArray, length);
@@ -1307,6 +3676,30 @@ impl<'a> JNIEnv<'a> {
         Ok(())
     }
 
+    /// Copy all elements of a java int array of a known length into a rust
+    /// array, which saves allocating a vector for small fixed-size results,
+    /// such as coordinates returned by a method:
+    ///
+    /// ```rust,ignore
+    /// let point = env.call_method(obj, "getLocation", "()[I", &[])?.l()?;
+    /// let [x, y, z] = env.get_int_array_fixed::<3>(point.into_inner())?;
+    /// ```
+    ///
+    /// Returns `Err` with the kind `ArrayLengthMismatch` if the length of the
+    /// java array is not `N`.
+    pub fn get_int_array_fixed<const N: usize>(&self, array: jintArray) -> Result<[jint; N]> {
+        let length = self.get_array_length(array)? as usize;
+        if length != N {
+            return Err(Error::ArrayLengthMismatch {
+                expected: N,
+                actual: length,
+            });
+        }
+        let mut buf = [0; N];
+        self.get_int_array_region(array, 0, &mut buf)?;
+        Ok(buf)
+    }
+
     /// Copy elements of the java long array from the `start` index to the
     /// `buf` slice. The number of copied elements is equal to the `buf` length.
     ///
@@ -1544,6 +3937,10 @@ impl<'a> JNIEnv<'a> {
     }
 
     /// Get a field without checking the provided type against the actual field.
+    ///
+    /// With a `JFieldID` looked up once with `get_field_id`, this is the fast
+    /// way to read the same field of many objects, e.g. in a loop. The type
+    /// must match the field's, or the behavior is undefined.
     pub fn get_field_unchecked<'f, O, T>(
         &self,
         obj: O,
@@ -1555,20 +3952,20 @@ impl<'a> JNIEnv<'a> {
         T: Desc<'a, JFieldID<'f>>,
     {
         let obj = obj.into();
-        non_null!(obj, "get_field_typed obj argument");
+        non_null!(obj, "get_field_unchecked obj argument");
 
         let field = field.lookup(self)?.into_inner();
         let obj = obj.into_inner();
 
-        // TODO clean this up
         Ok(match ty {
             JavaType::Object(_) | JavaType::Array(_) => {
                 let obj: JObject =
                     jni_non_void_call!(self.internal, GetObjectField, obj, field).into();
                 obj.into()
             }
-            // JavaType::Object
-            JavaType::Method(_) => unimplemented!(),
+            JavaType::Method(_) => {
+                return Err(Error::WrongJValueType("method", "see java field"));
+            }
             JavaType::Primitive(p) => match p {
                 Primitive::Boolean => {
                     jni_unchecked!(self.internal, GetBooleanField, obj, field).into()
@@ -1590,23 +3987,25 @@ impl<'a> JNIEnv<'a> {
     }
 
     /// Set a field without any type checking.
+    ///
+    /// Like `get_field_unchecked`, this is meant for writing the same field of
+    /// many objects with a `JFieldID` looked up once. The value must match the
+    /// field's type, or the behavior is undefined.
     pub fn set_field_unchecked<'f, O, T>(&self, obj: O, field: T, val: JValue) -> Result<()>
     where
         O: Into<JObject<'a>>,
         T: Desc<'a, JFieldID<'f>>,
     {
         let obj = obj.into();
-        non_null!(obj, "set_field_typed obj argument");
+        non_null!(obj, "set_field_unchecked obj argument");
 
         let field = field.lookup(self)?.into_inner();
         let obj = obj.into_inner();
 
-        // TODO clean this up
         match val {
             JValue::Object(o) => {
                 jni_unchecked!(self.internal, SetObjectField, obj, field, o.into_inner());
             }
-            // JavaType::Object
             JValue::Bool(b) => {
                 jni_unchecked!(self.internal, SetBooleanField, obj, field, b);
             }
@@ -1641,6 +4040,9 @@ impl<'a> JNIEnv<'a> {
 
     /// Get a field. Requires an object class lookup and a field id lookup
     /// internally.
+    ///
+    /// Note: JNI field access doesn't guarantee the semantics of `volatile`
+    /// fields. Use `get_volatile_field` for those.
     pub fn get_field<O, S, T>(&self, obj: O, name: S, ty: T) -> Result<JValue<'a>>
     where
         O: Into<JObject<'a>>,
@@ -1659,6 +4061,9 @@ impl<'a> JNIEnv<'a> {
 
     /// Set a field. Does the same lookups as `get_field` and ensures that the
     /// type matches the given value.
+    ///
+    /// Note: JNI field access doesn't guarantee the semantics of `volatile`
+    /// fields. Use `set_volatile_field` for those.
     pub fn set_field<O, S, T>(&self, obj: O, name: S, ty: T, val: JValue) -> Result<()>
     where
         O: Into<JObject<'a>>,
@@ -1694,6 +4099,370 @@ impl<'a> JNIEnv<'a> {
         self.set_field_unchecked(obj, (&class, name, ty), val)
     }
 
+    /// Get an `int[]` field and copy its contents into a vector. Does the same
+    /// lookups as `get_field`.
+    ///
+    /// Returns `Ok(None)` if the field is `null`.
+    pub fn get_int_array_field<O, S>(&self, obj: O, name: S) -> Result<Option<Vec<jint>>>
+    where
+        O: Into<JObject<'a>>,
+        S: Into<JNIString>,
+    {
+        let array = self.get_field(obj, name, "[I")?.l()?;
+        if array.is_null() {
+            return Ok(None);
+        }
+        let array = self.auto_local(array);
+
+        let raw = array.as_obj().into_inner();
+        let length = self.get_array_length(raw)?;
+        let mut vec = vec![0; length as usize];
+        self.get_int_array_region(raw, 0, &mut vec)?;
+        Ok(Some(vec))
+    }
+
+    /// Get a `String[]` field and convert its elements into Rust strings.
+    /// Does the same lookups as `get_field`. `null` elements are returned as
+    /// `None`.
+    ///
+    /// Returns `Ok(None)` if the field is `null`.
+    pub fn get_string_array_field<O, S>(
+        &self,
+        obj: O,
+        name: S,
+    ) -> Result<Option<Vec<Option<String>>>>
+    where
+        O: Into<JObject<'a>>,
+        S: Into<JNIString>,
+    {
+        let array = self.get_field(obj, name, "[Ljava/lang/String;")?.l()?;
+        if array.is_null() {
+            return Ok(None);
+        }
+        let array = self.auto_local(array);
+
+        let raw = array.as_obj().into_inner();
+        let length = self.get_array_length(raw)?;
+        let mut vec = Vec::with_capacity(length as usize);
+        for i in 0..length {
+            let element = self.auto_local(self.get_object_array_element(raw, i)?);
+            if element.as_obj().is_null() {
+                vec.push(None);
+            } else {
+                vec.push(Some(self.get_string(element.as_obj().into())?.into()));
+            }
+        }
+        Ok(Some(vec))
+    }
+
+    /// Get a field without specifying its type. The type is looked up through
+    /// reflection (`Class#getDeclaredField`), walking up the class hierarchy
+    /// until a non-static field with the given name is found.
+    ///
+    /// The reflective lookup is cached per class and field name, so it only
+    /// happens once for each pair, as long as it stays among the 256 most
+    /// recent ones. The classes are told apart by identity, so classes of the
+    /// same name in different class loaders don't share an entry. However,
+    /// every call still requires a class lookup, an identity hash code and a
+    /// field id lookup, which makes it noticeably slower than `get_field` with
+    /// an explicit signature. Prefer the latter (or cached `JFieldID`s) when
+    /// the field type is known.
+    ///
+    /// Returns `Err` with the kind `FieldNotFound` if neither the class nor any
+    /// of its superclasses declare a non-static field with that name.
+    pub fn get_field_inferred<O, S>(&self, obj: O, name: S) -> Result<JValue<'a>>
+    where
+        O: Into<JObject<'a>>,
+        S: AsRef<str>,
+    {
+        let obj = obj.into();
+        let sig = self.infer_field_sig(obj, name.as_ref())?;
+        self.get_field(obj, name.as_ref(), sig)
+    }
+
+    /// Set a field without specifying its type. Does the same lookups as
+    /// `get_field_inferred` and ensures that the type matches the given value.
+    pub fn set_field_inferred<O, S>(&self, obj: O, name: S, val: JValue) -> Result<()>
+    where
+        O: Into<JObject<'a>>,
+        S: AsRef<str>,
+    {
+        let obj = obj.into();
+        let sig = self.infer_field_sig(obj, name.as_ref())?;
+        self.set_field(obj, name.as_ref(), sig, val)
+    }
+
+    /// Looks up the signature of a field of the object's class, consulting the
+    /// cache first.
+    fn infer_field_sig(&self, obj: JObject, name: &str) -> Result<String> {
+        non_null!(obj, "infer_field_sig obj argument");
+        let class = self.auto_local(self.get_object_class(obj)?);
+        let key = (self.identity_hash_code(class.as_obj())?, name.to_owned());
+
+        let cached = INFERRED_FIELD_SIGS
+            .lock()
+            .unwrap()
+            .get(self, &key, class.as_obj())?;
+        if let Some(sig) = cached {
+            return Ok(sig);
+        }
+
+        let sig = self.lookup_field_sig(&class, name)?;
+        let global = self.new_global_ref(&class)?;
+
+        let mut sigs = INFERRED_FIELD_SIGS.lock().unwrap();
+        // Another thread may have looked up the field in the meantime
+        if let Some(cached) = sigs.get(self, &key, class.as_obj())? {
+            return Ok(cached);
+        }
+        sigs.insert(key, global, sig.clone());
+        Ok(sig)
+    }
+
+    /// Finds the non-static field declared by the class or one of its
+    /// superclasses through reflection and returns its signature.
+    fn lookup_field_sig(&self, class: &AutoLocal<'a, '_>, name: &str) -> Result<String> {
+        let field = self.find_reflected_field(class, name, "", Modifiers::STATIC)?;
+        let field = self.auto_local(field);
+        let ty = self
+            .call_method(field.as_obj(), "getType", "()Ljava/lang/Class;", &[])?
+            .l()?;
+        let ty = self.auto_local(ty);
+        let type_name = self.get_class_name(ty.as_obj())?;
+        Ok(class_name_to_signature(&type_name))
+    }
+
+    /// Finds the `java.lang.reflect.Field` declared by the class or one of its
+    /// superclasses, skipping the fields having any of the `skip_modifiers`.
+    /// The signature is only used for the error if there is no such field, and
+    /// is empty when it's not known.
+    fn find_reflected_field(
+        &self,
+        class: &AutoLocal<'a, '_>,
+        name: &str,
+        sig: &str,
+        skip_modifiers: Modifiers,
+    ) -> Result<JObject<'a>> {
+        let jname = self.auto_local(self.new_string(name)?);
+        let mut current = self.auto_local(self.new_local_ref::<JObject>(class.as_obj())?);
+
+        while !current.as_obj().is_null() {
+            let res = self.call_method(
+                current.as_obj(),
+                "getDeclaredField",
+                "(Ljava/lang/String;)Ljava/lang/reflect/Field;",
+                &[jname.as_obj().into()],
+            );
+
+            match res {
+                Ok(field) => {
+                    let field = self.auto_local(field.l()?);
+                    let modifiers = self
+                        .call_method(field.as_obj(), "getModifiers", "()I", &[])?
+                        .i()?;
+                    if !Modifiers::from_bits(modifiers).intersects(skip_modifiers) {
+                        return Ok(field.forget());
+                    }
+                }
+                Err(Error::JavaException) => {
+                    let exception = self.auto_local(self.exception_occurred()?);
+                    self.exception_clear()?;
+                    if !self.is_instance_of(exception.as_obj(), "java/lang/NoSuchFieldException")? {
+                        self.throw(JThrowable::from(exception.as_obj()))?;
+                        return Err(Error::JavaException);
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+
+            let superclass = self.get_superclass(&current)?;
+            current = self.auto_local(superclass);
+        }
+
+        Err(Error::FieldNotFound {
+            name: name.to_owned(),
+            sig: sig.to_owned(),
+        })
+    }
+
+    /// Get a field with the memory semantics of a java `volatile` read.
+    ///
+    /// The JNI field access functions behind `get_field` and `set_field` don't
+    /// guarantee the semantics of the java memory model for `volatile`
+    /// fields, so a value written by another thread might not be seen, or be
+    /// seen out of order with other writes. This method reads the field
+    /// through reflection instead (`Field#getInt` and the like), which honours
+    /// `volatile`. Use it for fields that are shared between threads without
+    /// a lock.
+    ///
+    /// The reflective `Field` is looked up once for each class, by identity,
+    /// field name and type, and the 256 most recent ones are cached. Every
+    /// call still goes through a reflective call though, which makes it much
+    /// slower than `get_field`.
+    ///
+    /// Non-public fields are made accessible with `Field#setAccessible`. That
+    /// is a caller-sensitive method, so since Java 9 it only succeeds when
+    /// there is a java caller on the stack, i.e. in a native method called
+    /// from java, but not in a native thread attached with `JavaVM`.
+    ///
+    /// Returns `Err` with the kind `FieldNotFound` if the field doesn't exist
+    /// or doesn't have the type `ty`.
+    pub fn get_volatile_field<O, S, T>(&self, obj: O, name: S, ty: T) -> Result<JValue<'a>>
+    where
+        O: Into<JObject<'a>>,
+        S: AsRef<str>,
+        T: AsRef<str>,
+    {
+        let obj = obj.into();
+        non_null!(obj, "get_volatile_field obj argument");
+        let parsed = JavaType::from_str(ty.as_ref())?;
+        let (type_name, type_sig) = reflected_field_accessor(&parsed)?;
+
+        let field = self.get_volatile_field_handle(obj, name.as_ref(), ty.as_ref())?;
+        // The result doesn't borrow from the field, which outlives the call
+        let field_obj = JObject::from(field.as_obj().into_inner());
+        self.call_method(
+            field_obj,
+            format!("get{}", type_name),
+            format!("(Ljava/lang/Object;){}", type_sig),
+            &[obj.into()],
+        )
+    }
+
+    /// Set a field with the memory semantics of a java `volatile` write. Does
+    /// the same lookups as `get_volatile_field` and ensures that the type
+    /// matches the given value.
+    pub fn set_volatile_field<O, S, T>(&self, obj: O, name: S, ty: T, val: JValue) -> Result<()>
+    where
+        O: Into<JObject<'a>>,
+        S: AsRef<str>,
+        T: AsRef<str>,
+    {
+        let obj = obj.into();
+        non_null!(obj, "set_volatile_field obj argument");
+        let parsed = JavaType::from_str(ty.as_ref())?;
+        let (type_name, type_sig) = reflected_field_accessor(&parsed)?;
+
+        let matches = match parsed {
+            JavaType::Primitive(p) => val.primitive_type() == Some(p),
+            _ => val.primitive_type().is_none(),
+        };
+        if !matches {
+            return Err(Error::WrongJValueType(val.type_name(), "see java field"));
+        }
+
+        let field = self.get_volatile_field_handle(obj, name.as_ref(), ty.as_ref())?;
+        self.call_method(
+            field.as_obj(),
+            format!("set{}", type_name),
+            format!("(Ljava/lang/Object;{})V", type_sig),
+            &[obj.into(), val],
+        )?;
+        Ok(())
+    }
+
+    /// Looks up the accessible reflective `Field` for `get_volatile_field` and
+    /// `set_volatile_field`, consulting the cache first.
+    fn get_volatile_field_handle(&self, obj: JObject, name: &str, ty: &str) -> Result<GlobalRef> {
+        let class = self.auto_local(self.get_object_class(obj)?);
+        let key = (
+            self.identity_hash_code(class.as_obj())?,
+            (name.to_owned(), ty.to_owned()),
+        );
+
+        let cached = VOLATILE_FIELDS
+            .lock()
+            .unwrap()
+            .get(self, &key, class.as_obj())?;
+        if let Some(field) = cached {
+            return Ok(field);
+        }
+
+        let field =
+            self.auto_local(self.find_reflected_field(&class, name, ty, Modifiers::NONE)?);
+        let field_ty = self
+            .call_method(field.as_obj(), "getType", "()Ljava/lang/Class;", &[])?
+            .l()?;
+        let field_ty = self.auto_local(field_ty);
+        if class_name_to_signature(&self.get_class_name(field_ty.as_obj())?) != ty {
+            return Err(Error::FieldNotFound {
+                name: name.to_owned(),
+                sig: ty.to_owned(),
+            });
+        }
+        if !self.is_public_member(field.as_obj())? {
+            self.call_method(
+                field.as_obj(),
+                "setAccessible",
+                "(Z)V",
+                &[JValue::from(true)],
+            )?;
+        }
+
+        let field = self.new_global_ref(&field)?;
+        let global = self.new_global_ref(&class)?;
+
+        let mut fields = VOLATILE_FIELDS.lock().unwrap();
+        // Another thread may have looked up the field in the meantime
+        if let Some(cached) = fields.get(self, &key, class.as_obj())? {
+            return Ok(cached);
+        }
+        fields.insert(key, global, field.clone());
+        Ok(field)
+    }
+
+    /// Checks whether both the reflective member and its declaring class are
+    /// public.
+    fn is_public_member(&self, member: JObject) -> Result<bool> {
+        let modifiers = self.call_method(member, "getModifiers", "()I", &[])?.i()?;
+        let class = self
+            .call_method(member, "getDeclaringClass", "()Ljava/lang/Class;", &[])?
+            .l()?;
+        let class = self.auto_local(class);
+        let class_modifiers = self
+            .call_method(class.as_obj(), "getModifiers", "()I", &[])?
+            .i()?;
+        Ok(Modifiers::from_bits(modifiers & class_modifiers).intersects(Modifiers::PUBLIC))
+    }
+
+    /// Returns the name of the class for the labels of the call metrics, only
+    /// looking it up the first time a method of the class is called.
+    #[cfg(feature = "metrics")]
+    fn metrics_class_name(&self, class: JObject) -> Result<Arc<str>> {
+        let key = (self.identity_hash_code(class)?, ());
+        if let Some(name) = METRICS_CLASS_NAMES.lock().unwrap().get(self, &key, class)? {
+            return Ok(name);
+        }
+
+        let name: Arc<str> = Arc::from(self.get_class_name(class)?);
+        let global = self.new_global_ref(class)?;
+
+        let mut names = METRICS_CLASS_NAMES.lock().unwrap();
+        // Another thread may have called a method of the class in the meantime
+        if let Some(cached) = names.get(self, &key, class)? {
+            return Ok(cached);
+        }
+        names.insert(key, global, name.clone());
+        Ok(name)
+    }
+
+    /// Returns the binary name of the class, as returned by `Class#getName`.
+    fn get_class_name(&self, class: JObject) -> Result<String> {
+        // This doesn't go through `call_method`, which uses it for recording metrics
+        let class_class = self.auto_local(self.find_class("java/lang/Class")?);
+        let name = self
+            .call_method_unchecked(
+                class,
+                (&class_class, "getName", "()Ljava/lang/String;"),
+                JavaType::Object("java/lang/String".to_owned()),
+                &[],
+            )?
+            .l()?;
+        let name = self.auto_local(name);
+        let name: String = self.get_string(name.as_obj().into())?.into();
+        Ok(name)
+    }
+
     /// Get a static field without checking the provided type against the actual
     /// field.
     pub fn get_static_field_unchecked<'c, 'f, T, U>(
@@ -1762,8 +4531,45 @@ impl<'a> JNIEnv<'a> {
         self.get_static_field_unchecked(class, (class, field, sig), ty)
     }
 
-    /// Set a static field. Requires a class lookup and a field id lookup internally.
-    pub fn set_static_field<'c, 'f, T, U>(&self, class: T, field: U, value: JValue) -> Result<()>
+    /// Set a static field. Requires a class lookup and a field id lookup
+    /// internally, and ensures that the type matches the given value.
+    pub fn set_static_field<'c, T, U, V>(
+        &self,
+        class: T,
+        field: U,
+        sig: V,
+        value: JValue,
+    ) -> Result<()>
+    where
+        T: Desc<'a, JClass<'c>>,
+        U: Into<JNIString>,
+        V: Into<JNIString> + AsRef<str>,
+    {
+        let matches = match JavaType::from_str(sig.as_ref())? {
+            JavaType::Object(_) | JavaType::Array(_) => value.primitive_type().is_none(),
+            JavaType::Primitive(p) => value.primitive_type() == Some(p),
+            JavaType::Method(_) => false,
+        };
+        if !matches {
+            return Err(Error::WrongJValueType(value.type_name(), "see java field"));
+        }
+
+        let class = class.lookup(self)?;
+
+        self.set_static_field_unchecked(class, (class, field, sig), value)
+    }
+
+    /// Set a static field without checking the value against the field's
+    /// type, calling the `SetStatic<Type>Field` function for the variant of
+    /// the value. The field is usually a `JStaticFieldID` looked up
+    /// beforehand. The value must match the field's type, or the behavior is
+    /// undefined.
+    pub fn set_static_field_unchecked<'c, 'f, T, U>(
+        &self,
+        class: T,
+        field: U,
+        value: JValue,
+    ) -> Result<()>
     where
         T: Desc<'a, JClass<'c>>,
         U: Desc<'a, JStaticFieldID<'f>>,
@@ -1791,7 +4597,7 @@ impl<'a> JNIEnv<'a> {
             JValue::Double(v) => {
                 jni_unchecked!(self.internal, SetStaticDoubleField, class, field, v)
             }
-            JValue::Void => return Err(Error::WrongJValueType("void", "?")),
+            JValue::Void => return Err(Error::WrongJValueType("void", "see java field")),
         }
 
         Ok(())
@@ -1902,17 +4708,47 @@ impl<'a> JNIEnv<'a> {
         Ok(mbox.into_inner().unwrap())
     }
 
+    /// Enter the monitor of a java object, like at the start of a
+    /// `synchronized` block. The monitor is reentrant, and each call must be
+    /// matched by a call to `monitor_exit` on the same thread.
+    ///
+    /// Prefer to use [`lock_obj`](struct.JNIEnv.html#method.lock_obj), which
+    /// can't leave the monitor locked by mistake.
+    pub fn monitor_enter<O>(&self, obj: O) -> Result<()>
+    where
+        O: Into<JObject<'a>>,
+    {
+        let obj = obj.into();
+        non_null!(obj, "monitor_enter obj argument");
+        let res = jni_non_void_call!(self.internal, MonitorEnter, obj.into_inner());
+        jni_error_code_to_result(res)
+    }
+
+    /// Exit the monitor of a java object entered with `monitor_enter`.
+    ///
+    /// Returns `Err` with a pending `IllegalMonitorStateException` if the
+    /// current thread doesn't own the monitor.
+    pub fn monitor_exit<O>(&self, obj: O) -> Result<()>
+    where
+        O: Into<JObject<'a>>,
+    {
+        let obj = obj.into();
+        non_null!(obj, "monitor_exit obj argument");
+        let res = jni_non_void_call!(self.internal, MonitorExit, obj.into_inner());
+        jni_error_code_to_result(res)
+    }
+
     /// Lock a Java object. The MonitorGuard that this returns is responsible
     /// for ensuring that it gets unlocked.
     pub fn lock_obj<O>(&self, obj: O) -> Result<MonitorGuard<'a>>
     where
         O: Into<JObject<'a>>,
     {
-        let inner = obj.into().into_inner();
-        let _ = jni_unchecked!(self.internal, MonitorEnter, inner);
+        let obj = obj.into();
+        self.monitor_enter(obj)?;
 
         Ok(MonitorGuard {
-            obj: inner,
+            obj: obj.into_inner(),
             env: self.internal,
             life: Default::default(),
         })
@@ -1931,11 +4767,61 @@ impl<'a> JNIEnv<'a> {
         unsafe { JavaVM::from_raw(raw) }
     }
 
+    /// Set a rust closure as the handler for exceptions that terminate the
+    /// given `java.lang.Thread`, with `Thread#setUncaughtExceptionHandler`.
+    /// This lets native code log or capture the failures of java threads it
+    /// started.
+    ///
+    /// The closure is called on the terminating thread, which is attached to
+    /// the JVM already, with the thread and the uncaught exception. A panic in
+    /// the closure is caught and logged, as it can't unwind into java code.
+    ///
+    /// The closure is owned by a java handler object, and is dropped on a
+    /// daemon java thread once that object is garbage collected after the
+    /// thread has terminated or the handler has been replaced.
+    ///
+    /// The handler class is defined on the first call, in a new class loader
+    /// owned by this library, so that it doesn't clash with the handler class
+    /// of other native libraries using this crate in the same JVM.
+    pub fn set_uncaught_exception_handler<O, F>(&self, thread: O, handler: F) -> Result<()>
+    where
+        O: Into<JObject<'a>>,
+        F: for<'b> Fn(&JNIEnv<'b>, JObject<'b>, JThrowable<'b>) + Send + Sync + 'static,
+    {
+        let thread = thread.into();
+        non_null!(thread, "set_uncaught_exception_handler thread argument");
+
+        let handler = self.auto_local(exception_handler::new_handler(self, Box::new(handler))?);
+        self.call_method(
+            thread,
+            "setUncaughtExceptionHandler",
+            "(Ljava/lang/Thread$UncaughtExceptionHandler;)V",
+            &[handler.as_obj().into()],
+        )?;
+        Ok(())
+    }
+
     /// Ensures that at least a given number of local references can be created
-    /// in the current thread.
+    /// in the current local reference frame.
+    ///
+    /// Unlike [`push_local_frame`](struct.JNIEnv.html#method.push_local_frame),
+    /// this doesn't start a new frame, so the references created afterwards
+    /// stay alive until the native method returns or the current frame is
+    /// popped. Use it when all the references are needed at once, such as
+    /// when converting a collection, and a frame (or
+    /// [`with_local_frame`](struct.JNIEnv.html#method.with_local_frame)) when
+    /// the references can be freed together once a piece of work is done.
+    ///
+    /// Returns `Err` with the kind `JavaException` and a pending
+    /// `OutOfMemoryError` if the capacity can't be reserved, and `Err` with
+    /// the kind `JniCall` if the capacity is negative.
     pub fn ensure_local_capacity(&self, capacity: jint) -> Result<()> {
-        jni_void_call!(self.internal, EnsureLocalCapacity, capacity);
-        Ok(())
+        if capacity < 0 {
+            // A negative capacity is a fatal error with -Xcheck:jni
+            return Err(Error::JniCall(JniError::InvalidArguments));
+        }
+        let res = jni_non_void_call!(self.internal, EnsureLocalCapacity, capacity);
+        jni_error_code_to_result(res)
     }
 
     /// Bind function pointers to native methods of class
@@ -2081,6 +4967,8 @@ impl<'a> JNIEnv<'a> {
     /// The result is valid until the corresponding AutoPrimitiveArray object goes out of scope,
     /// when the release happens automatically according to the mode parameter.
     ///
+    /// No other JNI functions may be called while the AutoPrimitiveArray is alive.
+    ///
     /// Given that Critical sections must be as short as possible, and that they come with a
     /// number of important restrictions (see GetPrimitiveArrayCritical JNI doc), use this
     /// wrapper wisely, to avoid holding the array longer that strictly necessary.
@@ -2102,6 +4990,8 @@ impl<'a> JNIEnv<'a> {
         mode: ReleaseMode,
     ) -> Result<AutoPrimitiveArray> {
         non_null!(array, "get_primitive_array_critical array argument");
+        // No JNI calls are allowed in the critical region, so read the length first
+        let len = self.get_array_length(array)?;
         let mut is_copy: jboolean = 0xff;
         // Even though this method may throw OoME, use `jni_unchecked`
         // instead of `jni_non_null_call` to remove (a slight) overhead
@@ -2114,10 +5004,75 @@ impl<'a> JNIEnv<'a> {
             array,
             &mut is_copy
         );
-        AutoPrimitiveArray::new(self, array.into(), ptr, mode, is_copy == sys::JNI_TRUE)
+        AutoPrimitiveArray::new(self, array.into(), ptr, len, mode, is_copy == sys::JNI_TRUE)
     }
 }
 
+/// Checks that a method signature has a `void` return type.
+fn ensure_void_return(sig: &str) -> Result<()> {
+    let parsed = TypeSignature::from_str(sig)?;
+    if parsed.ret != JavaType::Primitive(Primitive::Void) {
+        return Err(Error::NonVoidMethod(parsed));
+    }
+    Ok(())
+}
+
+/// Checks that a method signature has a `boolean` return type.
+fn ensure_bool_return(sig: &str) -> Result<()> {
+    let parsed = TypeSignature::from_str(sig)?;
+    if parsed.ret != JavaType::Primitive(Primitive::Boolean) {
+        return Err(Error::WrongJValueType("bool", "see method signature"));
+    }
+    Ok(())
+}
+
+/// Converts a `boolean` result to a `bool`, treating any non-zero value as
+/// `true`.
+fn bool_result(val: JValue) -> Result<bool> {
+    match val {
+        JValue::Bool(b) => Ok(b != sys::JNI_FALSE),
+        _ => val.z(),
+    }
+}
+
+/// Converts a class name returned by `Class#getName` to a type signature.
+fn class_name_to_signature(name: &str) -> String {
+    match name {
+        "boolean" => "Z".to_owned(),
+        "byte" => "B".to_owned(),
+        "char" => "C".to_owned(),
+        "double" => "D".to_owned(),
+        "float" => "F".to_owned(),
+        "int" => "I".to_owned(),
+        "long" => "J".to_owned(),
+        "short" => "S".to_owned(),
+        "void" => "V".to_owned(),
+        array if array.starts_with('[') => array.replace('.', "/"),
+        object => format!("L{};", object.replace('.', "/")),
+    }
+}
+
+/// Returns the type name used in the names of the `java.lang.reflect.Field`
+/// accessors for the type (e.g. `Int` for `getInt`), and the signature of the
+/// accessed value.
+fn reflected_field_accessor(ty: &JavaType) -> Result<(&'static str, &'static str)> {
+    Ok(match ty {
+        JavaType::Primitive(Primitive::Boolean) => ("Boolean", "Z"),
+        JavaType::Primitive(Primitive::Byte) => ("Byte", "B"),
+        JavaType::Primitive(Primitive::Char) => ("Char", "C"),
+        JavaType::Primitive(Primitive::Short) => ("Short", "S"),
+        JavaType::Primitive(Primitive::Int) => ("Int", "I"),
+        JavaType::Primitive(Primitive::Long) => ("Long", "J"),
+        JavaType::Primitive(Primitive::Float) => ("Float", "F"),
+        JavaType::Primitive(Primitive::Double) => ("Double", "D"),
+        JavaType::Object(_) | JavaType::Array(_) => ("", "Ljava/lang/Object;"),
+        JavaType::Primitive(Primitive::Void) => {
+            return Err(Error::WrongJValueType("void", "see java field"))
+        }
+        JavaType::Method(_) => return Err(Error::WrongJValueType("Method", "see java field")),
+    })
+}
+
 /// Native method descriptor.
 pub struct NativeMethod {
     /// Name of method.
@@ -2143,8 +5098,8 @@ pub struct MonitorGuard<'a> {
 impl<'a> Drop for MonitorGuard<'a> {
     fn drop(&mut self) {
         let res: Result<()> = catch!({
-            jni_unchecked!(self.env, MonitorExit, self.obj);
-            Ok(())
+            let res = jni_unchecked!(self.env, MonitorExit, self.obj);
+            jni_error_code_to_result(res)
         });
 
         if let Err(e) = res {
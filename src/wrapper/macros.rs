@@ -15,8 +15,9 @@ macro_rules! jni_non_void_call {
         log::trace!("calling checked jni method: {}", stringify!($name));
 
         let res = unsafe {
-            jni_method!($jnienv, $name)($jnienv, $($args),*)
+            jni_method!($jnienv, $name)($jnienv, $(debug_ref_arg!($args)),*)
         };
+        debug_ref_result!(&res);
 
         check_exception!($jnienv);
         res
@@ -40,7 +41,7 @@ macro_rules! jni_void_call {
         log::trace!("calling checked jni method: {}", stringify!($name));
 
         unsafe {
-            jni_method!($jnienv, $name)($jnienv, $($args),*)
+            jni_method!($jnienv, $name)($jnienv, $(debug_ref_arg!($args)),*)
         };
 
         check_exception!($jnienv);
@@ -53,12 +54,43 @@ macro_rules! jni_unchecked {
     ( $jnienv:expr, $name:tt $(, $args:expr )* ) => ({
         log::trace!("calling unchecked jni method: {}", stringify!($name));
 
-        unsafe {
-            jni_method!($jnienv, $name)($jnienv, $($args),*)
-        }
+        let res = unsafe {
+            jni_method!($jnienv, $name)($jnienv, $(debug_ref_arg!($args)),*)
+        };
+        debug_ref_result!(&res);
+        res
     })
 }
 
+// With the `debug-refs` feature, debug builds check the arguments of JNI calls
+// against the deleted local references, and forget the deletions of the slots
+// reused for the returned references.
+#[cfg(all(feature = "debug-refs", debug_assertions))]
+macro_rules! debug_ref_arg {
+    ( $arg:expr ) => {
+        $crate::wrapper::debug_refs::check_arg($arg)
+    };
+}
+
+#[cfg(not(all(feature = "debug-refs", debug_assertions)))]
+macro_rules! debug_ref_arg {
+    ( $arg:expr ) => {
+        $arg
+    };
+}
+
+#[cfg(all(feature = "debug-refs", debug_assertions))]
+macro_rules! debug_ref_result {
+    ( $res:expr ) => {
+        $crate::wrapper::debug_refs::check_result($res)
+    };
+}
+
+#[cfg(not(all(feature = "debug-refs", debug_assertions)))]
+macro_rules! debug_ref_result {
+    ( $res:expr ) => {};
+}
+
 macro_rules! jni_method {
     ( $jnienv:expr, $name:tt ) => {{
         log::trace!("looking up jni method {}", stringify!($name));
@@ -1,7 +1,7 @@
 use crate::sys::jsize;
 use log::error;
 
-use std::ptr::NonNull;
+use std::{ptr::NonNull, slice};
 
 use crate::objects::release_mode::ReleaseMode;
 use crate::sys::{jboolean, jbyte, jchar, jdouble, jfloat, jint, jlong, jshort};
@@ -64,9 +64,14 @@ type_array!(jdouble, GetDoubleArrayElements, ReleaseDoubleArrayElements);
 ///
 /// AutoArray provides automatic array release through a call to appropriate
 /// Release<Type>ArrayElements when it goes out of scope.
+///
+/// As dropping can't return an error, a release that fails or leaves a new
+/// pending exception is only logged. Use `commit` to get such errors reported
+/// before the array is dropped.
 pub struct AutoArray<'a: 'b, 'b, T: TypeArray> {
     obj: JObject<'a>,
     ptr: NonNull<T>,
+    len: jsize,
     mode: ReleaseMode,
     is_copy: bool,
     env: &'b JNIEnv<'a>,
@@ -74,9 +79,11 @@ pub struct AutoArray<'a: 'b, 'b, T: TypeArray> {
 
 impl<'a, 'b, T: TypeArray> AutoArray<'a, 'b, T> {
     pub(crate) fn new(env: &'b JNIEnv<'a>, obj: JObject<'a>, mode: ReleaseMode) -> Result<Self> {
+        let len = env.get_array_length(*obj)?;
         let mut is_copy: jboolean = 0xff;
         Ok(AutoArray {
             obj,
+            len,
             ptr: {
                 let ptr = T::get(env, obj, &mut is_copy)?;
                 NonNull::new(ptr).ok_or(Error::NullPtr("Non-null ptr expected"))?
@@ -92,13 +99,36 @@ impl<'a, 'b, T: TypeArray> AutoArray<'a, 'b, T> {
         self.ptr.as_ptr()
     }
 
-    /// Commits the changes to the array, if it is a copy
+    /// Get the elements of the array as a slice
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len as usize) }
+    }
+
+    /// Get the elements of the array as a mutable slice. The changes are
+    /// written back to the java array according to the release mode, or on
+    /// `commit`.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len as usize) }
+    }
+
+    /// Commits the changes to the array, if it is a copy.
+    ///
+    /// Returns `Err` with the kind `JavaException` if the release leaves a
+    /// pending exception.
     pub fn commit(&self) -> Result<()> {
         self.release_array_elements(sys::JNI_COMMIT)
     }
 
     fn release_array_elements(&self, mode: i32) -> Result<()> {
-        T::release(self.env, self.obj, self.ptr, mode)
+        let internal = self.env.get_native_interface();
+        // The array may be released while an earlier exception is pending,
+        // e.g. when an error is being propagated; don't report that one.
+        let pending = jni_unchecked!(internal, ExceptionCheck) == sys::JNI_TRUE;
+        T::release(self.env, self.obj, self.ptr, mode)?;
+        if !pending {
+            check_exception!(internal);
+        }
+        Ok(())
     }
 
     /// Don't commit the changes to the array on release (if it is a copy).
@@ -116,7 +146,7 @@ impl<'a, 'b, T: TypeArray> AutoArray<'a, 'b, T> {
 
     /// Returns the array size
     pub fn size(&self) -> Result<jsize> {
-        self.env.get_array_length(*self.obj)
+        Ok(self.len)
     }
 }
 
@@ -125,6 +155,9 @@ impl<'a, 'b, T: TypeArray> Drop for AutoArray<'a, 'b, T> {
         let res = self.release_array_elements(self.mode as i32);
         match res {
             Ok(()) => {}
+            Err(Error::JavaException) => {
+                error!("java exception pending after releasing array")
+            }
             Err(e) => error!("error releasing array: {:#?}", e),
         }
     }
@@ -13,9 +13,15 @@ use std::ptr::NonNull;
 ///
 /// AutoPrimitiveArray provides automatic array release through a call to
 /// ReleasePrimitiveArrayCritical when it goes out of scope.
+///
+/// While it's alive, the current thread is in a JNI critical region: no other
+/// JNI functions may be called, and the thread must not block on other java
+/// threads, as the garbage collector may be paused until the array is
+/// released.
 pub struct AutoPrimitiveArray<'a: 'b, 'b> {
     obj: JObject<'a>,
     ptr: NonNull<c_void>,
+    len: jsize,
     mode: ReleaseMode,
     is_copy: bool,
     env: &'b JNIEnv<'a>,
@@ -26,12 +32,14 @@ impl<'a, 'b> AutoPrimitiveArray<'a, 'b> {
         env: &'b JNIEnv<'a>,
         obj: JObject<'a>,
         ptr: *mut c_void,
+        len: jsize,
         mode: ReleaseMode,
         is_copy: bool,
     ) -> Result<Self> {
         Ok(AutoPrimitiveArray {
             obj,
             ptr: NonNull::new(ptr).ok_or(Error::NullPtr("Non-null ptr expected"))?,
+            len,
             mode,
             is_copy,
             env,
@@ -67,9 +75,10 @@ impl<'a, 'b> AutoPrimitiveArray<'a, 'b> {
         self.is_copy
     }
 
-    /// Returns the array size
+    /// Returns the array size. The size is read before entering the critical
+    /// region, so this doesn't call into JNI.
     pub fn size(&self) -> Result<jsize> {
-        self.env.get_array_length(*self.obj)
+        Ok(self.len)
     }
 }
 
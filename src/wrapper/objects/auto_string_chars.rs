@@ -0,0 +1,84 @@
+use std::{ops::Deref, ptr::NonNull, slice};
+
+use log::debug;
+
+use crate::{
+    errors::*,
+    objects::JString,
+    sys::{jchar, jsize},
+    JNIEnv,
+};
+
+/// Auto-release wrapper for the UTF-16 code units of a java string, as
+/// returned by `GetStringCritical`. The code units can be accessed through the
+/// `Deref` impl to `[jchar]`.
+///
+/// The characters are released with `ReleaseStringCritical` when the wrapper
+/// goes out of scope.
+///
+/// While it's alive, the current thread is in a JNI critical region: no other
+/// JNI functions may be called, and the thread must not block on other java
+/// threads, as the garbage collector may be paused until the string is
+/// released. See `AutoPrimitiveArray` for the same restrictions on arrays.
+pub struct AutoStringChars<'a: 'b, 'b> {
+    string: JString<'a>,
+    ptr: NonNull<jchar>,
+    len: jsize,
+    is_copy: bool,
+    env: &'b JNIEnv<'a>,
+}
+
+impl<'a, 'b> AutoStringChars<'a, 'b> {
+    pub(crate) fn new(
+        env: &'b JNIEnv<'a>,
+        string: JString<'a>,
+        ptr: *const jchar,
+        len: jsize,
+        is_copy: bool,
+    ) -> Result<Self> {
+        Ok(AutoStringChars {
+            string,
+            ptr: NonNull::new(ptr as *mut jchar).ok_or(Error::NullPtr("Non-null ptr expected"))?,
+            len,
+            is_copy,
+            env,
+        })
+    }
+
+    /// Get a pointer to the code units.
+    pub fn as_ptr(&self) -> *const jchar {
+        self.ptr.as_ptr()
+    }
+
+    /// Indicates if the code units are a copy or not
+    pub fn is_copy(&self) -> bool {
+        self.is_copy
+    }
+}
+
+impl<'a, 'b> Deref for AutoStringChars<'a, 'b> {
+    type Target = [jchar];
+
+    fn deref(&self) -> &[jchar] {
+        // The length is read before entering the critical region
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len as usize) }
+    }
+}
+
+impl<'a, 'b> Drop for AutoStringChars<'a, 'b> {
+    fn drop(&mut self) {
+        let res: Result<()> = catch!({
+            jni_unchecked!(
+                self.env.get_native_interface(),
+                ReleaseStringCritical,
+                self.string.into_inner(),
+                self.ptr.as_ptr()
+            );
+            Ok(())
+        });
+        match res {
+            Ok(()) => {}
+            Err(e) => debug!("error releasing string chars: {:#?}", e),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use crate::{
+    errors::*,
+    objects::{AutoLocal, JObject},
+    JNIEnv,
+};
+
+/// A java `byte[]` argument of a method called through the `call!` macro.
+///
+/// It's created from either an existing array, or from a byte slice or vector,
+/// in which case a new java array is created for the call and deleted when the
+/// argument gets dropped after the call returns.
+pub enum ByteArrayArg<'a: 'b, 'b> {
+    /// An existing array, which is passed as it is.
+    Array(JObject<'a>),
+    /// An array created for the call.
+    Created(AutoLocal<'a, 'b>),
+}
+
+impl<'a: 'b, 'b> ByteArrayArg<'a, 'b> {
+    /// Returns the array to pass to java.
+    pub fn as_obj(&self) -> JObject<'a> {
+        match self {
+            ByteArrayArg::Array(obj) => *obj,
+            ByteArrayArg::Created(local) => local.as_obj(),
+        }
+    }
+}
+
+/// Conversion into a `ByteArrayArg`, implemented for byte slices and vectors,
+/// and for anything that converts into a `JObject`.
+pub trait IntoByteArrayArg<'a> {
+    /// Converts the value, creating a java array if needed.
+    fn into_byte_array_arg<'b>(self, env: &'b JNIEnv<'a>) -> Result<ByteArrayArg<'a, 'b>>;
+}
+
+impl<'a, T> IntoByteArrayArg<'a> for T
+where
+    T: Into<JObject<'a>>,
+{
+    fn into_byte_array_arg<'b>(self, _env: &'b JNIEnv<'a>) -> Result<ByteArrayArg<'a, 'b>> {
+        Ok(ByteArrayArg::Array(self.into()))
+    }
+}
+
+impl<'a> IntoByteArrayArg<'a> for &[u8] {
+    fn into_byte_array_arg<'b>(self, env: &'b JNIEnv<'a>) -> Result<ByteArrayArg<'a, 'b>> {
+        let array = env.byte_array_from_slice(self)?;
+        Ok(ByteArrayArg::Created(env.auto_local(array)))
+    }
+}
+
+impl<'a> IntoByteArrayArg<'a> for &Vec<u8> {
+    fn into_byte_array_arg<'b>(self, env: &'b JNIEnv<'a>) -> Result<ByteArrayArg<'a, 'b>> {
+        self.as_slice().into_byte_array_arg(env)
+    }
+}
+
+impl<'a> IntoByteArrayArg<'a> for Vec<u8> {
+    fn into_byte_array_arg<'b>(self, env: &'b JNIEnv<'a>) -> Result<ByteArrayArg<'a, 'b>> {
+        self.as_slice().into_byte_array_arg(env)
+    }
+}
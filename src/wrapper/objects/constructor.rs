@@ -0,0 +1,43 @@
+use crate::{
+    errors::*,
+    objects::{GlobalRef, JMethodID, JObject, JValue, SendMethodId},
+    signature::TypeSignature,
+    sys::jmethodID,
+    JNIEnv,
+};
+
+/// A constructor of a class, which is resolved once and can then be used to
+/// create objects from any attached thread. This gets returned from
+/// `JNIEnv::get_constructor`.
+///
+/// Holds a global reference to the class, which keeps the class from being
+/// unloaded and so the constructor id valid.
+pub struct Constructor {
+    class: GlobalRef,
+    id: SendMethodId,
+    sig: TypeSignature,
+}
+
+impl Constructor {
+    pub(crate) fn new(class: GlobalRef, id: jmethodID, sig: TypeSignature) -> Self {
+        Constructor {
+            class,
+            id: SendMethodId(id),
+            sig,
+        }
+    }
+
+    /// Create a new object with the constructor. Checks that the number of
+    /// arguments matches the signature, like `JNIEnv::new_object`.
+    pub fn new_instance<'a>(&self, env: &JNIEnv<'a>, args: &[JValue]) -> Result<JObject<'a>> {
+        if self.sig.args.len() != args.len() {
+            return Err(Error::InvalidArgList(self.sig.clone()));
+        }
+        env.new_object_unchecked(&self.class, JMethodID::from(self.id.0), args)
+    }
+
+    /// Get the class of the objects created by the constructor.
+    pub fn class(&self) -> &GlobalRef {
+        &self.class
+    }
+}
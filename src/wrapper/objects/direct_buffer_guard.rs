@@ -0,0 +1,44 @@
+use std::{ops::Deref, slice};
+
+use crate::objects::{GlobalRef, JObject};
+
+/// Guard for the memory of a direct `java.nio.ByteBuffer`. This gets returned
+/// from the `call_method_direct_buffer` method.
+///
+/// The buffer object is pinned with a global reference for as long as the guard
+/// lives, so the memory it points to can't be freed by the garbage collector
+/// while it's being read. The guard can be dereferenced to a `&[u8]`.
+pub struct DirectBufferGuard {
+    buffer: GlobalRef,
+    ptr: *mut u8,
+    len: usize,
+}
+
+impl DirectBufferGuard {
+    /// Creates a new guard for the memory of a direct buffer. This assumes that
+    /// `ptr` and `len` describe the memory of the buffer held by `buffer`.
+    pub(crate) fn new(buffer: GlobalRef, ptr: *mut u8, len: usize) -> Self {
+        DirectBufferGuard { buffer, ptr, len }
+    }
+
+    /// Get the contents of the buffer.
+    pub fn as_slice(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.ptr, self.len) }
+    }
+
+    /// Get the buffer object.
+    ///
+    /// This borrows the guard and prevents it from being dropped as long as the
+    /// JObject sticks around.
+    pub fn as_obj(&self) -> JObject<'_> {
+        self.buffer.as_obj()
+    }
+}
+
+impl Deref for DirectBufferGuard {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
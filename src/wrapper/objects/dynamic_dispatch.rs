@@ -0,0 +1,116 @@
+use std::sync::Mutex;
+
+use crate::{
+    errors::*,
+    objects::{IdentityCache, JMethodID, JObject, JValue, SendMethodId},
+    signature::TypeSignature,
+    JNIEnv,
+};
+
+const DEFAULT_CAPACITY: usize = 16;
+
+/// A call site for a method with a given name and signature, which may be
+/// called on objects of unrelated classes, e.g. to call a `close()` method
+/// which is not declared by a common interface.
+///
+/// The method id is looked up in the runtime class of each object, and cached
+/// per class, so calling the method again on an object of an already seen
+/// class doesn't have to look it up again. The classes are found in the cache
+/// by their identity hash codes. The cache holds a limited number of classes,
+/// evicting the oldest one once it's full.
+///
+/// The dispatcher can be shared between threads.
+///
+/// # Example
+/// ```rust,ignore
+/// let close = DynamicDispatch::new("close", "()V")?;
+/// for resource in resources {
+///     close.call(&env, resource, &[])?;
+/// }
+/// ```
+pub struct DynamicDispatch {
+    name: String,
+    sig: String,
+    parsed: TypeSignature,
+    classes: Mutex<IdentityCache<(), SendMethodId>>,
+}
+
+impl DynamicDispatch {
+    /// Creates a new call site for the method with the given name and
+    /// signature, caching the method ids of up to 16 classes.
+    pub fn new(name: &str, sig: &str) -> Result<Self> {
+        Self::with_capacity(name, sig, DEFAULT_CAPACITY)
+    }
+
+    /// Creates a new call site caching the method ids of up to `capacity`
+    /// classes.
+    ///
+    /// Returns `Err` with the kind `JniCall(InvalidArguments)` if `capacity`
+    /// is zero.
+    pub fn with_capacity(name: &str, sig: &str, capacity: usize) -> Result<Self> {
+        if capacity == 0 {
+            return Err(Error::JniCall(JniError::InvalidArguments));
+        }
+        Ok(DynamicDispatch {
+            name: name.to_owned(),
+            sig: sig.to_owned(),
+            parsed: TypeSignature::from_str(sig)?,
+            classes: Mutex::new(IdentityCache::new(capacity)),
+        })
+    }
+
+    /// Calls the method on an object, looking up the method id in its runtime
+    /// class if it's not cached yet. This does the same checks as
+    /// `JNIEnv::call_method`.
+    pub fn call<'a>(
+        &self,
+        env: &JNIEnv<'a>,
+        obj: JObject<'a>,
+        args: &[JValue],
+    ) -> Result<JValue<'a>> {
+        non_null!(obj, "DynamicDispatch::call obj argument");
+        if self.parsed.args.len() != args.len() {
+            return Err(Error::InvalidArgList(self.parsed.clone()));
+        }
+
+        let class = env.auto_local(env.get_object_class(obj)?);
+        let key = (env.identity_hash_code(class.as_obj())?, ());
+        let cached = self
+            .classes
+            .lock()
+            .unwrap()
+            .get(env, &key, class.as_obj())?;
+        let id = match cached {
+            Some(id) => id,
+            None => {
+                let id = env
+                    .get_method_id(&class, self.name.as_str(), self.sig.as_str())?
+                    .into_inner();
+                let global = env.new_global_ref(&class)?;
+
+                let mut classes = self.classes.lock().unwrap();
+                // Another thread may have cached the class in the meantime
+                match classes.get(env, &key, class.as_obj())? {
+                    Some(id) => id,
+                    None => {
+                        classes.insert(key, global, SendMethodId(id));
+                        SendMethodId(id)
+                    }
+                }
+            }
+        };
+
+        env.call_method_unchecked(obj, JMethodID::from(id.0), self.parsed.ret.clone(), args)
+    }
+
+    /// Returns the number of classes whose method id is cached.
+    pub fn len(&self) -> usize {
+        self.classes.lock().unwrap().len()
+    }
+
+    /// Returns `true` if no class has been dispatched to yet, or only ones
+    /// which don't have the method.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
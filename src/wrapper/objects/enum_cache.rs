@@ -0,0 +1,136 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::{
+    errors::*,
+    objects::{GlobalRef, JObject},
+    signature::JavaType,
+    JNIEnv,
+};
+
+/// A cache of the constants of a java enum, keyed by their names.
+///
+/// Each constant is read from its static field on first access and pinned with
+/// a global reference, so later lookups of the same name don't call into java.
+/// Enum constants are singletons that live as long as their class, so pinning
+/// them doesn't keep anything alive that would otherwise be collected.
+///
+/// The cache can be shared between threads, e.g. in a `static` or in the
+/// state of a native library.
+///
+/// # Example
+/// ```rust,ignore
+/// let time_units = EnumCache::new("java/util/concurrent/TimeUnit");
+/// let seconds = time_units.get(&env, "SECONDS")?;
+/// assert_eq!(time_units.name_of(&env, seconds.as_obj())?, "SECONDS");
+/// ```
+pub struct EnumCache {
+    class: String,
+    constants: Mutex<HashMap<String, GlobalRef>>,
+}
+
+impl EnumCache {
+    /// Creates a new, empty cache for the enum with the given class name,
+    /// e.g. `java/util/concurrent/TimeUnit`.
+    pub fn new<S: Into<String>>(class: S) -> Self {
+        EnumCache {
+            class: class.into(),
+            constants: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the name of the enum class.
+    pub fn class(&self) -> &str {
+        &self.class
+    }
+
+    /// Returns the constant with the given name, reading it from its static
+    /// field if it's not in the cache yet.
+    ///
+    /// Returns `Err` with the kind `FieldNotFound` if the enum has no such
+    /// constant.
+    pub fn get(&self, env: &JNIEnv, name: &str) -> Result<GlobalRef> {
+        if let Some(constant) = self.constants.lock().unwrap().get(name) {
+            return Ok(constant.clone());
+        }
+
+        // The lock isn't held during the lookup, as it calls into java
+        let class = env.auto_local(env.find_class(self.class.as_str())?);
+        let sig = format!("L{};", self.class);
+        let field = match env.get_static_field_id(&class, name, sig.as_str()) {
+            Err(Error::JavaException) => {
+                // The NoSuchFieldError
+                env.exception_clear()?;
+                return Err(Error::FieldNotFound {
+                    name: format!("{}.{}", self.class, name),
+                    sig,
+                });
+            }
+            res => res?,
+        };
+        let constant = env
+            .get_static_field_unchecked(&class, field, JavaType::Object(self.class.clone()))?
+            .l()?;
+        let constant = env.auto_local(constant);
+        let constant = env.new_global_ref(&constant)?;
+        Ok(self
+            .constants
+            .lock()
+            .unwrap()
+            .entry(name.to_owned())
+            .or_insert(constant)
+            .clone())
+    }
+
+    /// Returns the name of an enum constant. Constants in the cache are found
+    /// by comparing references, others by calling `Enum#name`, after which
+    /// they're added to the cache.
+    ///
+    /// Returns `Err` with the kind `WrongObjectClass` if `obj` is not a
+    /// constant of this enum.
+    pub fn name_of<'a, O>(&self, env: &JNIEnv<'a>, obj: O) -> Result<String>
+    where
+        O: Into<JObject<'a>>,
+    {
+        let obj = obj.into();
+        non_null!(obj, "EnumCache::name_of obj argument");
+        let cached: Vec<(String, GlobalRef)> = self
+            .constants
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, constant)| (name.clone(), constant.clone()))
+            .collect();
+        for (name, constant) in cached {
+            if env.is_same_object(obj, constant.as_obj())? {
+                return Ok(name);
+            }
+        }
+
+        let class = env.auto_local(env.find_class(self.class.as_str())?);
+        if !env.is_instance_of(obj, &class)? {
+            return Err(Error::WrongObjectClass("EnumCache::name_of obj argument"));
+        }
+        let name = env
+            .call_method(obj, "name", "()Ljava/lang/String;", &[])?
+            .l()?;
+        let name = env.auto_local(name);
+        let name: String = env.get_string(name.as_obj().into())?.into();
+        let constant = env.new_global_ref(obj)?;
+        self.constants
+            .lock()
+            .unwrap()
+            .entry(name.clone())
+            .or_insert(constant);
+        Ok(name)
+    }
+
+    /// Returns the number of constants in the cache.
+    pub fn len(&self) -> usize {
+        self.constants.lock().unwrap().len()
+    }
+
+    /// Returns `true` if the cache holds no constants.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
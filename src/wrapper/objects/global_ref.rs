@@ -1,4 +1,4 @@
-use std::{convert::From, sync::Arc};
+use std::{convert::From, fmt, sync::Arc};
 
 use log::{debug, warn};
 
@@ -20,7 +20,14 @@ use crate::{errors::Result, objects::JObject, sys, JNIEnv, JavaVM};
 /// to the Java thread (i.e., has an instance of `JNIEnv`). If the native thread is *not* attached,
 /// the `GlobalRef#drop` will print a warning and implicitly `attach` and `detach` it, which
 /// significantly affects performance.
-
+///
+/// # Thread safety
+///
+/// `GlobalRef` is `Send` and `Sync`: a global reference is valid on all
+/// threads, and is only deleted once the last clone is dropped. `as_obj` just
+/// copies the immutable reference, so it can be shared without a `Mutex`,
+/// e.g. in an `Arc` or a `static`. Calling methods on the object from several
+/// threads is subject to the thread safety of the java object itself.
 #[derive(Clone)]
 pub struct GlobalRef {
     inner: Arc<GlobalRefGuard>,
@@ -31,6 +38,7 @@ struct GlobalRefGuard {
     vm: JavaVM,
 }
 
+// Global references are valid on all threads, and the guard is never mutated.
 unsafe impl Send for GlobalRef {}
 unsafe impl Sync for GlobalRef {}
 
@@ -40,13 +48,23 @@ impl<'a> From<&'a GlobalRef> for JObject<'a> {
     }
 }
 
+impl fmt::Debug for GlobalRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("GlobalRef").field(&self.as_obj()).finish()
+    }
+}
+
 impl GlobalRef {
-    /// Creates a new wrapper for a global reference.
+    /// Creates a new wrapper for a global reference, taking ownership of it:
+    /// the reference is deleted with `DeleteGlobalRef` when the last clone of
+    /// the wrapper is dropped. This adopts global references created by other
+    /// JNI code.
     ///
     /// # Safety
     ///
-    /// Expects a valid raw global reference that should be created with `NewGlobalRef` JNI function.
-    pub(crate) unsafe fn from_raw(vm: JavaVM, raw_global_ref: sys::jobject) -> Self {
+    /// Expects a valid raw global reference that should be created with `NewGlobalRef` JNI function,
+    /// in the JVM given by `vm`. The reference must not be deleted by anything else.
+    pub unsafe fn from_raw(vm: JavaVM, raw_global_ref: sys::jobject) -> Self {
         GlobalRef {
             inner: Arc::new(GlobalRefGuard::from_raw(vm, raw_global_ref)),
         }
@@ -59,6 +77,23 @@ impl GlobalRef {
     pub fn as_obj(&self) -> JObject {
         self.inner.as_obj()
     }
+
+    /// Creates a new global reference to the same object with `NewGlobalRef`.
+    ///
+    /// Unlike `clone`, which shares the same global reference, the result is
+    /// a separate reference with its own lifetime, e.g. to hand the object
+    /// over to another part of the program.
+    pub fn new_global(&self, env: &JNIEnv) -> Result<GlobalRef> {
+        env.new_global_ref(self.as_obj())
+    }
+
+    /// Get the raw global reference, for passing to other JNI code.
+    ///
+    /// The reference stays owned by this `GlobalRef`, so it must not be
+    /// deleted, and must not be used after the last clone is dropped.
+    pub fn as_raw(&self) -> sys::jobject {
+        self.inner.as_obj().into_inner()
+    }
 }
 
 impl GlobalRefGuard {
@@ -0,0 +1,68 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+};
+
+use crate::{
+    errors::Result,
+    objects::{GlobalRef, JObject},
+    sys::jint,
+    JNIEnv,
+};
+
+/// A bounded map from java objects to values, keyed by the identity of the
+/// objects along with an extra key, such as the name of a member of a class.
+/// The entries are grouped by the identity hash codes of their objects and
+/// the extra keys, so a lookup only compares the object with the entries of
+/// the same group. The oldest entries are evicted first.
+///
+/// Each object is kept alive by a global reference for as long as its entry
+/// is in the cache.
+pub(crate) struct IdentityCache<K, V> {
+    capacity: usize,
+    // Entries with the same hash code and extra key are in insertion order
+    entries: HashMap<(jint, K), Vec<(GlobalRef, V)>>,
+    // The hash codes and extra keys of all the entries, oldest first
+    order: VecDeque<(jint, K)>,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> IdentityCache<K, V> {
+    pub(crate) fn new(capacity: usize) -> Self {
+        IdentityCache {
+            capacity,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Returns the value of an object, given its identity hash code, and the
+    /// extra key.
+    pub(crate) fn get(&self, env: &JNIEnv, key: &(jint, K), obj: JObject) -> Result<Option<V>> {
+        for (cached_obj, value) in self.entries.get(key).into_iter().flatten() {
+            if env.is_same_object(obj, cached_obj.as_obj())? {
+                return Ok(Some(value.clone()));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Adds the value of an object, evicting the oldest entry if the cache is
+    /// full.
+    pub(crate) fn insert(&mut self, key: (jint, K), obj: GlobalRef, value: V) {
+        if self.order.len() >= self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                let same_hash = self.entries.get_mut(&oldest).unwrap();
+                same_hash.remove(0);
+                if same_hash.is_empty() {
+                    self.entries.remove(&oldest);
+                }
+            }
+        }
+        self.order.push_back(key.clone());
+        self.entries.entry(key).or_default().push((obj, value));
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.order.len()
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{
+    errors::*,
+    objects::{JMethodID, JObject},
+    signature::{JavaType, Primitive},
+    JNIEnv,
+};
+
+/// Wrapper for JObjects that implement `java/util/Iterator`. Implements rust's
+/// `Iterator`, yielding the elements as local references.
+///
+/// Looks up the class and method ids on creation rather than for every
+/// element.
+pub struct JIterator<'a: 'b, 'b> {
+    internal: JObject<'a>,
+    has_next: JMethodID<'a>,
+    next: JMethodID<'a>,
+    env: &'b JNIEnv<'a>,
+}
+
+impl<'a: 'b, 'b> ::std::ops::Deref for JIterator<'a, 'b> {
+    type Target = JObject<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.internal
+    }
+}
+
+impl<'a: 'b, 'b> From<JIterator<'a, 'b>> for JObject<'a> {
+    fn from(other: JIterator<'a, 'b>) -> JObject<'a> {
+        other.internal
+    }
+}
+
+impl<'a: 'b, 'b> JIterator<'a, 'b> {
+    /// Create an iterator from the environment and an object implementing
+    /// `java/util/Iterator`. This looks up the method ids of `hasNext` and
+    /// `next` so that extra work doesn't need to be done for every element.
+    pub fn from_env(env: &'b JNIEnv<'a>, obj: JObject<'a>) -> Result<JIterator<'a, 'b>> {
+        let class = env.auto_local(env.find_class("java/util/Iterator")?);
+
+        let has_next = env.get_method_id(&class, "hasNext", "()Z")?;
+        let next = env.get_method_id(&class, "next", "()Ljava/lang/Object;")?;
+
+        Ok(JIterator {
+            internal: obj,
+            has_next,
+            next,
+            env,
+        })
+    }
+
+    /// Create an iterator over an object implementing `java/lang/Iterable`,
+    /// such as a `java.util.Set`, by calling its `iterator` method.
+    pub fn from_iterable(env: &'b JNIEnv<'a>, iterable: JObject<'a>) -> Result<JIterator<'a, 'b>> {
+        non_null!(iterable, "from_iterable iterable argument");
+        let iter = env
+            .call_method(iterable, "iterator", "()Ljava/util/Iterator;", &[])?
+            .l()?;
+        JIterator::from_env(env, iter)
+    }
+
+    /// Get the next element, or `None` once the iterator is exhausted. Unlike
+    /// `Iterator::next`, this returns the errors of the java calls, such as a
+    /// pending `ConcurrentModificationException`.
+    pub fn try_next(&self) -> Result<Option<JObject<'a>>> {
+        let has_next = self
+            .env
+            .call_method_unchecked(
+                self.internal,
+                self.has_next,
+                JavaType::Primitive(Primitive::Boolean),
+                &[],
+            )?
+            .z()?;
+
+        if !has_next {
+            return Ok(None);
+        }
+        let next = self
+            .env
+            .call_method_unchecked(
+                self.internal,
+                self.next,
+                JavaType::Object("java/lang/Object".into()),
+                &[],
+            )?
+            .l()?;
+
+        Ok(Some(next))
+    }
+}
+
+/// Yields the elements, including `null` ones, and ends at the first error.
+/// Use `try_next` to tell the two cases apart.
+impl<'a: 'b, 'b> Iterator for JIterator<'a, 'b> {
+    type Item = JObject<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.try_next().unwrap_or(None)
+    }
+}
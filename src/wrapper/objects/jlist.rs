@@ -1,6 +1,6 @@
 use crate::{
     errors::*,
-    objects::{JMethodID, JObject},
+    objects::{jmap::non_null_result, AutoLocal, JMethodID, JObject},
     signature::{JavaType, Primitive},
     sys::jint,
     JNIEnv,
@@ -14,9 +14,13 @@ use crate::{
 pub struct JList<'a: 'b, 'b> {
     internal: JObject<'a>,
     get: JMethodID<'a>,
+    set: JMethodID<'a>,
     add: JMethodID<'a>,
     add_idx: JMethodID<'a>,
     remove: JMethodID<'a>,
+    index_of: JMethodID<'a>,
+    contains: JMethodID<'a>,
+    clear: JMethodID<'a>,
     size: JMethodID<'a>,
     env: &'b JNIEnv<'a>,
 }
@@ -43,24 +47,64 @@ impl<'a: 'b, 'b> JList<'a, 'b> {
         let class = env.auto_local(env.find_class("java/util/List")?);
 
         let get = env.get_method_id(&class, "get", "(I)Ljava/lang/Object;")?;
+        let set = env.get_method_id(&class, "set", "(ILjava/lang/Object;)Ljava/lang/Object;")?;
         let add = env.get_method_id(&class, "add", "(Ljava/lang/Object;)Z")?;
         let add_idx = env.get_method_id(&class, "add", "(ILjava/lang/Object;)V")?;
         let remove = env.get_method_id(&class, "remove", "(I)Ljava/lang/Object;")?;
+        let index_of = env.get_method_id(&class, "indexOf", "(Ljava/lang/Object;)I")?;
+        let contains = env.get_method_id(&class, "contains", "(Ljava/lang/Object;)Z")?;
+        let clear = env.get_method_id(&class, "clear", "()V")?;
         let size = env.get_method_id(&class, "size", "()I")?;
 
         Ok(JList {
             internal: obj,
             get,
+            set,
             add,
             add_idx,
             remove,
+            index_of,
+            contains,
+            clear,
             size,
             env,
         })
     }
 
-    /// Look up the value for a key. Returns `Some` if it's found and `None` if
-    /// a null pointer would be returned.
+    /// Create a new `java.util.ArrayList` holding the given elements, in
+    /// order.
+    ///
+    /// Each element is taken from the iterator and added in its own local
+    /// reference frame, so the local references created by a lazy iterator,
+    /// like `(0..n).map(|i| env.new_string(...).unwrap())`, are deleted as
+    /// soon as their element has been added to the list. References that were
+    /// created before this call stay valid.
+    pub fn from_iter<I>(env: &'b JNIEnv<'a>, items: I) -> Result<JList<'a, 'b>>
+    where
+        I: IntoIterator,
+        I::Item: Into<JObject<'a>>,
+    {
+        let internal = env.new_object("java/util/ArrayList", "()V", &[])?;
+        let list = JList::from_env(env, internal)?;
+
+        let mut items = items.into_iter();
+        loop {
+            env.push_local_frame(1)?;
+            let added = match items.next() {
+                Some(item) => list.add(item.into()).map(|_| true),
+                None => Ok(false),
+            };
+            env.pop_local_frame(JObject::null())?;
+            if !added? {
+                return Ok(list);
+            }
+        }
+    }
+
+    /// Get the element at an index. Returns `None` if the element is `null`.
+    ///
+    /// An index out of bounds returns an `Error::JavaException`, with the
+    /// `IndexOutOfBoundsException` pending.
     pub fn get(&self, idx: jint) -> Result<Option<JObject<'a>>> {
         let result = self.env.call_method_unchecked(
             self.internal,
@@ -69,13 +113,23 @@ impl<'a: 'b, 'b> JList<'a, 'b> {
             &[idx.into()],
         );
 
-        match result {
-            Ok(val) => Ok(Some(val.l()?)),
-            Err(e) => match e {
-                Error::NullPtr(_) => Ok(None),
-                _ => Err(e),
-            },
-        }
+        non_null_result(result)
+    }
+
+    /// Replace the element at an index, and return the previous element, or
+    /// `None` if it was `null`.
+    ///
+    /// An index out of bounds returns an `Error::JavaException`, with the
+    /// `IndexOutOfBoundsException` pending.
+    pub fn set(&self, idx: jint, value: JObject<'a>) -> Result<Option<JObject<'a>>> {
+        let result = self.env.call_method_unchecked(
+            self.internal,
+            self.set,
+            JavaType::Object("java/lang/Object".into()),
+            &[idx.into(), value.into()],
+        );
+
+        non_null_result(result)
     }
 
     /// Append an element to the list
@@ -104,7 +158,11 @@ impl<'a: 'b, 'b> JList<'a, 'b> {
         Ok(())
     }
 
-    /// Remove an element from the list by index
+    /// Remove an element from the list by index, and return it, or `None` if
+    /// it was `null`.
+    ///
+    /// An index out of bounds returns an `Error::JavaException`, with the
+    /// `IndexOutOfBoundsException` pending.
     pub fn remove(&self, idx: jint) -> Result<Option<JObject<'a>>> {
         let result = self.env.call_method_unchecked(
             self.internal,
@@ -113,13 +171,47 @@ impl<'a: 'b, 'b> JList<'a, 'b> {
             &[idx.into()],
         );
 
-        match result {
-            Ok(val) => Ok(Some(val.l()?)),
-            Err(e) => match e {
-                Error::NullPtr(_) => Ok(None),
-                _ => Err(e),
-            },
-        }
+        non_null_result(result)
+    }
+
+    /// Get the index of the first element equal to the given one, according
+    /// to its `equals` method, or `None` if the list doesn't contain it.
+    pub fn index_of(&self, value: JObject<'a>) -> Result<Option<jint>> {
+        let result = self.env.call_method_unchecked(
+            self.internal,
+            self.index_of,
+            JavaType::Primitive(Primitive::Int),
+            &[value.into()],
+        );
+
+        let idx = result?.i()?;
+        Ok(if idx < 0 { None } else { Some(idx) })
+    }
+
+    /// Check if the list contains an element equal to the given one,
+    /// according to its `equals` method.
+    pub fn contains(&self, value: JObject<'a>) -> Result<bool> {
+        let result = self.env.call_method_unchecked(
+            self.internal,
+            self.contains,
+            JavaType::Primitive(Primitive::Boolean),
+            &[value.into()],
+        );
+
+        result.and_then(|v| v.z())
+    }
+
+    /// Remove all the elements from the list
+    pub fn clear(&self) -> Result<()> {
+        let result = self.env.call_method_unchecked(
+            self.internal,
+            self.clear,
+            JavaType::Primitive(Primitive::Void),
+            &[],
+        );
+
+        let _ = result?;
+        Ok(())
     }
 
     /// Get the size of the list
@@ -134,7 +226,8 @@ impl<'a: 'b, 'b> JList<'a, 'b> {
         result.and_then(|v| v.i())
     }
 
-    /// Pop the last element from the list
+    /// Pop the last element from the list. Returns `None` if the list is
+    /// empty or the last element is `null`.
     ///
     /// Note that this calls `size()` to determine the last index.
     pub fn pop(&self) -> Result<Option<JObject<'a>>> {
@@ -150,17 +243,18 @@ impl<'a: 'b, 'b> JList<'a, 'b> {
             &[(size - 1).into()],
         );
 
-        match result {
-            Ok(val) => Ok(Some(val.l()?)),
-            Err(e) => match e {
-                Error::NullPtr(_) => Ok(None),
-                _ => Err(e),
-            },
-        }
+        non_null_result(result)
     }
 
-    /// Get key/value iterator for the map. This is done by getting the
-    /// `EntrySet` from java and iterating over it.
+    /// Get an iterator over the elements of the list.
+    ///
+    /// Unlike `get`, which returns `None` for them, `null` elements are yielded
+    /// as null objects, so the iteration covers the whole list.
+    ///
+    /// The elements are returned as local references which are not deleted
+    /// until the native method returns, so this is the iterator to use when an
+    /// element needs to be passed back to the caller. For large lists, use
+    /// `iter_auto` instead.
     pub fn iter(&self) -> Result<JListIter<'a, 'b, '_>> {
         Ok(JListIter {
             list: &self,
@@ -168,12 +262,19 @@ impl<'a: 'b, 'b> JList<'a, 'b> {
             size: self.size()?,
         })
     }
+
+    /// Get an iterator over the elements of the list which wraps each element
+    /// in an `AutoLocal`, so its local reference is deleted once the consumer
+    /// drops it. Use `AutoLocal::forget` to keep an element around.
+    pub fn iter_auto(&self) -> Result<JListAutoIter<'a, 'b, '_>> {
+        Ok(JListAutoIter {
+            inner: self.iter()?,
+        })
+    }
 }
 
-/// An iterator over the keys and values in a map.
-///
-/// TODO: make the iterator implementation for java iterators its own thing
-/// and generic enough to use elsewhere.
+/// An iterator over the elements of a list, by index. This gets returned from
+/// `JList::iter`. Use `JIterator` for other collections.
 pub struct JListIter<'a: 'b, 'b: 'c, 'c> {
     list: &'c JList<'a, 'b>,
     current: jint,
@@ -191,7 +292,9 @@ impl<'a: 'b, 'b: 'c, 'c> Iterator for JListIter<'a, 'b, 'c> {
         match res {
             Ok(elem) => {
                 self.current += 1;
-                elem
+                // Null elements are yielded as null objects rather than ending
+                // the iteration
+                Some(elem.unwrap_or_else(JObject::null))
             }
             Err(_) => {
                 self.current = self.size;
@@ -200,3 +303,18 @@ impl<'a: 'b, 'b: 'c, 'c> Iterator for JListIter<'a, 'b, 'c> {
         }
     }
 }
+
+/// An iterator over the elements of a list, yielding each element wrapped in
+/// an `AutoLocal`. This gets returned from `JList::iter_auto`.
+pub struct JListAutoIter<'a: 'b, 'b: 'c, 'c> {
+    inner: JListIter<'a, 'b, 'c>,
+}
+
+impl<'a: 'b, 'b: 'c, 'c> Iterator for JListAutoIter<'a, 'b, 'c> {
+    type Item = AutoLocal<'a, 'b>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let env = self.inner.list.env;
+        self.inner.next().map(|elem| env.auto_local(elem))
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     errors::*,
-    objects::{AutoLocal, JMethodID, JObject},
-    signature::{JavaType, Primitive},
+    objects::{AutoLocal, JIterator, JMethodID, JObject, JValue},
+    signature::JavaType,
     JNIEnv,
 };
 
@@ -12,10 +12,10 @@ use crate::{
 /// call.
 pub struct JMap<'a: 'b, 'b> {
     internal: JObject<'a>,
-    class: AutoLocal<'a, 'b>,
     get: JMethodID<'a>,
     put: JMethodID<'a>,
     remove: JMethodID<'a>,
+    entry_set: JMethodID<'a>,
     env: &'b JNIEnv<'a>,
 }
 
@@ -51,18 +51,20 @@ impl<'a: 'b, 'b> JMap<'a, 'b> {
         let remove =
             env.get_method_id(&class, "remove", "(Ljava/lang/Object;)Ljava/lang/Object;")?;
 
+        let entry_set = env.get_method_id(&class, "entrySet", "()Ljava/util/Set;")?;
+
         Ok(JMap {
             internal: obj,
-            class,
             get,
             put,
             remove,
+            entry_set,
             env,
         })
     }
 
     /// Look up the value for a key. Returns `Some` if it's found and `None` if
-    /// a null pointer would be returned.
+    /// a null pointer is returned.
     pub fn get(&self, key: JObject<'a>) -> Result<Option<JObject<'a>>> {
         let result = self.env.call_method_unchecked(
             self.internal,
@@ -71,13 +73,7 @@ impl<'a: 'b, 'b> JMap<'a, 'b> {
             &[key.into()],
         );
 
-        match result {
-            Ok(val) => Ok(Some(val.l()?)),
-            Err(e) => match e {
-                Error::NullPtr(_) => Ok(None),
-                _ => Err(e),
-            },
-        }
+        non_null_result(result)
     }
 
     /// Look up the value for a key. Returns `Some` with the old value if the
@@ -90,13 +86,7 @@ impl<'a: 'b, 'b> JMap<'a, 'b> {
             &[key.into(), value.into()],
         );
 
-        match result {
-            Ok(val) => Ok(Some(val.l()?)),
-            Err(e) => match e {
-                Error::NullPtr(_) => Ok(None),
-                _ => Err(e),
-            },
-        }
+        non_null_result(result)
     }
 
     /// Remove a value from the map. Returns `Some` with the removed value and
@@ -109,28 +99,12 @@ impl<'a: 'b, 'b> JMap<'a, 'b> {
             &[key.into()],
         );
 
-        match result {
-            Ok(val) => Ok(Some(val.l()?)),
-            Err(e) => match e {
-                Error::NullPtr(_) => Ok(None),
-                _ => Err(e),
-            },
-        }
+        non_null_result(result)
     }
 
     /// Get key/value iterator for the map. This is done by getting the
     /// `EntrySet` from java and iterating over it.
     pub fn iter(&self) -> Result<JMapIter<'a, 'b, '_>> {
-        let iter_class = self
-            .env
-            .auto_local(self.env.find_class("java/util/Iterator")?);
-
-        let has_next = self.env.get_method_id(&iter_class, "hasNext", "()Z")?;
-
-        let next = self
-            .env
-            .get_method_id(&iter_class, "next", "()Ljava/lang/Object;")?;
-
         let entry_class = self
             .env
             .auto_local(self.env.find_class("java/util/Map$Entry")?);
@@ -151,7 +125,7 @@ impl<'a: 'b, 'b> JMap<'a, 'b> {
                 .env
                 .call_method_unchecked(
                     self.internal,
-                    (&self.class, "entrySet", "()Ljava/util/Set;"),
+                    self.entry_set,
                     JavaType::Object("java/util/Set".into()),
                     &[],
                 )?
@@ -169,59 +143,46 @@ impl<'a: 'b, 'b> JMap<'a, 'b> {
 
             Ok(iter)
         })?;
+        let entries = JIterator::from_env(self.env, iter)?;
         let iter = self.env.auto_local(iter);
 
         Ok(JMapIter {
             map: &self,
-            has_next,
-            next,
+            entries,
             get_key,
             get_value,
-            iter,
+            _iter: iter,
         })
     }
 }
 
+/// Converts the result of a method returning an object into `None` if it's
+/// `null`.
+pub(crate) fn non_null_result(result: Result<JValue>) -> Result<Option<JObject>> {
+    let obj = result?.l()?;
+    if obj.is_null() {
+        Ok(None)
+    } else {
+        Ok(Some(obj))
+    }
+}
+
 /// An iterator over the keys and values in a map.
-///
-/// TODO: make the iterator implementation for java iterators its own thing
-/// and generic enough to use elsewhere.
-pub struct JMapIter<'a, 'b, 'c> {
+pub struct JMapIter<'a: 'b, 'b: 'c, 'c> {
     map: &'c JMap<'a, 'b>,
-    has_next: JMethodID<'a>,
-    next: JMethodID<'a>,
+    entries: JIterator<'a, 'b>,
     get_key: JMethodID<'a>,
     get_value: JMethodID<'a>,
-    iter: AutoLocal<'a, 'b>,
+    // Deletes the local reference to the java iterator
+    _iter: AutoLocal<'a, 'b>,
 }
 
 impl<'a: 'b, 'b: 'c, 'c> JMapIter<'a, 'b, 'c> {
     fn get_next(&self) -> Result<Option<(JObject<'a>, JObject<'a>)>> {
-        let iter = self.iter.as_obj();
-        let has_next = self
-            .map
-            .env
-            .call_method_unchecked(
-                iter,
-                self.has_next,
-                JavaType::Primitive(Primitive::Boolean),
-                &[],
-            )?
-            .z()?;
-
-        if !has_next {
-            return Ok(None);
-        }
-        let next = self
-            .map
-            .env
-            .call_method_unchecked(
-                iter,
-                self.next,
-                JavaType::Object("java/util/Map$Entry".into()),
-                &[],
-            )?
-            .l()?;
+        let next = match self.entries.try_next()? {
+            Some(next) => next,
+            None => return Ok(None),
+        };
 
         let key = self
             .map
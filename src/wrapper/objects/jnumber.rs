@@ -0,0 +1,140 @@
+use crate::{
+    errors::*,
+    objects::{JMethodID, JObject, JValue, MethodIdCache},
+    signature::{JavaType, Primitive},
+    sys::{jint, jlong},
+    JNIEnv,
+};
+
+use lazy_static::lazy_static;
+
+lazy_static! {
+    // Classes and method ids of the boxed number types, looked up once for all
+    // the wrappers.
+    static ref NUMBER_METHODS: MethodIdCache = MethodIdCache::new();
+}
+
+macro_rules! boxed_number {
+    (
+        $name:ident,
+        $prim:ty,
+        $class:expr,
+        $unbox:ident,
+        $unbox_name:expr,
+        $unbox_sig:expr,
+        $value_of_sig:expr,
+        $primitive:ident,
+        $getter:ident
+    ) => {
+        #[doc = concat!(
+                    "Wrapper for JObjects that are instances of `", $class, "`. Provides a\n",
+                    "method to unbox the value.\n\n",
+                    "The class and method id are looked up once and cached for all the\n",
+                    "wrappers, rather than for every wrapper or method call."
+                )]
+        pub struct $name<'a: 'b, 'b> {
+            internal: JObject<'a>,
+            $unbox: JMethodID<'a>,
+            env: &'b JNIEnv<'a>,
+        }
+
+        impl<'a: 'b, 'b> ::std::ops::Deref for $name<'a, 'b> {
+            type Target = JObject<'a>;
+
+            fn deref(&self) -> &Self::Target {
+                &self.internal
+            }
+        }
+
+        impl<'a: 'b, 'b> From<$name<'a, 'b>> for JObject<'a> {
+            fn from(other: $name<'a, 'b>) -> JObject<'a> {
+                other.internal
+            }
+        }
+
+        impl<'a: 'b, 'b> From<&$name<'a, 'b>> for JValue<'a> {
+            fn from(other: &$name<'a, 'b>) -> JValue<'a> {
+                JValue::Object(other.internal)
+            }
+        }
+
+        impl<'a: 'b, 'b> $name<'a, 'b> {
+            #[doc = concat!(
+                        "Wrap an object, after checking its class.\n\n",
+                        "Returns `Err` with the kind `WrongObjectClass` if `obj` is not a\n",
+                        "`", $class, "`."
+                    )]
+            pub fn from_env(env: &'b JNIEnv<'a>, obj: JObject<'a>) -> Result<$name<'a, 'b>> {
+                non_null!(obj, concat!(stringify!($name), " obj argument"));
+                let (class, $unbox) = env.lookup_cached_method(
+                    &NUMBER_METHODS,
+                    $class,
+                    $unbox_name,
+                    $unbox_sig,
+                    false,
+                )?;
+                if !env.is_instance_of(obj, &class)? {
+                    return Err(Error::WrongObjectClass(concat!(
+                        stringify!($name),
+                        " obj argument"
+                    )));
+                }
+
+                Ok($name {
+                    internal: obj,
+                    $unbox: $unbox.into(),
+                    env,
+                })
+            }
+
+            #[doc = concat!("Box a value with `", $class, "#valueOf`.")]
+            pub fn new(env: &'b JNIEnv<'a>, value: $prim) -> Result<$name<'a, 'b>> {
+                let obj = env
+                    .call_static_method_cached(
+                        &NUMBER_METHODS,
+                        $class,
+                        "valueOf",
+                        $value_of_sig,
+                        &[value.into()],
+                    )?
+                    .l()?;
+                $name::from_env(env, obj)
+            }
+
+            /// Get the boxed value.
+            pub fn $unbox(&self) -> Result<$prim> {
+                self.env
+                    .call_method_unchecked(
+                        self.internal,
+                        self.$unbox,
+                        JavaType::Primitive(Primitive::$primitive),
+                        &[],
+                    )?
+                    .$getter()
+            }
+        }
+    };
+}
+
+boxed_number!(
+    JInteger,
+    jint,
+    "java/lang/Integer",
+    int_value,
+    "intValue",
+    "()I",
+    "(I)Ljava/lang/Integer;",
+    Int,
+    i
+);
+boxed_number!(
+    JLong,
+    jlong,
+    "java/lang/Long",
+    long_value,
+    "longValue",
+    "()J",
+    "(J)Ljava/lang/Long;",
+    Long,
+    j
+);
@@ -10,6 +10,10 @@ use crate::sys::jobject;
 ///
 /// Most other types in the `objects` module deref to this, as they do in the C
 /// representation.
+///
+/// Comparing the raw pointers of two `JObject`s doesn't tell whether they
+/// refer to the same java object, use `JNIEnv#is_same_object` or `RefEq` for
+/// that.
 #[repr(transparent)]
 #[derive(Clone, Copy, Debug)]
 pub struct JObject<'a> {
@@ -1,6 +1,8 @@
 use crate::{
-    objects::JObject,
+    errors::*,
+    objects::{JObject, JString},
     sys::{jobject, jthrowable},
+    JNIEnv,
 };
 
 /// Lifetime'd representation of a `jthrowable`. Just a `JObject` wrapped in a
@@ -34,3 +36,56 @@ impl<'a> From<JObject<'a>> for JThrowable<'a> {
         (other.into_inner() as jthrowable).into()
     }
 }
+
+impl<'a> JThrowable<'a> {
+    /// Get the detail message of the throwable with `Throwable#getMessage`.
+    ///
+    /// Returns `Ok(None)` if the throwable has no message.
+    pub fn get_message(&self, env: &JNIEnv<'a>) -> Result<Option<JString<'a>>> {
+        let message = env
+            .call_method(self.0, "getMessage", "()Ljava/lang/String;", &[])?
+            .l()?;
+        Ok(if message.is_null() {
+            None
+        } else {
+            Some(message.into())
+        })
+    }
+
+    /// Get the cause of the throwable with `Throwable#getCause`.
+    ///
+    /// Returns `Ok(None)` if the cause is unknown or nonexistent.
+    pub fn get_cause(&self, env: &JNIEnv<'a>) -> Result<Option<JThrowable<'a>>> {
+        let cause = env
+            .call_method(self.0, "getCause", "()Ljava/lang/Throwable;", &[])?
+            .l()?;
+        Ok(if cause.is_null() {
+            None
+        } else {
+            Some(cause.into())
+        })
+    }
+
+    /// Get the `StackTraceElement`s of the throwable with
+    /// `Throwable#getStackTrace`, starting with the innermost frame.
+    ///
+    /// Every element is a new local reference, so the length of the stack
+    /// trace must be taken into account when reserving local capacity.
+    pub fn get_stack_trace(&self, env: &JNIEnv<'a>) -> Result<Vec<JObject<'a>>> {
+        let array = env
+            .call_method(
+                self.0,
+                "getStackTrace",
+                "()[Ljava/lang/StackTraceElement;",
+                &[],
+            )?
+            .l()?;
+        let array = env.auto_local(array);
+
+        let raw = array.as_obj().into_inner();
+        let length = env.get_array_length(raw)?;
+        (0..length)
+            .map(|i| env.get_object_array_element(raw, i))
+            .collect()
+    }
+}
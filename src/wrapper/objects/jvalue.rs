@@ -118,6 +118,15 @@ impl<'a> JValue<'a> {
         }
     }
 
+    /// Try to unwrap to a rust `char`.
+    ///
+    /// A java `char` is a single UTF-16 code unit, so this fails with
+    /// `Error::InvalidChar` for the high or low half of a surrogate pair.
+    pub fn char(self) -> Result<char> {
+        let c = self.c()?;
+        char::from_u32(c as u32).ok_or(Error::InvalidChar(c))
+    }
+
     /// Try to unwrap to a double.
     pub fn d(self) -> Result<jdouble> {
         match self {
@@ -190,6 +199,14 @@ impl<'a> From<bool> for JValue<'a> {
     }
 }
 
+impl<'a> TryFrom<JValue<'a>> for bool {
+    type Error = Error;
+
+    fn try_from(value: JValue<'a>) -> Result<Self> {
+        value.z()
+    }
+}
+
 // jbool
 impl<'a> From<jboolean> for JValue<'a> {
     fn from(other: jboolean) -> Self {
@@ -226,6 +243,26 @@ impl<'a> TryFrom<JValue<'a>> for jchar {
     }
 }
 
+/// Converts a rust `char` to a java `char`.
+///
+/// Only characters of the Basic Multilingual Plane fit in a single UTF-16
+/// code unit. Supplementary characters, like emojis, are truncated to their
+/// lower 16 bits, which gives an unrelated character: pass them to java as a
+/// `String` instead.
+impl<'a> From<char> for JValue<'a> {
+    fn from(other: char) -> Self {
+        JValue::Char(other as u32 as jchar)
+    }
+}
+
+impl<'a> TryFrom<JValue<'a>> for char {
+    type Error = Error;
+
+    fn try_from(value: JValue<'a>) -> Result<Self> {
+        value.char()
+    }
+}
+
 // jshort
 impl<'a> From<jshort> for JValue<'a> {
     fn from(other: jshort) -> Self {
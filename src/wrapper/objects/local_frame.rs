@@ -0,0 +1,44 @@
+use std::mem;
+
+use log::debug;
+
+use crate::{errors::*, objects::JObject, JNIEnv};
+
+/// Guard for a local reference frame, returned from
+/// `JNIEnv::push_auto_local_frame`. The frame is popped when the guard is
+/// dropped, deleting all the local references created since it was pushed.
+///
+/// Unlike `with_local_frame`, this doesn't require moving the code into a
+/// closure. To keep one of the references, pass it to `pop_with`, which
+/// returns a new reference to it in the enclosing frame.
+///
+/// Frames must be popped in the reverse order of their creation, which is the
+/// case for guards dropped at the end of nested scopes. Don't use the local
+/// references created in the frame after it's popped, or you'll get a JVM
+/// crash.
+pub struct LocalFrame<'a: 'b, 'b> {
+    env: &'b JNIEnv<'a>,
+}
+
+impl<'a, 'b> LocalFrame<'a, 'b> {
+    /// Creates a guard for a frame that was just pushed.
+    pub(crate) fn new(env: &'b JNIEnv<'a>) -> Self {
+        LocalFrame { env }
+    }
+
+    /// Pops the frame, keeping the given object, which can be `null`. Returns
+    /// a local reference to it which is valid in the enclosing frame.
+    pub fn pop_with(self, result: JObject<'a>) -> Result<JObject<'a>> {
+        let env = self.env;
+        mem::forget(self);
+        env.pop_local_frame(result)
+    }
+}
+
+impl<'a, 'b> Drop for LocalFrame<'a, 'b> {
+    fn drop(&mut self) {
+        if let Err(e) = self.env.pop_local_frame(JObject::null()) {
+            debug!("error popping local frame: {:#?}", e);
+        }
+    }
+}
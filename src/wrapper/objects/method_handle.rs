@@ -0,0 +1,142 @@
+use crate::{
+    errors::*,
+    objects::{GlobalRef, JClass, JObject, JValue},
+    signature::{JavaType, Primitive, TypeSignature},
+    sys::jsize,
+    JNIEnv,
+};
+
+/// A `java.lang.invoke.MethodHandle` bound to a method, which is resolved
+/// once and can then be invoked from any thread. This gets returned from
+/// `JNIEnv::bind_method_handle` and `JNIEnv::bind_static_method_handle`.
+///
+/// The handle is resolved with `MethodHandles#publicLookup`, so only public
+/// methods of public classes can be bound: the full lookup is caller
+/// sensitive and can't be used from native code.
+///
+/// This is not a fast path. As `invokeExact` and `invoke` are signature
+/// polymorphic, they can't be called through JNI, so the handle is invoked
+/// with `invokeWithArguments`, which boxes the arguments and the result. This
+/// makes it slower than a call through a method id (see the
+/// `jni_call_static_method_handle` benchmark); prefer
+/// `JNIEnv::call_method_unchecked` with a cached method id on hot paths.
+pub struct MethodHandle {
+    handle: GlobalRef,
+    sig: TypeSignature,
+    is_static: bool,
+}
+
+impl MethodHandle {
+    pub(crate) fn bind<'a, 'c>(
+        env: &JNIEnv<'a>,
+        class: JClass<'c>,
+        name: &str,
+        sig: &str,
+        is_static: bool,
+    ) -> Result<Self> {
+        let parsed = TypeSignature::from_str(sig)?;
+        let handle = env.with_local_frame(8, || {
+            let lookup = env
+                .call_static_method(
+                    "java/lang/invoke/MethodHandles",
+                    "publicLookup",
+                    "()Ljava/lang/invoke/MethodHandles$Lookup;",
+                    &[],
+                )?
+                .l()?;
+            let method_type = env
+                .call_static_method(
+                    "java/lang/invoke/MethodType",
+                    "fromMethodDescriptorString",
+                    "(Ljava/lang/String;Ljava/lang/ClassLoader;)Ljava/lang/invoke/MethodType;",
+                    &[env.new_string(sig)?.into(), JObject::null().into()],
+                )?
+                .l()?;
+            let find = if is_static {
+                "findStatic"
+            } else {
+                "findVirtual"
+            };
+            env.call_method(
+                lookup,
+                find,
+                "(Ljava/lang/Class;Ljava/lang/String;Ljava/lang/invoke/MethodType;)\
+                 Ljava/lang/invoke/MethodHandle;",
+                &[
+                    class.into(),
+                    env.new_string(name)?.into(),
+                    method_type.into(),
+                ],
+            )?
+            .l()
+        })?;
+        let handle = env.auto_local(handle);
+
+        Ok(MethodHandle {
+            handle: env.new_global_ref(&handle)?,
+            sig: parsed,
+            is_static,
+        })
+    }
+
+    /// Invokes the method with the given arguments. For an object method, the
+    /// object to call it on must be passed as the first argument.
+    ///
+    /// Returns `Err` with the kind `InvalidArgList` if the number of arguments
+    /// doesn't match the signature.
+    pub fn invoke<'a>(&self, env: &JNIEnv<'a>, args: &[JValue]) -> Result<JValue<'a>> {
+        let expected = self.sig.args.len() + if self.is_static { 0 } else { 1 };
+        if args.len() != expected {
+            return Err(Error::InvalidArgList(self.sig.clone()));
+        }
+
+        let array =
+            env.new_object_array(args.len() as jsize, "java/lang/Object", JObject::null())?;
+        let array = env.auto_local(JObject::from(array));
+        for (i, arg) in args.iter().enumerate() {
+            let element = env.auto_local(env.box_value(*arg)?);
+            env.set_object_array_element(
+                array.as_obj().into_inner(),
+                i as jsize,
+                element.as_obj(),
+            )?;
+        }
+
+        // The handle outlives the call, so its reference can be used with the
+        // lifetime of the env
+        let handle = JObject::from(self.handle.as_obj().into_inner());
+        let result = env
+            .call_method(
+                handle,
+                "invokeWithArguments",
+                "([Ljava/lang/Object;)Ljava/lang/Object;",
+                &[array.as_obj().into()],
+            )?
+            .l()?;
+        unbox_result(env, result, &self.sig.ret)
+    }
+
+    /// Returns the `MethodHandle` object.
+    pub fn as_obj(&self) -> JObject<'_> {
+        self.handle.as_obj()
+    }
+}
+
+/// Converts the boxed result of `invokeWithArguments` to the return type.
+fn unbox_result<'a>(env: &JNIEnv<'a>, result: JObject<'a>, ret: &JavaType) -> Result<JValue<'a>> {
+    let (name, sig) = match ret {
+        JavaType::Primitive(Primitive::Void) => return Ok(JValue::Void),
+        JavaType::Primitive(Primitive::Boolean) => ("booleanValue", "()Z"),
+        JavaType::Primitive(Primitive::Byte) => ("byteValue", "()B"),
+        JavaType::Primitive(Primitive::Char) => ("charValue", "()C"),
+        JavaType::Primitive(Primitive::Short) => ("shortValue", "()S"),
+        JavaType::Primitive(Primitive::Int) => ("intValue", "()I"),
+        JavaType::Primitive(Primitive::Long) => ("longValue", "()J"),
+        JavaType::Primitive(Primitive::Float) => ("floatValue", "()F"),
+        JavaType::Primitive(Primitive::Double) => ("doubleValue", "()D"),
+        _ => return Ok(JValue::Object(result)),
+    };
+
+    let boxed = env.auto_local(result);
+    env.call_method(boxed.as_obj(), name, sig, &[])
+}
@@ -0,0 +1,107 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::{errors::Result, objects::GlobalRef, sys::jmethodID};
+
+/// A cache of method ids, keyed by the class name, method name and signature,
+/// for calling the same methods over and over without looking them up every
+/// time. It's used with the `JNIEnv` methods taking a cache, such as
+/// `get_cached_method_id` and `call_static_method_cached`.
+///
+/// Method ids are valid on all threads, so a cache can be shared between
+/// threads, e.g. in a `static` or in the state of a native library. Each
+/// cached class is pinned with a global reference, so it can't get unloaded
+/// while its method ids are in the cache.
+///
+/// # Example
+/// ```rust,ignore
+/// let cache = MethodIdCache::new();
+/// let abs = env.call_static_method_cached(&cache, "java/lang/Math", "abs", "(I)I", &[x.into()])?;
+/// ```
+#[derive(Default)]
+pub struct MethodIdCache {
+    ids: Mutex<HashMap<MethodKey, CachedMethodId>>,
+}
+
+#[derive(Hash, PartialEq, Eq)]
+struct MethodKey {
+    class: String,
+    name: String,
+    sig: String,
+    is_static: bool,
+}
+
+#[derive(Clone)]
+struct CachedMethodId {
+    class: GlobalRef,
+    id: SendMethodId,
+}
+
+/// A method id which can be kept in caches shared between threads.
+#[derive(Clone, Copy)]
+pub(crate) struct SendMethodId(pub(crate) jmethodID);
+
+// Method ids are not tied to the thread they were looked up on, and stay
+// valid as long as their class is loaded.
+unsafe impl Send for SendMethodId {}
+unsafe impl Sync for SendMethodId {}
+
+impl MethodIdCache {
+    /// Creates a new, empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the class and the method id cached for the given key, looking
+    /// them up with `lookup` if they're not in the cache yet.
+    pub(crate) fn get_or_lookup<F>(
+        &self,
+        class: &str,
+        name: &str,
+        sig: &str,
+        is_static: bool,
+        lookup: F,
+    ) -> Result<(GlobalRef, jmethodID)>
+    where
+        F: FnOnce() -> Result<(GlobalRef, jmethodID)>,
+    {
+        let key = MethodKey {
+            class: class.to_owned(),
+            name: name.to_owned(),
+            sig: sig.to_owned(),
+            is_static,
+        };
+        if let Some(cached) = self.ids.lock().unwrap().get(&key) {
+            return Ok((cached.class.clone(), cached.id.0));
+        }
+
+        // The lock isn't held during the lookup, as it may call into java
+        let (class, id) = lookup()?;
+        let cached = self
+            .ids
+            .lock()
+            .unwrap()
+            .entry(key)
+            .or_insert(CachedMethodId {
+                class,
+                id: SendMethodId(id),
+            })
+            .clone();
+        Ok((cached.class, cached.id.0))
+    }
+
+    /// Returns the number of method ids in the cache.
+    pub fn len(&self) -> usize {
+        self.ids.lock().unwrap().len()
+    }
+
+    /// Returns `true` if the cache holds no method ids.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all the method ids from the cache. Ids returned earlier stay
+    /// valid only as long as their class is loaded.
+    pub fn clear(&self) {
+        self.ids.lock().unwrap().clear();
+    }
+}
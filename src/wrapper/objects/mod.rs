@@ -26,19 +26,85 @@ pub use self::jclass::*;
 mod jstring;
 pub use self::jstring::*;
 
+mod jiterator;
+pub use self::jiterator::*;
+
 mod jmap;
 pub use self::jmap::*;
 
 mod jlist;
 pub use self::jlist::*;
 
+mod jnumber;
+pub use self::jnumber::*;
+
 mod jbytebuffer;
 pub use self::jbytebuffer::*;
 
+// For pinning a direct buffer while its memory is accessed
+mod direct_buffer_guard;
+pub use self::direct_buffer_guard::*;
+
 // For storing a reference to a java object
 mod global_ref;
 pub use self::global_ref::*;
 
+// For observing a java object without pinning it
+mod weak_ref;
+pub use self::weak_ref::*;
+
+// For reusing java strings for constants
+mod string_pool;
+pub use self::string_pool::*;
+
+// For caching method ids between calls
+mod method_id_cache;
+pub use self::method_id_cache::*;
+
+// For caching values per object, by identity
+mod identity_cache;
+pub(crate) use self::identity_cache::*;
+
+// For passing byte slices to `call!`
+mod byte_array_arg;
+pub use self::byte_array_arg::*;
+
+// For reading enum constants without looking them up every time
+mod enum_cache;
+pub use self::enum_cache::*;
+
+// For creating objects from any thread
+mod constructor;
+pub use self::constructor::*;
+
+// For invoking methods through java.lang.invoke
+mod method_handle;
+pub use self::method_handle::*;
+
+// For calling a method on objects of unrelated classes
+mod dynamic_dispatch;
+pub use self::dynamic_dispatch::*;
+
+// For reusing short-lived objects
+mod object_pool;
+pub use self::object_pool::*;
+
+// For comparing objects by identity
+mod ref_eq;
+pub use self::ref_eq::*;
+
+// For telling local, global and weak global references apart
+mod ref_type;
+pub use self::ref_type::*;
+
+// For filtering class members by their modifiers
+mod modifiers;
+pub use self::modifiers::*;
+
+// For iterating over object arrays
+mod object_array_iter;
+pub use self::object_array_iter::*;
+
 // For automatic local ref deletion
 mod auto_local;
 pub use self::auto_local::*;
@@ -46,10 +112,18 @@ pub use self::auto_local::*;
 mod release_mode;
 pub use self::release_mode::*;
 
+// For automatically popping local reference frames
+mod local_frame;
+pub use self::local_frame::*;
+
 // For automatic pointer-based generic array release
 mod auto_array;
 pub use self::auto_array::*;
 
+// For automatic release of the characters of strings in critical regions
+mod auto_string_chars;
+pub use self::auto_string_chars::*;
+
 // For automatic pointer-based primitive array release
 mod auto_primitive_array;
 
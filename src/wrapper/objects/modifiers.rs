@@ -0,0 +1,58 @@
+use std::ops::BitOr;
+
+use crate::sys::jint;
+
+/// A set of the `java.lang.reflect.Modifier` flags of a class member, as used
+/// by `JNIEnv::get_declared_fields` and `JNIEnv::get_declared_methods`.
+///
+/// Flags are combined with `|`, e.g. `Modifiers::STATIC | Modifiers::TRANSIENT`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct Modifiers(jint);
+
+impl Modifiers {
+    /// No modifiers.
+    pub const NONE: Modifiers = Modifiers(0);
+    /// `public`
+    pub const PUBLIC: Modifiers = Modifiers(0x0001);
+    /// `private`
+    pub const PRIVATE: Modifiers = Modifiers(0x0002);
+    /// `protected`
+    pub const PROTECTED: Modifiers = Modifiers(0x0004);
+    /// `static`
+    pub const STATIC: Modifiers = Modifiers(0x0008);
+    /// `final`
+    pub const FINAL: Modifiers = Modifiers(0x0010);
+    /// `synchronized`
+    pub const SYNCHRONIZED: Modifiers = Modifiers(0x0020);
+    /// `volatile`
+    pub const VOLATILE: Modifiers = Modifiers(0x0040);
+    /// `transient`
+    pub const TRANSIENT: Modifiers = Modifiers(0x0080);
+    /// `native`
+    pub const NATIVE: Modifiers = Modifiers(0x0100);
+    /// `abstract`
+    pub const ABSTRACT: Modifiers = Modifiers(0x0400);
+
+    /// Wraps the flags returned by `getModifiers`.
+    pub fn from_bits(bits: jint) -> Self {
+        Modifiers(bits)
+    }
+
+    /// The raw flags.
+    pub fn bits(self) -> jint {
+        self.0
+    }
+
+    /// Whether any of the `other` flags are set.
+    pub fn intersects(self, other: Modifiers) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl BitOr for Modifiers {
+    type Output = Modifiers;
+
+    fn bitor(self, other: Modifiers) -> Modifiers {
+        Modifiers(self.0 | other.0)
+    }
+}
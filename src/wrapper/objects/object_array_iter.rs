@@ -0,0 +1,104 @@
+use crate::{
+    errors::*,
+    objects::{AutoLocal, JObject},
+    sys::{jobjectArray, jsize},
+    JNIEnv,
+};
+
+/// An iterator over the elements of an object array. This gets returned from
+/// `JNIEnv::object_array_iter`.
+///
+/// Each element is returned as a new local reference, which the caller is
+/// responsible for. This is what you want when an element needs to outlive the
+/// iteration, e.g. to return it from the current function. For large arrays,
+/// prefer `JNIEnv::object_array_iter_auto`, which deletes each element when it
+/// gets dropped, or process the elements in a local frame, so the local
+/// references don't pile up.
+///
+/// The iteration stops early if an element can't be read, leaving the
+/// exception pending. Use `try_next` to tell this apart from the end of the
+/// array.
+pub struct ObjectArrayIter<'a: 'b, 'b> {
+    env: &'b JNIEnv<'a>,
+    array: jobjectArray,
+    current: jsize,
+    len: jsize,
+}
+
+impl<'a: 'b, 'b> ObjectArrayIter<'a, 'b> {
+    pub(crate) fn new(env: &'b JNIEnv<'a>, array: jobjectArray, len: jsize) -> Self {
+        ObjectArrayIter {
+            env,
+            array,
+            current: 0,
+            len,
+        }
+    }
+
+    /// Get the next element, or `None` once the end of the array is reached.
+    /// Unlike `Iterator::next`, this returns the error if the element can't be
+    /// read, after which the iteration ends.
+    pub fn try_next(&mut self) -> Result<Option<JObject<'a>>> {
+        if self.current == self.len {
+            return Ok(None);
+        }
+        match self.env.get_object_array_element(self.array, self.current) {
+            Ok(elem) => {
+                self.current += 1;
+                Ok(Some(elem))
+            }
+            Err(e) => {
+                self.current = self.len;
+                Err(e)
+            }
+        }
+    }
+}
+
+impl<'a: 'b, 'b> Iterator for ObjectArrayIter<'a, 'b> {
+    type Item = JObject<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.try_next().unwrap_or(None)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some((self.len - self.current) as usize))
+    }
+}
+
+/// An iterator over the elements of an object array, yielding each element
+/// wrapped in an `AutoLocal`. This gets returned from
+/// `JNIEnv::object_array_iter_auto`.
+///
+/// The local reference to an element is deleted as soon as the consumer drops
+/// it, so iterating a large array doesn't exhaust the local reference table.
+/// Use `AutoLocal::forget` to keep an element beyond that.
+pub struct ObjectArrayAutoIter<'a: 'b, 'b> {
+    inner: ObjectArrayIter<'a, 'b>,
+}
+
+impl<'a: 'b, 'b> ObjectArrayAutoIter<'a, 'b> {
+    pub(crate) fn new(inner: ObjectArrayIter<'a, 'b>) -> Self {
+        ObjectArrayAutoIter { inner }
+    }
+
+    /// Like `ObjectArrayIter::try_next`, but wraps the element in an
+    /// `AutoLocal`.
+    pub fn try_next(&mut self) -> Result<Option<AutoLocal<'a, 'b>>> {
+        let env = self.inner.env;
+        Ok(self.inner.try_next()?.map(|elem| env.auto_local(elem)))
+    }
+}
+
+impl<'a: 'b, 'b> Iterator for ObjectArrayAutoIter<'a, 'b> {
+    type Item = AutoLocal<'a, 'b>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.try_next().unwrap_or(None)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
@@ -0,0 +1,65 @@
+use std::cell::RefCell;
+
+use crate::{
+    errors::*,
+    objects::{JClass, JObject},
+    JNIEnv,
+};
+
+/// A pool of objects of one class, for reusing short-lived objects instead of
+/// allocating new ones, e.g. in a tight loop. This gets created by
+/// `JNIEnv::with_object_pool`.
+///
+/// The objects are created with the no-args constructor of the class. An
+/// object taken from the pool is handed out as it was left by its previous
+/// user, so it must be reset before it's given back with `recycle`, or be of
+/// a class without mutable state.
+///
+/// The objects are local references which are deleted once the
+/// `with_object_pool` closure returns, so their lifetime is limited to the
+/// closure.
+pub struct ObjectPool<'a: 'b, 'b> {
+    class: JClass<'a>,
+    free: RefCell<Vec<JObject<'a>>>,
+    env: &'b JNIEnv<'a>,
+}
+
+impl<'a: 'b, 'b> ObjectPool<'a, 'b> {
+    pub(crate) fn new(env: &'b JNIEnv<'a>, class: JClass<'a>, count: usize) -> Result<Self> {
+        let pool = ObjectPool {
+            class,
+            free: RefCell::new(Vec::with_capacity(count)),
+            env,
+        };
+        for _ in 0..count {
+            let obj = pool.allocate()?;
+            pool.free.borrow_mut().push(obj);
+        }
+        Ok(pool)
+    }
+
+    /// Takes an object from the pool, or allocates a new one if there are no
+    /// free objects left.
+    pub fn take(&self) -> Result<JObject<'a>> {
+        let obj = self.free.borrow_mut().pop();
+        match obj {
+            Some(obj) => Ok(obj),
+            None => self.allocate(),
+        }
+    }
+
+    /// Gives an object back to the pool, so it can be taken again. The object
+    /// must be reset to its initial state first.
+    pub fn recycle(&self, obj: JObject<'a>) {
+        self.free.borrow_mut().push(obj);
+    }
+
+    /// Returns the number of free objects in the pool.
+    pub fn available(&self) -> usize {
+        self.free.borrow().len()
+    }
+
+    fn allocate(&self) -> Result<JObject<'a>> {
+        self.env.new_object(self.class, "()V", &[])
+    }
+}
@@ -0,0 +1,47 @@
+use crate::{objects::JObject, JNIEnv};
+
+/// Wrapper for comparing java objects by identity with `==`, within the scope
+/// of an environment.
+///
+/// Comparing the raw `jobject` pointers (such as `*a == *b`) is _not_ an
+/// identity check: two different references, for example a local and a
+/// global one, can refer to the same object. This compares with
+/// `JNIEnv#is_same_object` instead, so two `null` references are equal, too.
+///
+/// ```rust,ignore
+/// if RefEq::new(&env, a) == RefEq::new(&env, b) {
+///     // same object
+/// }
+/// ```
+#[derive(Clone, Copy)]
+pub struct RefEq<'a: 'b, 'b> {
+    obj: JObject<'a>,
+    env: &'b JNIEnv<'a>,
+}
+
+impl<'a: 'b, 'b> RefEq<'a, 'b> {
+    /// Creates a wrapper comparing the object by identity.
+    pub fn new<O>(env: &'b JNIEnv<'a>, obj: O) -> Self
+    where
+        O: Into<JObject<'a>>,
+    {
+        RefEq {
+            obj: obj.into(),
+            env,
+        }
+    }
+
+    /// Get the wrapped object.
+    pub fn as_obj(&self) -> JObject<'a> {
+        self.obj
+    }
+}
+
+impl<'a: 'b, 'b> PartialEq for RefEq<'a, 'b> {
+    fn eq(&self, other: &Self) -> bool {
+        // IsSameObject can't fail
+        self.env.ref_equals(self.obj, other.obj).unwrap_or(false)
+    }
+}
+
+impl<'a: 'b, 'b> Eq for RefEq<'a, 'b> {}
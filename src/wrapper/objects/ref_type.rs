@@ -0,0 +1,25 @@
+use crate::sys::jobjectRefType;
+
+/// The kind of a reference, as returned by `JNIEnv::get_object_ref_type`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RefType {
+    /// Not a valid reference, e.g. `null`.
+    Invalid,
+    /// A local reference, like most objects returned by JNI functions.
+    Local,
+    /// A global reference, like the ones held by `GlobalRef`.
+    Global,
+    /// A weak global reference, like the ones held by `WeakRef`.
+    WeakGlobal,
+}
+
+impl From<jobjectRefType> for RefType {
+    fn from(other: jobjectRefType) -> Self {
+        match other {
+            jobjectRefType::JNIInvalidRefType => RefType::Invalid,
+            jobjectRefType::JNILocalRefType => RefType::Local,
+            jobjectRefType::JNIGlobalRefType => RefType::Global,
+            jobjectRefType::JNIWeakGlobalRefType => RefType::WeakGlobal,
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+};
+
+use crate::{errors::Result, objects::GlobalRef, JNIEnv};
+
+/// A pool of java strings for constants that are passed to java over and
+/// over, such as enum labels or configuration keys. Each distinct string is
+/// created only once and then pinned with a global reference, which avoids
+/// creating a new java string for every call.
+///
+/// The pool can be shared between threads. An unbounded pool keeps every
+/// string until it's dropped or cleared, so it should only be used for a
+/// known, limited set of constants; a pool created with `bounded` evicts the
+/// oldest strings once it's full.
+///
+/// # Example
+/// ```rust,ignore
+/// let pool = StringPool::new();
+/// let key = pool.get(&env, "timeout")?;
+/// env.call_method(config, "get", "(Ljava/lang/Object;)Ljava/lang/Object;", &[key.as_obj().into()])?;
+/// ```
+#[derive(Default)]
+pub struct StringPool {
+    capacity: Option<usize>,
+    inner: Mutex<PoolInner>,
+}
+
+#[derive(Default)]
+struct PoolInner {
+    strings: HashMap<String, GlobalRef>,
+    // Insertion order, for evicting the oldest strings of a bounded pool
+    order: VecDeque<String>,
+}
+
+impl StringPool {
+    /// Creates a new pool without a limit on the number of strings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new pool holding at most `capacity` strings. When the pool is
+    /// full, the oldest string is evicted to make room for a new one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn bounded(capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity should be a positive integer");
+        StringPool {
+            capacity: Some(capacity),
+            inner: Mutex::new(PoolInner::default()),
+        }
+    }
+
+    /// Returns the java string with the given contents, creating it if it's
+    /// not in the pool yet.
+    ///
+    /// The returned `GlobalRef` keeps the string alive even after it gets
+    /// evicted from the pool.
+    pub fn get(&self, env: &JNIEnv, value: &str) -> Result<GlobalRef> {
+        if let Some(string) = self.inner.lock().unwrap().strings.get(value) {
+            return Ok(string.clone());
+        }
+
+        let local = env.auto_local(env.new_string(value)?);
+        let string = env.new_global_ref(&local)?;
+
+        let mut inner = self.inner.lock().unwrap();
+        // Another thread might have added it in the meantime
+        if let Some(existing) = inner.strings.get(value) {
+            return Ok(existing.clone());
+        }
+        if let Some(capacity) = self.capacity {
+            while inner.strings.len() >= capacity {
+                match inner.order.pop_front() {
+                    Some(oldest) => inner.strings.remove(&oldest),
+                    None => break,
+                };
+            }
+        }
+        inner.strings.insert(value.to_owned(), string.clone());
+        inner.order.push_back(value.to_owned());
+        Ok(string)
+    }
+
+    /// Returns the number of strings in the pool.
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().strings.len()
+    }
+
+    /// Returns `true` if the pool holds no strings.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all the strings from the pool.
+    pub fn clear(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.strings.clear();
+        inner.order.clear();
+    }
+}
@@ -0,0 +1,145 @@
+use std::{convert::From, fmt, sync::Arc};
+
+use log::{debug, warn};
+
+use crate::{
+    errors::Result,
+    objects::{GlobalRef, JObject},
+    sys, JNIEnv, JavaVM,
+};
+
+/// A weak global JVM reference. Unlike a `GlobalRef`, it doesn't prevent the
+/// object from being garbage collected, so it can be used to observe objects,
+/// e.g. in caches that shouldn't keep their keys alive. Like a `GlobalRef`, it
+/// can outlive the `JNIEnv` that it came from and can be used in other threads.
+///
+/// To use the object, the weak reference must first be upgraded to a local or
+/// a global reference with `upgrade_local` or `upgrade_global`, which return
+/// `None` once the object has been collected.
+///
+/// `WeakRef` can be cloned to use _the same_ weak reference in different
+/// contexts. The underlying weak reference is deleted when the last instance
+/// of `WeakRef` leaves its scope. As with `GlobalRef`, the native thread that
+/// drops it should be attached to the JVM, or it will be implicitly attached
+/// and detached.
+#[derive(Clone)]
+pub struct WeakRef {
+    inner: Arc<WeakRefGuard>,
+}
+
+struct WeakRefGuard {
+    raw: sys::jobject,
+    vm: JavaVM,
+}
+
+// Weak global references are valid on all threads.
+unsafe impl Send for WeakRefGuard {}
+unsafe impl Sync for WeakRefGuard {}
+
+impl fmt::Debug for WeakRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("WeakRef").field(&self.inner.raw).finish()
+    }
+}
+
+impl WeakRef {
+    /// Creates a new wrapper for a weak global reference.
+    ///
+    /// # Safety
+    ///
+    /// Expects a valid raw weak global reference that should be created with
+    /// `NewWeakGlobalRef` JNI function.
+    pub(crate) unsafe fn from_raw(vm: JavaVM, raw: sys::jobject) -> Self {
+        WeakRef {
+            inner: Arc::new(WeakRefGuard { raw, vm }),
+        }
+    }
+
+    /// Returns true if the object has been garbage collected, by comparing
+    /// the weak reference with `null` using `IsSameObject`. Unlike the
+    /// `upgrade_*` methods, this doesn't create a new reference.
+    ///
+    /// An object that is still alive may be collected right after this
+    /// returns false, so use `upgrade_local` or `upgrade_global` to actually
+    /// use it.
+    pub fn is_garbage_collected(&self, env: &JNIEnv) -> Result<bool> {
+        self.is_weak_ref_to(env, JObject::null())
+    }
+
+    /// Returns true if both weak references refer to the same object, using
+    /// `IsSameObject`.
+    ///
+    /// A collected object compares like `null`, so two references to
+    /// collected objects are the same, even if the objects were different.
+    pub fn is_same_object(&self, env: &JNIEnv, other: &WeakRef) -> Result<bool> {
+        env.is_same_object(JObject::from(self.as_raw()), JObject::from(other.as_raw()))
+    }
+
+    /// Returns true if this weak reference refers to the given object, using
+    /// `IsSameObject`.
+    ///
+    /// A collected object compares like `null`, so this returns true for a
+    /// `null` object once the referent has been collected.
+    pub fn is_weak_ref_to(&self, env: &JNIEnv, obj: JObject) -> Result<bool> {
+        env.is_same_object(JObject::from(self.as_raw()), obj)
+    }
+
+    /// Get the raw weak global reference, for passing to other JNI code.
+    ///
+    /// The reference stays owned by this `WeakRef`, so it must not be
+    /// deleted, and must not be used after the last clone is dropped.
+    pub fn as_raw(&self) -> sys::jobject {
+        self.inner.raw
+    }
+
+    /// Returns a new local reference to the object, or `None` if it has been
+    /// garbage collected.
+    pub fn upgrade_local<'a>(&self, env: &JNIEnv<'a>) -> Result<Option<JObject<'a>>> {
+        let obj: JObject =
+            jni_unchecked!(env.get_native_interface(), NewLocalRef, self.inner.raw).into();
+        Ok(if obj.is_null() { None } else { Some(obj) })
+    }
+
+    /// Returns a new global reference to the object, or `None` if it has been
+    /// garbage collected.
+    pub fn upgrade_global(&self, env: &JNIEnv) -> Result<Option<GlobalRef>> {
+        let raw = jni_unchecked!(env.get_native_interface(), NewGlobalRef, self.inner.raw);
+        if raw.is_null() {
+            return Ok(None);
+        }
+        Ok(Some(unsafe {
+            GlobalRef::from_raw(env.get_java_vm()?, raw)
+        }))
+    }
+}
+
+impl Drop for WeakRefGuard {
+    fn drop(&mut self) {
+        fn drop_impl(env: &JNIEnv, raw: sys::jobject) -> Result<()> {
+            let internal = env.get_native_interface();
+            // This method is safe to call in case of pending exceptions (see chapter 2 of the spec)
+            jni_unchecked!(internal, DeleteWeakGlobalRef, raw);
+            Ok(())
+        }
+
+        let res = match self.vm.get_env() {
+            Ok(env) => drop_impl(&env, self.raw),
+            Err(_) => {
+                warn!("Dropping a WeakRef in a detached thread. Fix your code if this message appears frequently (see the WeakRef docs).");
+                self.vm
+                    .attach_current_thread()
+                    .and_then(|env| drop_impl(&env, self.raw))
+            }
+        };
+
+        if let Err(err) = res {
+            debug!("error dropping weak ref: {:#?}", err);
+        }
+    }
+}
+
+impl<'a> From<&'a WeakRef> for sys::jobject {
+    fn from(other: &'a WeakRef) -> sys::jobject {
+        other.inner.raw
+    }
+}
@@ -0,0 +1,38 @@
+/// Runs a block of code in a new local reference frame, so all the local
+/// references created in it are deleted once it completes.
+///
+/// The block is run as the body of a closure returning a `Result`, so `?` can
+/// be used in it: if it fails, the frame is still popped before the error is
+/// returned. `return` likewise only leaves the block. The expression evaluates
+/// to the `Result` of the block, or to an `Err` if the frame can't be pushed
+/// or popped.
+///
+/// This is an alternative to `JNIEnv::with_local_frame` for blocks that
+/// return a value other than a `JObject`. Since the frame is popped before the
+/// value is returned, the value must not contain any local references created
+/// in the block.
+///
+/// # Example
+/// ```rust,ignore
+/// let total: jint = local_scope!(env, 16, {
+///     let list = env.new_object("java/util/ArrayList", "()V", &[])?;
+///     env.call_method(list, "size", "()I", &[])?.i()
+/// })?;
+/// ```
+#[macro_export]
+macro_rules! local_scope {
+    ($env:expr, $capacity:expr, $body:block) => {{
+        let env: &$crate::JNIEnv = &$env;
+        match env.push_local_frame($capacity) {
+            Ok(()) => {
+                #[allow(clippy::redundant_closure_call)]
+                let result = (|| -> $crate::errors::Result<_> { $body })();
+                match env.pop_local_frame($crate::objects::JObject::null()) {
+                    Ok(_) => result,
+                    Err(e) => Err(e),
+                }
+            }
+            Err(e) => Err(e),
+        }
+    }};
+}
@@ -4,7 +4,7 @@ use combine::{
     between, many, many1, parser, satisfy, token, ParseError, Parser, StdParseResult, Stream,
 };
 
-use crate::errors::*;
+use crate::{errors::*, strings::JNIString};
 
 /// A primitive java type. These are the things that can be represented without
 /// an object.
@@ -70,6 +70,20 @@ impl fmt::Display for JavaType {
     }
 }
 
+/// Renders the type as its JVM descriptor, so that a `JavaType` can be passed
+/// wherever a signature string is expected, e.g. to `JNIEnv::get_field_id`.
+impl From<JavaType> for JNIString {
+    fn from(other: JavaType) -> Self {
+        other.to_string().into()
+    }
+}
+
+impl<'a> From<&'a JavaType> for JNIString {
+    fn from(other: &'a JavaType) -> Self {
+        other.to_string().into()
+    }
+}
+
 /// A method type signature. This is the structure representation of something
 /// like `(Ljava/lang/String;)Z`. Used by the `call_(object|static)_method`
 /// functions on jnienv to ensure safety.
@@ -80,7 +94,49 @@ pub struct TypeSignature {
     pub ret: JavaType,
 }
 
+impl Default for TypeSignature {
+    fn default() -> Self {
+        TypeSignature::new()
+    }
+}
+
 impl TypeSignature {
+    /// Start building a signature with no arguments and a `void` return type.
+    ///
+    /// ```rust
+    /// # use jni::signature::*;
+    /// let sig = TypeSignature::new()
+    ///     .arg(JavaType::Object("java/lang/String".into()))
+    ///     .arg(JavaType::Primitive(Primitive::Int))
+    ///     .ret(JavaType::Primitive(Primitive::Void))
+    ///     .build();
+    /// assert_eq!(String::from(sig), "(Ljava/lang/String;I)V");
+    /// ```
+    pub fn new() -> Self {
+        TypeSignature {
+            args: Vec::new(),
+            ret: JavaType::Primitive(Primitive::Void),
+        }
+    }
+
+    /// Append an argument type.
+    pub fn arg(mut self, ty: JavaType) -> Self {
+        self.args.push(ty);
+        self
+    }
+
+    /// Set the return type.
+    pub fn ret(mut self, ty: JavaType) -> Self {
+        self.ret = ty;
+        self
+    }
+
+    /// Build the method descriptor, e.g. `(Ljava/lang/String;I)V`, ready to be
+    /// passed to the method lookup and call functions on `JNIEnv`.
+    pub fn build(&self) -> JNIString {
+        self.to_string().into()
+    }
+
     /// Parse a signature string into a TypeSignature enum.
     // Clippy suggests implementing `FromStr` or renaming it which is not possible in our case.
     #[allow(clippy::should_implement_trait)]
@@ -210,6 +266,66 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_builder() {
+        let sig = TypeSignature::new()
+            .arg(JavaType::Object("java/lang/String".into()))
+            .arg(JavaType::Primitive(Primitive::Int))
+            .ret(JavaType::Primitive(Primitive::Void))
+            .build();
+        assert_eq!(String::from(sig), "(Ljava/lang/String;I)V");
+
+        let sig = TypeSignature::new()
+            .arg(JavaType::Array(Box::new(JavaType::Primitive(
+                Primitive::Byte,
+            ))))
+            .arg(JavaType::Primitive(Primitive::Boolean))
+            .ret(JavaType::Array(Box::new(JavaType::Object(
+                "java/lang/Object".into(),
+            ))))
+            .build();
+        assert_eq!(String::from(sig), "([BZ)[Ljava/lang/Object;");
+
+        assert_eq!(String::from(TypeSignature::new().build()), "()V");
+    }
+
+    #[test]
+    fn test_builder_round_trip() {
+        let inputs = [
+            "(Ljava/lang/String;I)V",
+            "(IBCZ)Ljava/lang/String;",
+            "([[JLjava/util/List;D)[C",
+            "()S",
+        ];
+
+        for each in inputs.iter() {
+            let parsed = TypeSignature::from_str(*each).unwrap();
+            let built = parsed
+                .args
+                .iter()
+                .cloned()
+                .fold(TypeSignature::new(), TypeSignature::arg)
+                .ret(parsed.ret.clone())
+                .build();
+            let built = String::from(built);
+            assert_eq!(built, *each);
+            assert_eq!(
+                JavaType::from_str(&built).unwrap(),
+                JavaType::Method(Box::new(parsed))
+            );
+        }
+    }
+
+    #[test]
+    fn test_java_type_to_jni_string() {
+        let ty = JavaType::Array(Box::new(JavaType::Object("java/lang/String".into())));
+        assert_eq!(String::from(JNIString::from(&ty)), "[Ljava/lang/String;");
+        assert_eq!(
+            String::from(JNIString::from(JavaType::Primitive(Primitive::Int))),
+            "I"
+        );
+    }
+
     #[test]
     fn test_parser_invalid_signature() {
         let signature = "()Ljava/lang/List"; // no semicolon
@@ -7,11 +7,14 @@ use std::{
 use cesu8::{from_java_cesu8, to_java_cesu8};
 use log::debug;
 
-use crate::wrapper::strings::ffi_str;
+use crate::{errors::*, wrapper::strings::ffi_str};
 
 /// Wrapper for `std::ffi::CString` that also takes care of encoding between
 /// UTF-8 and Java's Modified UTF-8. As with `CString`, this implements `Deref`
 /// to `&JNIStr`.
+///
+/// Unlike `CString`, this can hold strings with NUL characters, which Modified
+/// UTF-8 encodes as `0xC0 0x80`, so they don't terminate the string early.
 pub struct JNIString {
     internal: ffi::CString,
 }
@@ -77,6 +80,10 @@ impl JNIString {
     }
 }
 
+/// Java's modified UTF-8 encodes NUL characters as `0xC0 0x80` and
+/// supplementary characters as surrogate pairs. Use the `Cow<str>` conversion
+/// or `try_to_str` to decode it, rather than `CStr::to_str`, which expects
+/// standard UTF-8.
 impl JNIStr {
     /// Construct a reference to a `JNIStr` from a pointer. Equivalent to `CStr::from_ptr`.
     ///
@@ -87,6 +94,56 @@ impl JNIStr {
     pub unsafe fn from_ptr<'a>(ptr: *const c_char) -> &'a JNIStr {
         &*(ffi::CStr::from_ptr(ptr) as *const ffi::CStr as *const ffi_str::JNIStr)
     }
+
+    /// Decode the string from Java's Modified UTF-8. Unlike the `Cow<str>`
+    /// conversion, which replaces malformed sequences, this fails on them.
+    ///
+    /// Returns `Err` with the kind `InvalidUtf8` and the byte offset of the
+    /// first malformed sequence if the string can't be decoded, for example
+    /// because it contains an unpaired surrogate.
+    pub fn try_to_str(&self) -> Result<Cow<'_, str>> {
+        let bytes = self.to_bytes();
+        from_java_cesu8(bytes).map_err(|_| Error::InvalidUtf8 {
+            offset: invalid_java_cesu8_offset(bytes),
+        })
+    }
+}
+
+/// Finds the offset of the first sequence that isn't valid Modified UTF-8.
+fn invalid_java_cesu8_offset(bytes: &[u8]) -> usize {
+    let mut i = 0;
+    while i < bytes.len() {
+        let rest = &bytes[i..];
+        let len = match rest {
+            // Encoded NUL
+            [0xC0, 0x80, ..] => {
+                i += 2;
+                continue;
+            }
+            // Supplementary character, encoded as a surrogate pair of two
+            // three-byte sequences
+            [0xED, 0xA0..=0xAF, c1, 0xED, 0xB0..=0xBF, c2, ..]
+                if is_continuation(*c1) && is_continuation(*c2) =>
+            {
+                i += 6;
+                continue;
+            }
+            [0x00..=0x7F, ..] => 1,
+            [0xC2..=0xDF, ..] => 2,
+            [0xE0..=0xEF, ..] => 3,
+            // Four-byte sequences are not used
+            _ => return i,
+        };
+        match rest.get(..len) {
+            Some(seq) if ::std::str::from_utf8(seq).is_ok() => i += len,
+            _ => return i,
+        }
+    }
+    i
+}
+
+fn is_continuation(byte: u8) -> bool {
+    byte & 0xC0 == 0x80
 }
 
 // impls for CoW
@@ -107,3 +164,22 @@ impl ToOwned for JNIStr {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_nul_round_trip() {
+        let string = JNIString::from("a\0b");
+        assert_eq!(string.to_bytes(), &[b'a', 0xC0, 0x80, b'b']);
+        assert_eq!(String::from(string), "a\0b");
+    }
+
+    #[test]
+    fn test_supplementary_round_trip() {
+        let string = JNIString::from("\u{1F600}");
+        assert_eq!(string.to_bytes(), &[0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80]);
+        assert_eq!(String::from(string), "\u{1F600}");
+    }
+}
@@ -1,4 +1,8 @@
-use std::{borrow::Cow, os::raw::c_char};
+use std::{
+    borrow::Cow,
+    ffi::{CString, NulError},
+    os::raw::c_char,
+};
 
 use log::warn;
 
@@ -29,9 +33,25 @@ impl<'a: 'b, 'b> JavaStr<'a, 'b> {
 
     /// Extract the raw C string pointer from the JavaStr. This will be
     /// encoded using the JVM internal `CESU-8`-style.
+    ///
+    /// The pointer stays valid until the JavaStr is dropped. The modified
+    /// UTF-8 differs from standard UTF-8 for the NUL character, which is
+    /// encoded in two bytes so it can't end the string early, and for
+    /// supplementary characters, which are encoded as two surrogates of three
+    /// bytes each.
     pub fn get_raw(&self) -> *const c_char {
         self.internal
     }
+
+    /// Copy the string into a new C string in standard UTF-8, for native code
+    /// that can't handle the modified UTF-8 returned by `get_raw`.
+    ///
+    /// Returns `Err` if the string contains a NUL character, like
+    /// `CString::new`.
+    pub fn to_utf8_cstring(&self) -> ::std::result::Result<CString, NulError> {
+        let cow: Cow<str> = self.into();
+        CString::new(cow.into_owned())
+    }
 }
 
 impl<'a: 'b, 'b> ::std::ops::Deref for JavaStr<'a, 'b> {
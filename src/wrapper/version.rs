@@ -2,10 +2,16 @@ use crate::sys::{
     JNI_VERSION_1_1, JNI_VERSION_1_2, JNI_VERSION_1_4, JNI_VERSION_1_6, JNI_VERSION_1_8,
 };
 
+// Not defined by jni-sys 0.3.0
+const JNI_VERSION_9: i32 = 0x0009_0000;
+const JNI_VERSION_10: i32 = 0x000a_0000;
+
 /// JNI Version
 ///
-/// This maps to the `jni_sys::JNI_VERSION_1_*` constants.
-#[derive(Debug, Copy, Clone)]
+/// This maps to the `jni_sys::JNI_VERSION_*` constants. Versions that are
+/// not known to this crate, such as the ones of future java releases, are
+/// kept as their raw value in `Invalid`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[allow(missing_docs)]
 pub enum JNIVersion {
     V1,
@@ -13,6 +19,8 @@ pub enum JNIVersion {
     V4,
     V6,
     V8,
+    V9,
+    V10,
     Invalid(i32),
 }
 
@@ -24,6 +32,8 @@ impl From<i32> for JNIVersion {
             JNI_VERSION_1_4 => JNIVersion::V4,
             JNI_VERSION_1_6 => JNIVersion::V6,
             JNI_VERSION_1_8 => JNIVersion::V8,
+            JNI_VERSION_9 => JNIVersion::V9,
+            JNI_VERSION_10 => JNIVersion::V10,
             v => JNIVersion::Invalid(v),
         }
     }
@@ -37,6 +47,8 @@ impl From<JNIVersion> for i32 {
             JNIVersion::V4 => JNI_VERSION_1_4,
             JNIVersion::V6 => JNI_VERSION_1_6,
             JNIVersion::V8 => JNI_VERSION_1_8,
+            JNIVersion::V9 => JNI_VERSION_9,
+            JNIVersion::V10 => JNI_VERSION_10,
             JNIVersion::Invalid(v) => v,
         }
     }
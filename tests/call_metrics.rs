@@ -0,0 +1,76 @@
+#![cfg(all(feature = "invocation", feature = "metrics"))]
+
+use std::sync::Mutex;
+
+use metrics::{Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit};
+
+use jni::objects::JValue;
+
+mod util;
+use util::{attach_current_thread, unwrap};
+
+/// Collects the names and labels of the registered metrics.
+#[derive(Default)]
+struct KeyRecorder {
+    keys: Mutex<Vec<String>>,
+}
+
+impl KeyRecorder {
+    fn register(&self, key: &Key) {
+        let labels: Vec<String> = key
+            .labels()
+            .map(|label| format!("{}={}", label.key(), label.value()))
+            .collect();
+        self.keys
+            .lock()
+            .unwrap()
+            .push(format!("{}{{{}}}", key.name(), labels.join(",")));
+    }
+}
+
+impl Recorder for KeyRecorder {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        self.register(key);
+        Counter::noop()
+    }
+
+    fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+        self.register(key);
+        Gauge::noop()
+    }
+
+    fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+        self.register(key);
+        Histogram::noop()
+    }
+}
+
+#[test]
+pub fn method_calls_are_recorded() {
+    let env = attach_current_thread();
+    let recorder = KeyRecorder::default();
+
+    metrics::with_local_recorder(&recorder, || {
+        let abs = env.call_static_method("java/lang/Math", "abs", "(I)I", &[JValue::from(-1)]);
+        assert_eq!(unwrap(&env, abs).i().unwrap(), 1);
+
+        let string = env.new_string("string").unwrap();
+        let len = env.call_method(string, "length", "()I", &[]);
+        assert_eq!(unwrap(&env, len).i().unwrap(), 6);
+    });
+
+    let keys = recorder.keys.lock().unwrap();
+    assert_eq!(
+        *keys,
+        vec![
+            "jni_calls_total{class=java.lang.Math,method=abs}",
+            "jni_call_duration_seconds{class=java.lang.Math,method=abs}",
+            "jni_calls_total{class=java.lang.String,method=length}",
+            "jni_call_duration_seconds{class=java.lang.String,method=length}",
+        ]
+    );
+}
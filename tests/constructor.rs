@@ -0,0 +1,60 @@
+#![cfg(feature = "invocation")]
+
+use std::{sync::Arc, thread::spawn};
+
+use jni::{errors::Error, objects::JValue};
+
+mod util;
+use util::{attach_current_thread, unwrap};
+
+#[test]
+pub fn constructor_new_instance() {
+    let env = attach_current_thread();
+    let ctor = unwrap(&env, env.get_constructor("java/lang/Integer", "(I)V"));
+
+    let integer = unwrap(&env, ctor.new_instance(&env, &[JValue::Int(42)]));
+    assert!(unwrap(&env, env.is_instance_of(integer, ctor.class())));
+    let value = unwrap(&env, env.call_method(integer, "intValue", "()I", &[]));
+    assert_eq!(value.i().unwrap(), 42);
+
+    let result = ctor.new_instance(&env, &[]);
+    assert!(matches!(result, Err(Error::InvalidArgList(_))));
+}
+
+#[test]
+pub fn constructor_invalid_return() {
+    let env = attach_current_thread();
+    let result = env.get_constructor("java/lang/Integer", "(I)I");
+    assert!(matches!(result, Err(Error::InvalidCtorReturn)));
+}
+
+#[test]
+pub fn constructor_from_other_threads() {
+    let ctor = {
+        let env = attach_current_thread();
+        Arc::new(unwrap(
+            &env,
+            env.get_constructor("java/lang/StringBuilder", "(I)V"),
+        ))
+    };
+
+    let threads: Vec<_> = (0..4)
+        .map(|i| {
+            let ctor = ctor.clone();
+            spawn(move || {
+                let env = attach_current_thread();
+                for capacity in 0..100 {
+                    let capacity = i * 100 + capacity;
+                    let builder = unwrap(&env, ctor.new_instance(&env, &[JValue::Int(capacity)]));
+                    let actual = unwrap(&env, env.call_method(builder, "capacity", "()I", &[]));
+                    assert_eq!(actual.i().unwrap(), capacity);
+                    env.delete_local_ref(builder).unwrap();
+                }
+            })
+        })
+        .collect();
+
+    for thread in threads {
+        thread.join().unwrap();
+    }
+}
@@ -0,0 +1,68 @@
+#![cfg(all(feature = "invocation", feature = "debug-refs", debug_assertions))]
+
+use jni::objects::JObject;
+
+mod util;
+use util::{attach_current_thread, unwrap};
+
+#[test]
+#[should_panic(expected = "deleted twice")]
+pub fn double_delete_local_ref_panics() {
+    let env = attach_current_thread();
+    let string = unwrap(&env, env.new_string("string"));
+
+    unwrap(&env, env.delete_local_ref(*string));
+    let _ = env.delete_local_ref(*string);
+}
+
+#[test]
+pub fn delete_reused_local_ref() {
+    let env = attach_current_thread();
+
+    // The slots of the deleted references get reused, which must not be
+    // mistaken for double deletes.
+    for _ in 0..100 {
+        let string = unwrap(&env, env.new_string("string"));
+        unwrap(&env, env.delete_local_ref(*string));
+    }
+}
+
+#[test]
+#[should_panic(expected = "used after being deleted")]
+pub fn use_deleted_local_ref_panics() {
+    let env = attach_current_thread();
+    let string = unwrap(&env, env.new_string("string"));
+
+    unwrap(&env, env.delete_local_ref(*string));
+    let _ = env.get_object_class(string);
+}
+
+#[test]
+pub fn delete_local_ref_in_popped_frame() {
+    let env = attach_current_thread();
+
+    // The slots of a popped frame get reused by the references created after it
+    for _ in 0..100 {
+        unwrap(
+            &env,
+            env.with_local_frame(4, || {
+                let string = env.new_string("string")?;
+                env.delete_local_ref(*string)?;
+                Ok(JObject::null())
+            }),
+        );
+        let string = env.auto_local(unwrap(&env, env.new_string("string")));
+        let class = unwrap(&env, env.get_object_class(&string));
+        unwrap(&env, env.delete_local_ref(*class));
+    }
+}
+
+#[test]
+pub fn delete_local_ref_with_pending_exception() {
+    let env = attach_current_thread();
+    let string = env.auto_local(unwrap(&env, env.new_string("string")));
+
+    unwrap(&env, env.throw_new("java/lang/RuntimeException", "pending"));
+    drop(string);
+    unwrap(&env, env.exception_clear());
+}
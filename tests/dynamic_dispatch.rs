@@ -0,0 +1,67 @@
+#![cfg(feature = "invocation")]
+
+use jni::{
+    errors::{Error, JniError},
+    objects::DynamicDispatch,
+    objects::JObject,
+};
+
+mod util;
+use util::{attach_current_thread, unwrap};
+
+#[test]
+pub fn dynamic_dispatch_unrelated_classes() {
+    let env = attach_current_thread();
+    let dispatch = DynamicDispatch::new("length", "()I").unwrap();
+
+    let string: JObject = env.new_string("four").unwrap().into();
+    let builder = env
+        .new_object(
+            "java/lang/StringBuilder",
+            "(Ljava/lang/String;)V",
+            &[env.new_string("twelve chars").unwrap().into()],
+        )
+        .unwrap();
+
+    for _ in 0..2 {
+        let length = unwrap(&env, dispatch.call(&env, string, &[]));
+        assert_eq!(length.i().unwrap(), 4);
+        let length = unwrap(&env, dispatch.call(&env, builder, &[]));
+        assert_eq!(length.i().unwrap(), 12);
+    }
+    assert_eq!(dispatch.len(), 2);
+}
+
+#[test]
+pub fn dynamic_dispatch_evicts_oldest_class() {
+    let env = attach_current_thread();
+    let dispatch = DynamicDispatch::with_capacity("hashCode", "()I", 1).unwrap();
+
+    let string: JObject = env.new_string("string").unwrap().into();
+    let obj = env.new_object("java/lang/Object", "()V", &[]).unwrap();
+
+    unwrap(&env, dispatch.call(&env, string, &[]));
+    unwrap(&env, dispatch.call(&env, obj, &[]));
+    assert_eq!(dispatch.len(), 1);
+}
+
+#[test]
+pub fn dynamic_dispatch_zero_capacity() {
+    let result = DynamicDispatch::with_capacity("hashCode", "()I", 0);
+    assert!(matches!(
+        result,
+        Err(Error::JniCall(JniError::InvalidArguments))
+    ));
+}
+
+#[test]
+pub fn dynamic_dispatch_method_not_found() {
+    let env = attach_current_thread();
+    let dispatch = DynamicDispatch::new("length", "()I").unwrap();
+    let obj = env.new_object("java/lang/Object", "()V", &[]).unwrap();
+
+    let result = dispatch.call(&env, obj, &[]);
+    assert!(matches!(result, Err(Error::JavaException)));
+    env.exception_clear().unwrap();
+    assert!(dispatch.is_empty());
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "invocation")]
+
+use std::{sync::Arc, thread::spawn};
+
+use jni::{errors::Error, objects::EnumCache};
+
+mod util;
+use util::{attach_current_thread, unwrap};
+
+const TIME_UNIT_CLASS: &str = "java/util/concurrent/TimeUnit";
+
+#[test]
+pub fn enum_cache_get() {
+    let env = attach_current_thread();
+    let cache = EnumCache::new(TIME_UNIT_CLASS);
+    assert!(cache.is_empty());
+
+    let seconds = unwrap(&env, cache.get(&env, "SECONDS"));
+    assert!(unwrap(
+        &env,
+        env.is_instance_of(seconds.as_obj(), TIME_UNIT_CLASS)
+    ));
+    let millis = unwrap(
+        &env,
+        env.call_method(seconds.as_obj(), "toMillis", "(J)J", &[2i64.into()]),
+    );
+    assert_eq!(millis.j().unwrap(), 2000);
+
+    // Served from the cache
+    let again = unwrap(&env, cache.get(&env, "SECONDS"));
+    assert!(unwrap(
+        &env,
+        env.is_same_object(seconds.as_obj(), again.as_obj())
+    ));
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+pub fn enum_cache_get_unknown_constant() {
+    let env = attach_current_thread();
+    let cache = EnumCache::new(TIME_UNIT_CLASS);
+
+    let result = cache.get(&env, "FORTNIGHTS");
+    assert!(matches!(result, Err(Error::FieldNotFound { .. })));
+    assert!(!env.exception_check().unwrap());
+    assert!(cache.is_empty());
+}
+
+#[test]
+pub fn enum_cache_name_of() {
+    let env = attach_current_thread();
+    let cache = EnumCache::new(TIME_UNIT_CLASS);
+
+    let name = unwrap(&env, env.new_string("MINUTES"));
+    let minutes = unwrap(
+        &env,
+        env.call_static_method(
+            TIME_UNIT_CLASS,
+            "valueOf",
+            "(Ljava/lang/String;)Ljava/util/concurrent/TimeUnit;",
+            &[name.into()],
+        ),
+    )
+    .l()
+    .unwrap();
+    assert_eq!(unwrap(&env, cache.name_of(&env, minutes)), "MINUTES");
+
+    // The constant found by name is the same one
+    let cached = unwrap(&env, cache.get(&env, "MINUTES"));
+    assert!(unwrap(&env, env.is_same_object(minutes, cached.as_obj())));
+    assert_eq!(cache.len(), 1);
+    assert_eq!(unwrap(&env, cache.name_of(&env, minutes)), "MINUTES");
+}
+
+#[test]
+pub fn enum_cache_name_of_wrong_class() {
+    let env = attach_current_thread();
+    let cache = EnumCache::new(TIME_UNIT_CLASS);
+
+    let string = unwrap(&env, env.new_string("SECONDS"));
+    let result = cache.name_of(&env, string);
+    assert!(matches!(result, Err(Error::WrongObjectClass(_))));
+}
+
+#[test]
+pub fn enum_cache_shared_between_threads() {
+    let cache = Arc::new(EnumCache::new(TIME_UNIT_CLASS));
+
+    let threads: Vec<_> = ["DAYS", "HOURS", "DAYS", "HOURS"]
+        .iter()
+        .map(|&name| {
+            let cache = cache.clone();
+            spawn(move || {
+                let env = attach_current_thread();
+                let constant = unwrap(&env, cache.get(&env, name));
+                assert_eq!(unwrap(&env, cache.name_of(&env, constant.as_obj())), name);
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(cache.len(), 2);
+}
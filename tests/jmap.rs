@@ -39,3 +39,26 @@ pub fn jmap_push_and_iterate() {
     let orig = data.to_vec();
     assert_eq!(orig, collected);
 }
+
+#[test]
+pub fn jmap_get_put_remove() {
+    let env = attach_current_thread();
+
+    let map_object = unwrap(&env, env.new_object("java/util/HashMap", "()V", &[]));
+    let map = unwrap(&env, JMap::from_env(&env, map_object));
+    let key: JObject = env.new_string("key").unwrap().into();
+    let first: JObject = env.new_string("first").unwrap().into();
+    let second: JObject = env.new_string("second").unwrap().into();
+
+    assert!(unwrap(&env, map.get(key)).is_none());
+    assert!(unwrap(&env, map.put(key, first)).is_none());
+
+    let old = unwrap(&env, map.put(key, second)).unwrap();
+    assert!(env.is_same_object(old, first).unwrap());
+    let value = unwrap(&env, map.get(key)).unwrap();
+    assert!(env.is_same_object(value, second).unwrap());
+
+    let removed = unwrap(&env, map.remove(key)).unwrap();
+    assert!(env.is_same_object(removed, second).unwrap());
+    assert!(unwrap(&env, map.remove(key)).is_none());
+}
@@ -1,21 +1,31 @@
 #![cfg(feature = "invocation")]
 
-use std::str::FromStr;
+use std::{
+    convert::{TryFrom, TryInto},
+    str::FromStr,
+    sync::Arc,
+};
 
 use jni::{
+    call,
     descriptors::Desc,
-    errors::Error,
+    errors::{Error, MapJavaErr},
+    jni_signature, local_scope,
     objects::{
-        AutoArray, AutoLocal, JByteBuffer, JList, JObject, JString, JThrowable, JValue, ReleaseMode,
+        AutoArray, AutoLocal, JByteBuffer, JClass, JIterator, JList, JObject, JString, JThrowable,
+        JValue, Modifiers, RefEq, RefType, ReleaseMode,
     },
-    signature::JavaType,
+    signature::{JavaType, Primitive, TypeSignature},
     strings::JNIString,
     sys::{jboolean, jbyte, jchar, jdouble, jfloat, jint, jlong, jobject, jshort, jsize},
-    JNIEnv,
+    JNIEnv, NativeMethod,
 };
 
 mod util;
-use util::{attach_current_thread, unwrap};
+use util::{
+    attach_current_thread, u2s, unwrap, ClassFile, ACC_FINAL, ACC_PRIVATE, ACC_PUBLIC, ACC_STATIC,
+    ACC_SUPER,
+};
 
 static ARRAYLIST_CLASS: &str = "java/util/ArrayList";
 static EXCEPTION_CLASS: &str = "java/lang/Exception";
@@ -154,8 +164,6 @@ pub fn get_static_public_field() {
 pub fn get_static_public_field_by_id() {
     let env = attach_current_thread();
 
-    // One can't pass a JavaType::Primitive(Primitive::Int) to
-    //   `get_static_field_id` unfortunately: #137
     let field_type = "I";
     let field_id = env
         .get_static_field_id(INTEGER_CLASS, "MIN_VALUE", field_type)
@@ -171,6 +179,455 @@ pub fn get_static_public_field_by_id() {
     assert_eq!(min_int_value, i32::min_value());
 }
 
+#[test]
+pub fn get_static_public_field_by_id_java_type() {
+    let env = attach_current_thread();
+
+    let field_type = JavaType::Primitive(Primitive::Int);
+    let field_id = env
+        .get_static_field_id(INTEGER_CLASS, "MIN_VALUE", &field_type)
+        .unwrap();
+
+    let min_int_value = env
+        .get_static_field_unchecked(INTEGER_CLASS, field_id, field_type)
+        .unwrap()
+        .i()
+        .unwrap();
+
+    assert_eq!(min_int_value, i32::min_value());
+}
+
+#[test]
+pub fn get_public_field_by_id_java_type() {
+    let env = attach_current_thread();
+    let point = unwrap(
+        &env,
+        env.new_object("java/awt/Point", "(II)V", &[3.into(), 4.into()]),
+    );
+
+    let field_type = JavaType::Primitive(Primitive::Int);
+    let field_id = unwrap(
+        &env,
+        env.get_field_id("java/awt/Point", "y", field_type.clone()),
+    );
+
+    let y = unwrap(&env, env.get_field_unchecked(point, field_id, field_type));
+    assert_eq!(y.i().unwrap(), 4);
+}
+
+#[test]
+pub fn field_unchecked_with_prefetched_id() {
+    let env = attach_current_thread();
+    let int_type = JavaType::Primitive(Primitive::Int);
+    let x = unwrap(
+        &env,
+        env.get_field_id("java/awt/Point", "x", int_type.clone()),
+    );
+    let points: Vec<JObject> = (0..3)
+        .map(|_| unwrap(&env, env.new_object("java/awt/Point", "()V", &[])))
+        .collect();
+
+    for (i, point) in points.iter().enumerate() {
+        unwrap(
+            &env,
+            env.set_field_unchecked(*point, x, JValue::Int(i as jint * 10)),
+        );
+    }
+    let xs: Vec<jint> = points
+        .iter()
+        .map(|point| {
+            let value = unwrap(&env, env.get_field_unchecked(*point, x, int_type.clone()));
+            value.i().unwrap()
+        })
+        .collect();
+    assert_eq!(xs, [0, 10, 20]);
+
+    // Object fields
+    let object_type = JavaType::Object("java/lang/Object".into());
+    let value = unwrap(
+        &env,
+        env.get_field_id(
+            "java/util/concurrent/atomic/AtomicReference",
+            "value",
+            object_type.clone(),
+        ),
+    );
+    let reference = unwrap(
+        &env,
+        env.new_object("java/util/concurrent/atomic/AtomicReference", "()V", &[]),
+    );
+    let string = unwrap(&env, env.new_string("value"));
+    unwrap(
+        &env,
+        env.set_field_unchecked(reference, value, string.into()),
+    );
+    let read = unwrap(&env, env.get_field_unchecked(reference, value, object_type));
+    assert!(unwrap(&env, env.is_same_object(read.l().unwrap(), string)));
+
+    let result = env.get_field_unchecked(reference, value, JavaType::Primitive(Primitive::Void));
+    assert!(matches!(result, Err(Error::WrongJValueType(..))));
+    let result = env.get_field_unchecked(JObject::null(), value, int_type);
+    assert!(matches!(result, Err(Error::NullPtr(_))));
+}
+
+// The class file of `public class <name> { public static int count; public static Object value; }`
+fn static_holder_class_file(name: &str) -> Vec<u8> {
+    let mut class = ClassFile::new(52);
+    class.field(ACC_PUBLIC | ACC_STATIC, "count", "I");
+    class.field(ACC_PUBLIC | ACC_STATIC, "value", "Ljava/lang/Object;");
+    class.finish(ACC_PUBLIC | ACC_SUPER, name, "java/lang/Object")
+}
+
+fn new_class_loader<'a>(env: &JNIEnv<'a>) -> JObject<'a> {
+    let urls = unwrap(
+        env,
+        env.new_object_array(0, "java/net/URL", JObject::null()),
+    );
+    unwrap(
+        env,
+        env.new_object(
+            "java/net/URLClassLoader",
+            "([Ljava/net/URL;)V",
+            &[JObject::from(urls).into()],
+        ),
+    )
+}
+
+// Defines the class in a new class loader, so it can be defined for each test
+fn define_static_holder_class<'a>(env: &JNIEnv<'a>, name: &str) -> JClass<'a> {
+    let loader = new_class_loader(env);
+    unwrap(
+        env,
+        env.define_class(name, loader, &static_holder_class_file(name)),
+    )
+}
+
+// Defines a class with a public `value` field in a new class loader, and
+// creates an instance of it
+fn new_field_holder<'a>(env: &JNIEnv<'a>, ty: &str) -> JObject<'a> {
+    let name = "com/example/FieldHolder";
+    let mut class_file = ClassFile::new(52);
+    class_file.field(ACC_PUBLIC, "value", ty);
+    let class_file = class_file.finish(ACC_PUBLIC | ACC_SUPER, name, "java/lang/Object");
+
+    let loader = new_class_loader(env);
+    let class = unwrap(env, env.define_class(name, loader, &class_file));
+    unwrap(env, env.alloc_object(class))
+}
+
+#[test]
+pub fn define_class() {
+    let env = attach_current_thread();
+    let loader = new_class_loader(&env);
+    let class_file = static_holder_class_file("com/example/Generated");
+
+    let class = unwrap(
+        &env,
+        env.define_class("com/example/Generated", loader, &class_file),
+    );
+    let name = unwrap(
+        &env,
+        env.call_method(class, "getName", "()Ljava/lang/String;", &[]),
+    );
+    let name: String = unwrap(&env, env.get_string(name.l().unwrap().into())).into();
+    assert_eq!(name, "com.example.Generated");
+    let class_loader = unwrap(
+        &env,
+        env.call_method(class, "getClassLoader", "()Ljava/lang/ClassLoader;", &[]),
+    );
+    assert!(unwrap(
+        &env,
+        env.is_same_object(class_loader.l().unwrap(), loader)
+    ));
+
+    // The name is inferred from the class file
+    let other_loader = new_class_loader(&env);
+    let unnamed = unwrap(&env, env.define_unnamed_class(other_loader, &class_file));
+    assert!(!unwrap(&env, env.is_same_object(unnamed, class)));
+}
+
+#[test]
+pub fn define_class_errors() {
+    let env = attach_current_thread();
+    let loader = new_class_loader(&env);
+    let class_file = static_holder_class_file("Duplicate");
+    unwrap(&env, env.define_class("Duplicate", loader, &class_file));
+
+    let invalid = &class_file[..class_file.len() - 1];
+    let cases = [
+        (invalid, "java/lang/ClassFormatError"),
+        (&class_file[..], "java/lang/LinkageError"),
+    ];
+    for (bytes, exception_class) in cases.iter() {
+        let result = env.define_class("Duplicate", loader, bytes);
+        assert!(matches!(result, Err(Error::JavaException)));
+        let exception = unwrap(&env, env.exception_occurred());
+        unwrap(&env, env.exception_clear());
+        assert!(unwrap(
+            &env,
+            env.is_instance_of(exception, *exception_class)
+        ));
+    }
+}
+
+#[test]
+pub fn set_static_field() {
+    let env = attach_current_thread();
+    let class = define_static_holder_class(&env, "StaticHolder");
+
+    unwrap(&env, env.set_static_field(class, "count", "I", 7.into()));
+    let count = unwrap(&env, env.get_static_field(class, "count", "I"));
+    assert_eq!(count.i().unwrap(), 7);
+
+    // The value is checked against the signature
+    let result = env.set_static_field(class, "count", "I", JValue::Long(7));
+    assert!(matches!(result, Err(Error::WrongJValueType(..))));
+    let result = env.set_static_field(class, "value", "Ljava/lang/Object;", JValue::Int(7));
+    assert!(matches!(result, Err(Error::WrongJValueType(..))));
+
+    let int_type = JavaType::Primitive(Primitive::Int);
+    let count_id = unwrap(
+        &env,
+        env.get_static_field_id(class, "count", int_type.clone()),
+    );
+    for i in 0..3 {
+        unwrap(
+            &env,
+            env.set_static_field_unchecked(class, count_id, JValue::Int(i)),
+        );
+    }
+    let count = unwrap(
+        &env,
+        env.get_static_field_unchecked(class, count_id, int_type),
+    );
+    assert_eq!(count.i().unwrap(), 2);
+
+    let string = unwrap(&env, env.new_string("value"));
+    let value_id = unwrap(
+        &env,
+        env.get_static_field_id(class, "value", "Ljava/lang/Object;"),
+    );
+    unwrap(
+        &env,
+        env.set_static_field_unchecked(class, value_id, string.into()),
+    );
+    let value = unwrap(
+        &env,
+        env.get_static_field(class, "value", "Ljava/lang/Object;"),
+    );
+    assert!(unwrap(&env, env.is_same_object(value.l().unwrap(), string)));
+
+    let result = env.set_static_field_unchecked(class, value_id, JValue::Void);
+    assert!(matches!(result, Err(Error::WrongJValueType(..))));
+}
+
+fn member_name(env: &JNIEnv, member: JObject) -> String {
+    let name = unwrap(
+        env,
+        env.call_method(member, "getName", "()Ljava/lang/String;", &[]),
+    );
+    unwrap(env, env.get_string(name.l().unwrap().into())).into()
+}
+
+#[test]
+pub fn reflected_methods() {
+    let env = attach_current_thread();
+    let string_class = unwrap(&env, env.find_class("java/lang/String"));
+    let name = unwrap(&env, env.new_string("length"));
+    let no_types = unwrap(
+        &env,
+        env.new_object_array(0, "java/lang/Class", JObject::null()),
+    );
+    let method = unwrap(
+        &env,
+        env.call_method(
+            string_class,
+            "getMethod",
+            "(Ljava/lang/String;[Ljava/lang/Class;)Ljava/lang/reflect/Method;",
+            &[name.into(), JObject::from(no_types).into()],
+        ),
+    );
+
+    let length = unwrap(&env, env.from_reflected_method(method.l().unwrap()));
+    let string = unwrap(&env, env.new_string("four"));
+    let len = unwrap(
+        &env,
+        env.call_method_unchecked(string, length, JavaType::Primitive(Primitive::Int), &[]),
+    );
+    assert_eq!(len.i().unwrap(), 4);
+
+    let reflected = unwrap(&env, env.to_reflected_method(string_class, length));
+    assert_eq!(member_name(&env, reflected), "length");
+    let value_of = unwrap(
+        &env,
+        env.get_static_method_id(string_class, "valueOf", "(I)Ljava/lang/String;"),
+    );
+    let reflected = unwrap(&env, env.to_reflected_static_method(string_class, value_of));
+    assert_eq!(member_name(&env, reflected), "valueOf");
+
+    let result = env.from_reflected_method(JObject::null());
+    assert!(matches!(result, Err(Error::NullPtr(_))));
+}
+
+#[test]
+pub fn reflected_fields() {
+    let env = attach_current_thread();
+    let point_class = unwrap(&env, env.find_class("java/awt/Point"));
+    let name = unwrap(&env, env.new_string("y"));
+    let field = unwrap(
+        &env,
+        env.call_method(
+            point_class,
+            "getField",
+            "(Ljava/lang/String;)Ljava/lang/reflect/Field;",
+            &[name.into()],
+        ),
+    );
+
+    let y = unwrap(&env, env.from_reflected_field(field.l().unwrap()));
+    let point = unwrap(
+        &env,
+        env.new_object(point_class, "(II)V", &[3.into(), 4.into()]),
+    );
+    let value = unwrap(
+        &env,
+        env.get_field_unchecked(point, y, JavaType::Primitive(Primitive::Int)),
+    );
+    assert_eq!(value.i().unwrap(), 4);
+
+    let reflected = unwrap(&env, env.to_reflected_field(point_class, y));
+    assert_eq!(member_name(&env, reflected), "y");
+    let max_value = unwrap(
+        &env,
+        env.get_static_field_id(INTEGER_CLASS, "MAX_VALUE", "I"),
+    );
+    let reflected = unwrap(
+        &env,
+        env.to_reflected_static_field(INTEGER_CLASS, max_value),
+    );
+    assert_eq!(member_name(&env, reflected), "MAX_VALUE");
+}
+
+#[test]
+pub fn get_field_inferred() {
+    let env = attach_current_thread();
+    let list = unwrap(&env, env.new_object(ARRAYLIST_CLASS, "()V", &[]));
+    let list = unwrap(&env, JList::from_env(&env, list));
+    unwrap(&env, list.add(JObject::null()));
+    unwrap(&env, list.add(JObject::null()));
+
+    // Declared by the class itself
+    let size = unwrap(&env, env.get_field_inferred(*list, "size"));
+    assert_eq!(size.i().unwrap(), 2);
+
+    // Declared by a superclass
+    let mod_count = unwrap(&env, env.get_field_inferred(*list, "modCount"));
+    assert_eq!(mod_count.i().unwrap(), 2);
+
+    // Served from the cache
+    let size = unwrap(&env, env.get_field_inferred(*list, "size"));
+    assert_eq!(size.i().unwrap(), 2);
+}
+
+#[test]
+pub fn get_field_inferred_same_class_name() {
+    let env = attach_current_thread();
+    let int_holder = new_field_holder(&env, "I");
+    let string_holder = new_field_holder(&env, "Ljava/lang/String;");
+
+    // The classes have the same name, but the fields have different types
+    let value = unwrap(&env, env.get_field_inferred(int_holder, "value"));
+    assert_eq!(value.i().unwrap(), 0);
+    let value = unwrap(&env, env.get_field_inferred(string_holder, "value"));
+    assert!(value.l().unwrap().is_null());
+}
+
+#[test]
+pub fn get_field_inferred_not_found() {
+    let env = attach_current_thread();
+    let obj = unwrap(&env, env.new_object(ARRAYLIST_CLASS, "()V", &[]));
+
+    let result = env.get_field_inferred(obj, "nonexistent");
+    assert!(matches!(result, Err(Error::FieldNotFound { .. })));
+    assert!(!env.exception_check().unwrap());
+}
+
+#[test]
+pub fn get_field_inferred_static() {
+    let env = attach_current_thread();
+    let obj = unwrap(&env, env.new_object(ARRAYLIST_CLASS, "()V", &[]));
+
+    // Static fields can't be read through an instance
+    let result = env.get_field_inferred(obj, "serialVersionUID");
+    assert!(matches!(result, Err(Error::FieldNotFound { .. })));
+    assert!(!env.exception_check().unwrap());
+}
+
+#[test]
+pub fn get_int_array_field() {
+    let env = attach_current_thread();
+    let polygon = unwrap(&env, env.new_object("java/awt/Polygon", "()V", &[]));
+    unwrap(
+        &env,
+        env.call_method(polygon, "addPoint", "(II)V", &[3.into(), 4.into()]),
+    );
+
+    let xpoints = unwrap(&env, env.get_int_array_field(polygon, "xpoints")).unwrap();
+    assert_eq!(xpoints[0], 3);
+    let ypoints = unwrap(&env, env.get_int_array_field(polygon, "ypoints")).unwrap();
+    assert_eq!(ypoints[0], 4);
+
+    // The constructor is skipped, so the fields stay null
+    let empty = unwrap(&env, env.alloc_object("java/awt/Polygon"));
+    assert_eq!(
+        unwrap(&env, env.get_int_array_field(empty, "xpoints")),
+        None
+    );
+}
+
+#[test]
+pub fn get_string_array_field() {
+    let env = attach_current_thread();
+    let locale = unwrap(
+        &env,
+        env.get_static_field("java/util/Locale", "US", "Ljava/util/Locale;"),
+    );
+    let symbols = unwrap(
+        &env,
+        env.new_object(
+            "java/text/DateFormatSymbols",
+            "(Ljava/util/Locale;)V",
+            &[locale],
+        ),
+    );
+
+    let eras = unwrap(&env, env.get_string_array_field(symbols, "eras")).unwrap();
+    assert_eq!(eras, vec![Some("BC".to_owned()), Some("AD".to_owned())]);
+}
+
+#[test]
+pub fn set_field_inferred() {
+    let env = attach_current_thread();
+    let atomic_integer = unwrap(
+        &env,
+        env.new_object(
+            "java/util/concurrent/atomic/AtomicInteger",
+            "(I)V",
+            &[JValue::from(0)],
+        ),
+    );
+
+    unwrap(
+        &env,
+        env.set_field_inferred(atomic_integer, "value", JValue::from(42)),
+    );
+    let value = unwrap(&env, env.call_method(atomic_integer, "get", "()I", &[]));
+    assert_eq!(value.i().unwrap(), 42);
+
+    env.set_field_inferred(atomic_integer, "value", JValue::from(42i64))
+        .expect_err("set_field_inferred must check the value type");
+}
+
 #[test]
 pub fn pop_local_frame_pending_exception() {
     let env = attach_current_thread();
@@ -215,6 +672,64 @@ pub fn push_local_frame_too_many_refs() {
     env.pop_local_frame(JObject::null()).unwrap();
 }
 
+#[test]
+pub fn ensure_local_capacity() {
+    let env = attach_current_thread();
+
+    unwrap(&env, env.ensure_local_capacity(1024));
+    let result = env.ensure_local_capacity(-1);
+    assert!(matches!(result, Err(Error::JniCall(_))));
+    assert!(!env.exception_check().unwrap());
+}
+
+#[test]
+pub fn push_auto_local_frame() {
+    let env = attach_current_thread();
+    let outer = unwrap(&env, env.new_string("outer"));
+
+    let kept = {
+        let frame = unwrap(&env, env.push_auto_local_frame(16));
+        let prefix = unwrap(&env, env.new_string("in"));
+        let inner = unwrap(
+            &env,
+            env.call_method(
+                prefix,
+                "concat",
+                "(Ljava/lang/String;)Ljava/lang/String;",
+                &[outer.into()],
+            ),
+        );
+        {
+            // Nested frames are popped first
+            let _nested = unwrap(&env, env.push_auto_local_frame(4));
+            unwrap(&env, env.new_string("nested"));
+        }
+        unwrap(&env, frame.pop_with(inner.l().unwrap()))
+    };
+    let kept: String = unwrap(&env, env.get_string(kept.into())).into();
+    assert_eq!(kept, "inouter");
+
+    // References created before the frame are still valid
+    let outer: String = unwrap(&env, env.get_string(outer)).into();
+    assert_eq!(outer, "outer");
+}
+
+#[test]
+pub fn push_auto_local_frame_pending_exception() {
+    let env = attach_current_thread();
+
+    {
+        let _frame = unwrap(&env, env.push_auto_local_frame(16));
+        unwrap(
+            &env,
+            env.throw_new(RUNTIME_EXCEPTION_CLASS, "Test Exception"),
+        );
+    }
+
+    assert!(unwrap(&env, env.exception_check()));
+    unwrap(&env, env.exception_clear());
+}
+
 #[test]
 pub fn with_local_frame() {
     let env = attach_current_thread();
@@ -373,6 +888,96 @@ macro_rules! test_get_array_elements {
     };
 }
 
+macro_rules! test_array_region_out_of_bounds {
+    ( $test_name:tt, $jni_type:ty, $new_array:tt, $get_region:tt, $set_region:tt ) => {
+        #[test]
+        pub fn $test_name() {
+            let env = attach_current_thread();
+            let java_array = env.$new_array(2).unwrap();
+
+            let mut buf = [1 as $jni_type; 3];
+            for result in vec![
+                env.$set_region(java_array, 0, &buf),
+                env.$get_region(java_array, -1, &mut buf[..1]),
+            ] {
+                assert!(matches!(result, Err(Error::JavaException)));
+                let exception = env.exception_occurred().unwrap();
+                env.exception_clear().unwrap();
+                assert_exception_type(&env, exception, "java/lang/ArrayIndexOutOfBoundsException");
+            }
+
+            // Nothing is copied to the java array
+            let mut res = [1 as $jni_type; 2];
+            env.$get_region(java_array, 0, &mut res).unwrap();
+            assert_eq!(res, [0 as $jni_type; 2]);
+        }
+    };
+}
+
+test_array_region_out_of_bounds!(
+    boolean_array_region_out_of_bounds,
+    jboolean,
+    new_boolean_array,
+    get_boolean_array_region,
+    set_boolean_array_region
+);
+
+test_array_region_out_of_bounds!(
+    byte_array_region_out_of_bounds,
+    jbyte,
+    new_byte_array,
+    get_byte_array_region,
+    set_byte_array_region
+);
+
+test_array_region_out_of_bounds!(
+    char_array_region_out_of_bounds,
+    jchar,
+    new_char_array,
+    get_char_array_region,
+    set_char_array_region
+);
+
+test_array_region_out_of_bounds!(
+    short_array_region_out_of_bounds,
+    jshort,
+    new_short_array,
+    get_short_array_region,
+    set_short_array_region
+);
+
+test_array_region_out_of_bounds!(
+    int_array_region_out_of_bounds,
+    jint,
+    new_int_array,
+    get_int_array_region,
+    set_int_array_region
+);
+
+test_array_region_out_of_bounds!(
+    long_array_region_out_of_bounds,
+    jlong,
+    new_long_array,
+    get_long_array_region,
+    set_long_array_region
+);
+
+test_array_region_out_of_bounds!(
+    float_array_region_out_of_bounds,
+    jfloat,
+    new_float_array,
+    get_float_array_region,
+    set_float_array_region
+);
+
+test_array_region_out_of_bounds!(
+    double_array_region_out_of_bounds,
+    jdouble,
+    new_double_array,
+    get_double_array_region,
+    set_double_array_region
+);
+
 // Test generic get_array_elements
 test_get_array_elements!(
     get_array_elements,
@@ -580,14 +1185,39 @@ pub fn new_direct_byte_buffer() {
 }
 
 #[test]
-pub fn get_direct_buffer_capacity_ok() {
+pub fn new_direct_byte_buffer_raw() {
     let env = attach_current_thread();
-    let mut vec: Vec<u8> = vec![0, 1, 2, 3];
-    let buf = vec.as_mut_slice();
-    let result = env.new_direct_byte_buffer(buf).unwrap();
-    assert!(!result.is_null());
+    let data = Box::into_raw(vec![0u8, 1, 2, 3].into_boxed_slice());
 
-    let capacity = env.get_direct_buffer_capacity(result).unwrap();
+    let buffer = unwrap(&env, unsafe {
+        env.new_direct_byte_buffer_raw(data as *mut u8, 4)
+    });
+    assert_eq!(unwrap(&env, env.get_direct_buffer_capacity(buffer)), 4);
+    unwrap(
+        &env,
+        env.call_method(
+            buffer,
+            "put",
+            "(IB)Ljava/nio/ByteBuffer;",
+            &[1.into(), JValue::Byte(9)],
+        ),
+    );
+
+    // The buffer must not be used by java code anymore when the memory is freed
+    unwrap(&env, env.delete_local_ref(buffer.into()));
+    let data = unsafe { Box::from_raw(data) };
+    assert_eq!(&*data, &[0, 9, 2, 3]);
+}
+
+#[test]
+pub fn get_direct_buffer_capacity_ok() {
+    let env = attach_current_thread();
+    let mut vec: Vec<u8> = vec![0, 1, 2, 3];
+    let buf = vec.as_mut_slice();
+    let result = env.new_direct_byte_buffer(buf).unwrap();
+    assert!(!result.is_null());
+
+    let capacity = env.get_direct_buffer_capacity(result).unwrap();
     assert_eq!(capacity, 4);
 }
 
@@ -626,224 +1256,2890 @@ pub fn get_direct_buffer_address_null_arg() {
     assert!(result.is_err());
 }
 
-// Group test for testing the family of new_PRIMITIVE_array functions with correct arguments
 #[test]
-pub fn new_primitive_array_ok() {
+pub fn with_direct_buffer() {
     let env = attach_current_thread();
-    const SIZE: jsize = 16;
+    let buffer = unwrap(
+        &env,
+        env.call_static_method(
+            "java/nio/ByteBuffer",
+            "allocateDirect",
+            "(I)Ljava/nio/ByteBuffer;",
+            &[3.into()],
+        ),
+    );
+    let buffer = JByteBuffer::from(buffer.l().unwrap());
 
-    let result = env.new_boolean_array(SIZE);
-    assert!(result.is_ok());
-    assert!(!result.unwrap().is_null());
+    let len = unwrap(
+        &env,
+        env.with_direct_buffer(buffer, |data| {
+            data.copy_from_slice(&[7, 8, 9]);
+            data.len()
+        }),
+    );
+    assert_eq!(len, 3);
 
-    let result = env.new_byte_array(SIZE);
-    assert!(result.is_ok());
-    assert!(!result.unwrap().is_null());
+    let last = unwrap(&env, env.call_method(buffer, "get", "(I)B", &[2.into()]));
+    assert_eq!(last.b().unwrap(), 9);
+}
 
-    let result = env.new_char_array(SIZE);
-    assert!(result.is_ok());
-    assert!(!result.unwrap().is_null());
+#[test]
+pub fn with_direct_buffer_not_direct() {
+    let env = attach_current_thread();
+    let buffer = unwrap(
+        &env,
+        env.call_static_method(
+            "java/nio/ByteBuffer",
+            "allocate",
+            "(I)Ljava/nio/ByteBuffer;",
+            &[3.into()],
+        ),
+    );
 
-    let result = env.new_short_array(SIZE);
-    assert!(result.is_ok());
-    assert!(!result.unwrap().is_null());
+    let result = env.with_direct_buffer(buffer.l().unwrap().into(), |_| unreachable!());
+    assert!(matches!(result, Err(Error::NullPtr(_))));
+    let result = env.with_direct_buffer(JObject::null().into(), |_| unreachable!());
+    assert!(matches!(result, Err(Error::NullPtr(_))));
+}
 
-    let result = env.new_int_array(SIZE);
-    assert!(result.is_ok());
-    assert!(!result.unwrap().is_null());
+#[test]
+pub fn call_method_clearing_throws() {
+    let env = attach_current_thread();
+    let string = env.new_string(TESTING_OBJECT_STR).unwrap();
 
-    let result = env.new_long_array(SIZE);
-    assert!(result.is_ok());
-    assert!(!result.unwrap().is_null());
+    let result = env.call_method_clearing(string, "charAt", "(I)C", &[JValue::from(-1)]);
+    let exception = match result {
+        Err(Error::JavaExceptionCleared {
+            exception, class, ..
+        }) => {
+            assert_eq!(class, "java.lang.StringIndexOutOfBoundsException");
+            exception
+        }
+        other => panic!("Unexpected result: {:?}", other),
+    };
 
-    let result = env.new_float_array(SIZE);
-    assert!(result.is_ok());
-    assert!(!result.unwrap().is_null());
+    assert!(!env.exception_check().unwrap());
+    assert_exception_type(
+        &env,
+        exception.as_obj().into(),
+        "java/lang/StringIndexOutOfBoundsException",
+    );
+}
 
-    let result = env.new_double_array(SIZE);
+#[test]
+pub fn capture_exception() {
+    let env = attach_current_thread();
+    let input = env.new_string("abc").unwrap();
+
+    let result = env.capture_exception(env.call_static_method(
+        INTEGER_CLASS,
+        "parseInt",
+        "(Ljava/lang/String;)I",
+        &[input.into()],
+    ));
+    assert!(!env.exception_check().unwrap());
+
+    let error = result.unwrap_err();
+    match &error {
+        Error::JavaExceptionCleared { class, message, .. } => {
+            assert_eq!(class, "java.lang.NumberFormatException");
+            assert_eq!(message.as_deref(), Some("For input string: \"abc\""));
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+    let exception = error.java_exception().unwrap();
+    assert_exception_type(
+        &env,
+        exception.as_obj().into(),
+        "java/lang/NumberFormatException",
+    );
+
+    // Other results are left alone
+    let result = env.capture_exception(env.find_class(STRING_CLASS));
     assert!(result.is_ok());
-    assert!(!result.unwrap().is_null());
+    assert!(Error::JavaException.java_exception().is_none());
 }
 
-// Group test for testing the family of new_PRIMITIVE_array functions with wrong arguments
 #[test]
-pub fn new_primitive_array_wrong() {
+pub fn call_method_clearing_ok() {
     let env = attach_current_thread();
-    const WRONG_SIZE: jsize = -1;
-
-    let result = env.new_boolean_array(WRONG_SIZE);
-    assert_exception(&result, "JNIEnv#new_boolean_array should throw exception");
-    assert_pending_java_exception(&env);
+    let string = env.new_string(TESTING_OBJECT_STR).unwrap();
 
-    let result = env.new_byte_array(WRONG_SIZE);
-    assert_exception(&result, "JNIEnv#new_byte_array should throw exception");
-    assert_pending_java_exception(&env);
+    let result = env.call_method_clearing(string, "charAt", "(I)C", &[JValue::from(0)]);
+    assert_eq!(unwrap(&env, result).c().unwrap(), 'T' as jchar);
+}
 
-    let result = env.new_char_array(WRONG_SIZE);
-    assert_exception(&result, "JNIEnv#new_char_array should throw exception");
-    assert_pending_java_exception(&env);
+// Returns the major version of the running java, e.g. `8` for `1.8`.
+fn java_version(env: &JNIEnv) -> u32 {
+    let key = env.new_string("java.specification.version").unwrap();
+    let version = unwrap(
+        env,
+        env.call_static_method(
+            "java/lang/System",
+            "getProperty",
+            "(Ljava/lang/String;)Ljava/lang/String;",
+            &[key.into()],
+        ),
+    );
+    let version: String = unwrap(env, env.get_string(version.l().unwrap().into())).into();
+    let mut parts = version.split('.');
+    match parts.next().unwrap() {
+        "1" => parts.next().unwrap().parse().unwrap(),
+        major => major.parse().unwrap(),
+    }
+}
 
-    let result = env.new_short_array(WRONG_SIZE);
-    assert_exception(&result, "JNIEnv#new_short_array should throw exception");
-    assert_pending_java_exception(&env);
+// Records are only available since Java 16.
+fn records_supported(env: &JNIEnv) -> bool {
+    java_version(env) >= 16
+}
 
-    let result = env.new_int_array(WRONG_SIZE);
-    assert_exception(&result, "JNIEnv#new_int_array should throw exception");
-    assert_pending_java_exception(&env);
+// `public record Pair(int first, String second) {}`, without the `equals`,
+// `hashCode` and `toString` implementations.
+fn record_class_file(name: &str) -> Vec<u8> {
+    let mut class = ClassFile::new(60);
+    let record_init = class.method_ref("java/lang/Record", "<init>", "()V");
+    let first = class.field_ref(name, "first", "I");
+    let second = class.field_ref(name, "second", "Ljava/lang/String;");
+
+    class.field(ACC_PRIVATE | ACC_FINAL, "first", "I");
+    class.field(ACC_PRIVATE | ACC_FINAL, "second", "Ljava/lang/String;");
+
+    let [r, f, s] = [
+        record_init.to_be_bytes(),
+        first.to_be_bytes(),
+        second.to_be_bytes(),
+    ];
+    #[rustfmt::skip]
+    let init = [
+        0x2a, 0xb7, r[0], r[1], // aload_0, invokespecial Record.<init>
+        0x2a, 0x1b, 0xb5, f[0], f[1], // aload_0, iload_1, putfield first
+        0x2a, 0x2c, 0xb5, s[0], s[1], // aload_0, aload_2, putfield second
+        0xb1, // return
+    ];
+    class.method(ACC_PUBLIC, "<init>", "(ILjava/lang/String;)V", 2, 3, &init);
+    // aload_0, getfield, ireturn/areturn
+    class.method(
+        ACC_PUBLIC,
+        "first",
+        "()I",
+        1,
+        1,
+        &[0x2a, 0xb4, f[0], f[1], 0xac],
+    );
+    let second_code = [0x2a, 0xb4, s[0], s[1], 0xb0];
+    class.method(
+        ACC_PUBLIC,
+        "second",
+        "()Ljava/lang/String;",
+        1,
+        1,
+        &second_code,
+    );
 
-    let result = env.new_long_array(WRONG_SIZE);
-    assert_exception(&result, "JNIEnv#new_long_array should throw exception");
-    assert_pending_java_exception(&env);
+    let mut record = vec![0, 2];
+    for (name, descriptor) in [("first", "I"), ("second", "Ljava/lang/String;")].iter() {
+        let (name, descriptor) = (class.utf8(name), class.utf8(descriptor));
+        // Components without attributes
+        record.extend_from_slice(&u2s(&[name, descriptor, 0]));
+    }
+    class.attribute("Record", &record);
 
-    let result = env.new_float_array(WRONG_SIZE);
-    assert_exception(&result, "JNIEnv#new_float_array should throw exception");
-    assert_pending_java_exception(&env);
+    class.finish(ACC_PUBLIC | ACC_FINAL | ACC_SUPER, name, "java/lang/Record")
+}
 
-    let result = env.new_double_array(WRONG_SIZE);
-    assert_exception(&result, "JNIEnv#new_double_array should throw exception");
-    assert_pending_java_exception(&env);
+// Defines the record in a new class loader, so it can be defined for each test
+fn define_record_class<'a>(env: &JNIEnv<'a>) -> JClass<'a> {
+    let loader = new_class_loader(env);
+    let name = "com/example/Pair";
+    unwrap(
+        env,
+        env.define_class(name, loader, &record_class_file(name)),
+    )
 }
 
 #[test]
-fn get_super_class_ok() {
+pub fn new_record_and_record_components() {
     let env = attach_current_thread();
-    let result = env.get_superclass(ARRAYLIST_CLASS);
-    assert!(result.is_ok());
-    assert!(!result.unwrap().is_null());
+    if !records_supported(&env) {
+        return;
+    }
+    let class = define_record_class(&env);
+    let second = env.new_string(TESTING_OBJECT_STR).unwrap();
+
+    let record = unwrap(&env, env.new_record(class, &[7.into(), second.into()]));
+    let components = unwrap(&env, env.record_components(record));
+
+    assert_eq!(components.len(), 2);
+    assert_eq!(components[0].i().unwrap(), 7);
+    assert!(unwrap(
+        &env,
+        env.is_same_object(second, components[1].l().unwrap())
+    ));
 }
 
 #[test]
-fn get_super_class_null() {
+pub fn new_record_wrong_arg_count() {
     let env = attach_current_thread();
-    let result = env.get_superclass("java/lang/Object");
-    assert!(result.is_ok());
-    assert!(result.unwrap().is_null());
+    if !records_supported(&env) {
+        return;
+    }
+    let class = define_record_class(&env);
+
+    let result = env.new_record(class, &[]);
+    assert!(matches!(result, Err(Error::InvalidArgList(_))));
 }
 
 #[test]
-fn convert_byte_array() {
+pub fn new_record_not_a_record() {
     let env = attach_current_thread();
-    let src: Vec<u8> = vec![1, 2, 3, 4];
-    let java_byte_array = env.byte_array_from_slice(&src).unwrap();
+    if !records_supported(&env) {
+        return;
+    }
 
-    let dest = env.convert_byte_array(java_byte_array);
-    assert!(dest.is_ok());
-    assert_eq!(dest.unwrap(), src);
+    let result = env.new_record(STRING_CLASS, &[]);
+    assert!(matches!(result, Err(Error::NotRecord(name)) if name == "java.lang.String"));
+
+    let string = env.new_string(TESTING_OBJECT_STR).unwrap();
+    let result = env.record_components(string);
+    assert!(matches!(result, Err(Error::NotRecord(_))));
 }
 
 #[test]
-fn local_ref_null() {
+pub fn call_macro_static() {
     let env = attach_current_thread();
-    let null_obj = JObject::null();
 
-    let result = env.new_local_ref::<JObject>(null_obj);
-    assert!(result.is_ok());
-    assert!(result.unwrap().is_null());
+    let x: jint = -3;
+    let abs = call!(env, static MATH_CLASS.abs(x: int) -> int);
+    assert_eq!(unwrap(&env, abs), 3);
 
-    // try to delete null reference
-    let result = env.delete_local_ref(null_obj);
-    assert!(result.is_ok());
+    let max = call!(env, static MATH_CLASS.max(1: long, (x as jlong): long) -> long);
+    assert_eq!(unwrap(&env, max), 1);
 }
 
 #[test]
-fn new_global_ref_null() {
+pub fn call_macro_object() {
     let env = attach_current_thread();
-    let null_obj = JObject::null();
-    let result = env.new_global_ref(null_obj);
-    assert!(result.is_ok());
-    assert!(result.unwrap().as_obj().is_null());
+    let string = env.new_string(TESTING_OBJECT_STR).unwrap();
+
+    let len = call!(env, string.length() -> int);
+    assert_eq!(unwrap(&env, len), TESTING_OBJECT_STR.len() as jint);
+
+    let empty = call!(env, string.isEmpty() -> boolean);
+    assert!(!unwrap(&env, empty));
+
+    let prefix = env.new_string("TESTING").unwrap();
+    let starts = call!(env, string.startsWith(prefix: "java/lang/String") -> boolean);
+    assert!(unwrap(&env, starts));
+
+    let sub = call!(env, string.substring(0: int, 7: int) -> "java/lang/String");
+    let sub: String = env.get_string(unwrap(&env, sub).into()).unwrap().into();
+    assert_eq!(sub, "TESTING");
 }
 
 #[test]
-fn auto_local_null() {
+pub fn call_macro_byte_slice() {
     let env = attach_current_thread();
-    let null_obj = JObject::null();
-    {
-        let auto_ref = AutoLocal::new(&env, null_obj);
-        assert!(auto_ref.as_obj().is_null());
+    let bytes: &[u8] = b"hello";
+
+    let string = call!(env, static "java/util/Base64".getEncoder() -> "java/util/Base64$Encoder")
+        .map(|encoder| call!(env, encoder.encodeToString(bytes: [byte]) -> "java/lang/String"));
+    let string = unwrap(&env, unwrap(&env, string));
+    let string: String = env.get_string(string.into()).unwrap().into();
+    assert_eq!(string, "aGVsbG8=");
+
+    // Existing arrays are passed as they are
+    let array = unwrap(&env, env.byte_array_from_slice(&[1, 2]));
+    let vec = vec![1u8, 2];
+    let equal =
+        call!(env, static "java/util/Arrays".equals(array: [byte], (&vec): [byte]) -> boolean);
+    assert!(unwrap(&env, equal));
+}
+
+#[test]
+pub fn call_macro_owned_bytes_and_question_mark() {
+    fn encode(env: &JNIEnv, bytes: Vec<u8>) -> jni::errors::Result<String> {
+        let encoder =
+            call!(env, static "java/util/Base64".getEncoder() -> "java/util/Base64$Encoder")?;
+        let string = call!(env, encoder.encodeToString(bytes: [byte]) -> "java/lang/String")?;
+        Ok(env.get_string(string.into())?.into())
     }
-    assert!(null_obj.is_null());
+    fn starts_with(env: &JNIEnv, string: JString, prefix: &str) -> jni::errors::Result<bool> {
+        call!(env, string.startsWith((env.new_string(prefix)?): "java/lang/String") -> boolean)
+    }
+
+    let env = attach_current_thread();
+    assert_eq!(unwrap(&env, encode(&env, b"hello".to_vec())), "aGVsbG8=");
+
+    let string = env.new_string(TESTING_OBJECT_STR).unwrap();
+    assert!(unwrap(&env, starts_with(&env, string, "TESTING")));
 }
 
 #[test]
-fn short_lifetime_with_local_frame() {
+pub fn jni_signature_macro() {
+    assert_eq!(jni_signature!(boolean), "Z");
+    assert_eq!(jni_signature!(["java/lang/Object"]), "[Ljava/lang/Object;");
+    assert_eq!(
+        jni_signature!((int, [char], "java/lang/String") -> void),
+        "(I[CLjava/lang/String;)V"
+    );
+    assert!(TypeSignature::from_str(jni_signature!((long, [[double]]) -> [int])).is_ok());
+}
+
+#[test]
+pub fn get_declared_fields_skip_static() {
     let env = attach_current_thread();
-    let object = short_lifetime_with_local_frame_sub_fn(&env);
-    assert!(object.is_ok());
+
+    let fields = unwrap(
+        &env,
+        env.get_declared_fields(INTEGER_CLASS, Modifiers::STATIC),
+    );
+    assert_eq!(fields.len(), 1);
+    let name = unwrap(
+        &env,
+        env.call_method(fields[0], "getName", "()Ljava/lang/String;", &[]),
+    );
+    let name: String = env.get_string(name.l().unwrap().into()).unwrap().into();
+    assert_eq!(name, "value");
+
+    let all_fields = unwrap(
+        &env,
+        env.get_declared_fields(INTEGER_CLASS, Modifiers::NONE),
+    );
+    assert!(all_fields.len() > 1);
 }
 
-fn short_lifetime_with_local_frame_sub_fn<'a>(env: &'_ JNIEnv<'a>) -> Result<JObject<'a>, Error> {
-    env.with_local_frame(16, || {
-        env.new_object(INTEGER_CLASS, "(I)V", &[JValue::from(5)])
-    })
+#[test]
+pub fn get_declared_methods_skip_static() {
+    let env = attach_current_thread();
+
+    let methods = unwrap(&env, env.get_declared_methods(MATH_CLASS, Modifiers::NONE));
+    assert!(!methods.is_empty());
+
+    let instance_methods = unwrap(
+        &env,
+        env.get_declared_methods(MATH_CLASS, Modifiers::STATIC),
+    );
+    assert!(instance_methods.is_empty());
 }
 
 #[test]
-fn short_lifetime_list() {
+pub fn bigint_bytes_round_trip() {
     let env = attach_current_thread();
-    let first_list_object = short_lifetime_list_sub_fn(&env).unwrap();
-    let value = env.call_method(first_list_object, "intValue", "()I", &[]);
-    assert_eq!(value.unwrap().i().unwrap(), 1);
+
+    let value = unwrap(
+        &env,
+        env.call_static_method(
+            "java/math/BigInteger",
+            "valueOf",
+            "(J)Ljava/math/BigInteger;",
+            &[JValue::from(-129 as jlong)],
+        ),
+    )
+    .l()
+    .unwrap();
+    assert_eq!(unwrap(&env, env.bigint_to_bytes(value)), vec![0xff, 0x7f]);
+
+    let from_bytes = unwrap(&env, env.bigint_from_bytes(&[0xff, 0x7f]));
+    let long_value = unwrap(&env, env.call_method(from_bytes, "longValue", "()J", &[]));
+    assert_eq!(long_value.j().unwrap(), -129);
+
+    let zero = unwrap(&env, env.bigint_from_bytes(&[]));
+    assert_eq!(unwrap(&env, env.bigint_to_bytes(zero)), vec![0]);
 }
 
-fn short_lifetime_list_sub_fn<'a>(env: &'_ JNIEnv<'a>) -> Result<JObject<'a>, Error> {
-    let list_object = env.new_object(ARRAYLIST_CLASS, "()V", &[])?;
-    let list = JList::from_env(env, list_object)?;
-    let element = env.new_object(INTEGER_CLASS, "(I)V", &[JValue::from(1)])?;
-    list.add(element)?;
-    short_lifetime_list_sub_fn_get_first_element(&list)
+#[test]
+pub fn bigdecimal_string_round_trip() {
+    let env = attach_current_thread();
+
+    let value = unwrap(&env, env.bigdecimal_from_str("1.2300E+5"));
+    let scale = unwrap(&env, env.call_method(value, "scale", "()I", &[]));
+    assert_eq!(scale.i().unwrap(), -1);
+    assert_eq!(unwrap(&env, env.bigdecimal_to_string(value)), "1.2300E+5");
 }
 
-fn short_lifetime_list_sub_fn_get_first_element<'a>(
-    list: &'_ JList<'a, '_>,
-) -> Result<JObject<'a>, Error> {
-    let mut iterator = list.iter()?;
-    Ok(iterator.next().unwrap())
+#[test]
+pub fn bigdecimal_from_invalid_str() {
+    let env = attach_current_thread();
+
+    let result = env.bigdecimal_from_str("not a number");
+    assert!(matches!(result, Err(Error::JavaException)));
+    let exception = env.exception_occurred().unwrap();
+    env.exception_clear().unwrap();
+    assert_exception_type(&env, exception, "java/lang/NumberFormatException");
 }
 
+#[cfg(feature = "num-bigint")]
 #[test]
-fn get_object_array_element() {
+pub fn bigint_num_bigint_round_trip() {
+    use num_bigint::BigInt;
+
     let env = attach_current_thread();
-    let array = env
-        .new_object_array(1, STRING_CLASS, JObject::null())
-        .unwrap();
-    assert!(!array.is_null());
-    assert!(env.get_object_array_element(array, 0).unwrap().is_null());
-    let test_str = env.new_string("test").unwrap();
-    env.set_object_array_element(array, 0, test_str).unwrap();
-    assert!(!env.get_object_array_element(array, 0).unwrap().is_null());
+
+    let value: BigInt = "-123456789012345678901234567890".parse().unwrap();
+    let obj = unwrap(&env, env.new_bigint(&value));
+    let string = unwrap(
+        &env,
+        env.call_method(obj, "toString", "()Ljava/lang/String;", &[]),
+    );
+    let string: String = env.get_string(string.l().unwrap().into()).unwrap().into();
+    assert_eq!(string, "-123456789012345678901234567890");
+
+    assert_eq!(unwrap(&env, env.get_bigint(obj)), value);
 }
 
 #[test]
-pub fn throw_new() {
+pub fn uuid_bytes_round_trip() {
     let env = attach_current_thread();
 
-    let result = env.throw_new(RUNTIME_EXCEPTION_CLASS, "Test Exception");
-    assert!(result.is_ok());
-    assert_pending_java_exception_detailed(
+    let string = env
+        .new_string("01234567-89ab-cdef-fedc-ba9876543210")
+        .unwrap();
+    let value = unwrap(
         &env,
-        Some(RUNTIME_EXCEPTION_CLASS),
-        Some("Test Exception"),
+        env.call_static_method(
+            "java/util/UUID",
+            "fromString",
+            "(Ljava/lang/String;)Ljava/util/UUID;",
+            &[string.into()],
+        ),
+    )
+    .l()
+    .unwrap();
+    let bytes = [
+        0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0xfe, 0xdc, 0xba, 0x98, 0x76, 0x54, 0x32,
+        0x10,
+    ];
+    assert_eq!(unwrap(&env, env.uuid_to_bytes(value)), bytes);
+
+    let from_bytes = unwrap(&env, env.uuid_from_bytes(bytes));
+    assert!(unwrap(
+        &env,
+        env.call_method(
+            from_bytes,
+            "equals",
+            "(Ljava/lang/Object;)Z",
+            &[value.into()]
+        )
+    )
+    .z()
+    .unwrap());
+    let lsb = unwrap(
+        &env,
+        env.call_method(from_bytes, "getLeastSignificantBits", "()J", &[]),
     );
+    assert_eq!(lsb.j().unwrap(), 0xfedc_ba98_7654_3210_u64 as jlong);
 }
 
 #[test]
-pub fn throw_new_fail() {
+pub fn uuid_to_bytes_null() {
     let env = attach_current_thread();
 
-    let result = env.throw_new("java/lang/NonexistentException", "Test Exception");
-    assert!(result.is_err());
-    // Just to clear the java.lang.NoClassDefFoundError
-    assert_pending_java_exception(&env);
+    let result = env.uuid_to_bytes(JObject::null());
+    assert!(matches!(result, Err(Error::NullPtr(_))));
 }
 
+#[cfg(feature = "uuid")]
 #[test]
-pub fn throw_defaults() {
+pub fn uuid_crate_round_trip() {
+    use uuid::Uuid;
+
     let env = attach_current_thread();
 
-    test_throwable_descriptor_with_default_type(&env, TEST_EXCEPTION_MESSAGE);
-    test_throwable_descriptor_with_default_type(&env, TEST_EXCEPTION_MESSAGE.to_owned());
-    test_throwable_descriptor_with_default_type(&env, JNIString::from(TEST_EXCEPTION_MESSAGE));
+    let value = Uuid::parse_str("f81d4fae-7dec-11d0-a765-00a0c91e6bf6").unwrap();
+    let obj = unwrap(&env, env.new_uuid(&value));
+    let string = unwrap(
+        &env,
+        env.call_method(obj, "toString", "()Ljava/lang/String;", &[]),
+    );
+    let string: String = env.get_string(string.l().unwrap().into()).unwrap().into();
+    assert_eq!(string, "f81d4fae-7dec-11d0-a765-00a0c91e6bf6");
+
+    assert_eq!(unwrap(&env, env.get_uuid(obj)), value);
+}
+
+#[test]
+pub fn new_object_array_primitive_element_class() {
+    let env = attach_current_thread();
+
+    let result = env.new_object_array(1, "I", JObject::null());
+    assert!(matches!(result, Err(Error::PrimitiveClassDesc(desc)) if desc == "I"));
+    assert!(!env.exception_check().unwrap());
+
+    let array = unwrap(&env, env.new_object_array(1, "[I", JObject::null()));
+    assert_eq!(unwrap(&env, env.get_array_length(array)), 1);
+    // Other lookups leave it to the JVM, as it may be a class in the default package
+    let result = env.find_class("I");
+    assert!(matches!(result, Err(Error::JavaException)));
+    unwrap(&env, env.exception_clear());
+}
+
+#[test]
+pub fn get_array_length() {
+    let env = attach_current_thread();
+
+    let ints = unwrap(&env, env.new_int_array(7));
+    assert_eq!(unwrap(&env, env.get_array_length(ints)), 7);
+    let empty = unwrap(&env, env.new_byte_array(0));
+    assert_eq!(unwrap(&env, env.get_array_length(empty)), 0);
+    let objects = unwrap(&env, env.new_object_array(3, STRING_CLASS, JObject::null()));
+    assert_eq!(unwrap(&env, env.get_array_length(objects)), 3);
+
+    let result = env.get_array_length(JObject::null().into_inner());
+    assert!(matches!(result, Err(Error::NullPtr(_))));
+}
+
+#[test]
+pub fn call_with_out_array_primitive() {
+    let env = attach_current_thread();
+    let string = env.new_string(TESTING_OBJECT_STR).unwrap();
+
+    let (result, out) = unwrap(
+        &env,
+        env.call_with_out_array(
+            string,
+            "getChars",
+            "(II[CI)V",
+            &[JValue::from(1), JValue::from(2), JValue::from(0)],
+            &[2],
+        ),
+    );
+    result.v().unwrap();
+    assert_eq!(out.len(), 1);
+    assert_eq!(out[0].c().unwrap(), 'E' as jchar);
+}
+
+#[test]
+pub fn call_with_out_array_object() {
+    let env = attach_current_thread();
+    let list = unwrap(&env, env.new_object(ARRAYLIST_CLASS, "()V", &[]));
+    let element = env.new_string(TESTING_OBJECT_STR).unwrap();
+    unwrap(
+        &env,
+        env.call_method(list, "add", "(Ljava/lang/Object;)Z", &[element.into()]),
+    );
+
+    let (_, out) = unwrap(
+        &env,
+        env.call_with_out_array(
+            list,
+            "toArray",
+            "([Ljava/lang/Object;)[Ljava/lang/Object;",
+            &[],
+            &[0],
+        ),
+    );
+    assert!(unwrap(
+        &env,
+        env.is_same_object(element, out[0].l().unwrap())
+    ));
+}
+
+#[test]
+pub fn call_with_out_array_invalid_out_param() {
+    let env = attach_current_thread();
+    let string = env.new_string(TESTING_OBJECT_STR).unwrap();
+    let args = [JValue::from(1), JValue::from(2), JValue::from(0)];
+
+    let result = env.call_with_out_array(string, "getChars", "(II[CI)V", &args, &[0]);
+    assert!(matches!(result, Err(Error::InvalidOutParam(0))));
+
+    let result = env.call_with_out_array(string, "getChars", "(II[CI)V", &args[..2], &[2, 2]);
+    assert!(matches!(result, Err(Error::InvalidOutParam(2))));
+
+    let result = env.call_with_out_array(string, "getChars", "(II[CI)V", &args[..1], &[2]);
+    assert!(matches!(result, Err(Error::InvalidArgList(_))));
+}
+
+#[test]
+pub fn get_string_cached_same_object() {
+    let env = attach_current_thread();
+    let string = env.new_string(TESTING_OBJECT_STR).unwrap();
+
+    let first = unwrap(&env, env.get_string_cached(string));
+    let second = unwrap(&env, env.get_string_cached(string));
+    assert_eq!(&*first, TESTING_OBJECT_STR);
+    assert!(Arc::ptr_eq(&first, &second));
+
+    let other = env.new_string(TESTING_OBJECT_STR).unwrap();
+    let third = unwrap(&env, env.get_string_cached(other));
+    assert_eq!(third, first);
+    assert!(!Arc::ptr_eq(&first, &third));
+}
+
+#[test]
+pub fn get_string_cached_evicts_oldest() {
+    let env = attach_current_thread();
+    let string = env.new_string(TESTING_OBJECT_STR).unwrap();
+    let first = unwrap(&env, env.get_string_cached(string));
+
+    // More strings than the cache holds
+    for i in 0..300 {
+        let other = env.auto_local(env.new_string(i.to_string()).unwrap());
+        let decoded = unwrap(&env, env.get_string_cached(JString::from(other.as_obj())));
+        assert_eq!(&*decoded, i.to_string());
+    }
+
+    let again = unwrap(&env, env.get_string_cached(string));
+    assert_eq!(again, first);
+    assert!(!Arc::ptr_eq(&first, &again));
+}
+
+#[test]
+pub fn call_nonvirtual_method_interface_default() {
+    let env = attach_current_thread();
+    let list = unwrap(&env, env.new_object(ARRAYLIST_CLASS, "()V", &[]));
+    for element in &["b", "a"] {
+        let element = env.new_string(element).unwrap();
+        unwrap(
+            &env,
+            env.call_method(list, "add", "(Ljava/lang/Object;)Z", &[element.into()]),
+        );
+    }
+    let mod_count = unwrap(&env, env.get_field(list, "modCount", "I"));
+    assert_eq!(mod_count.i().unwrap(), 2);
+
+    // `ArrayList#sort` overrides `List#sort` and increments `modCount`, while the
+    // default implementation sorts the list through its `ListIterator`.
+    unwrap(
+        &env,
+        env.call_nonvirtual_method(
+            list,
+            "java/util/List",
+            "sort",
+            "(Ljava/util/Comparator;)V",
+            &[JObject::null().into()],
+        ),
+    );
+    let mod_count = unwrap(&env, env.get_field(list, "modCount", "I"));
+    assert_eq!(mod_count.i().unwrap(), 2);
+
+    let first = unwrap(
+        &env,
+        env.call_method(list, "get", "(I)Ljava/lang/Object;", &[JValue::from(0)]),
+    );
+    let first: String = env.get_string(first.l().unwrap().into()).unwrap().into();
+    assert_eq!(first, "a");
+}
+
+#[test]
+pub fn call_nonvirtual_method_superclass() {
+    let env = attach_current_thread();
+    let list = unwrap(&env, env.new_object(ARRAYLIST_CLASS, "()V", &[]));
+
+    // `AbstractCollection` overrides `Object#toString`, so calling the latter
+    // directly returns the identity based representation.
+    let string = unwrap(
+        &env,
+        env.call_nonvirtual_method(
+            list,
+            "java/lang/Object",
+            "toString",
+            "()Ljava/lang/String;",
+            &[],
+        ),
+    );
+    let string: String = env.get_string(string.l().unwrap().into()).unwrap().into();
+    assert!(string.starts_with("java.util.ArrayList@"));
+}
+
+#[test]
+pub fn call_nonvirtual_method_unchecked_by_id() {
+    let env = attach_current_thread();
+    let list = unwrap(&env, env.new_object(ARRAYLIST_CLASS, "()V", &[]));
+
+    let hash_code = unwrap(
+        &env,
+        env.get_method_id("java/lang/Object", "hashCode", "()I"),
+    );
+    let identity_hash = unwrap(
+        &env,
+        env.call_nonvirtual_method_unchecked(
+            list,
+            "java/lang/Object",
+            hash_code,
+            JavaType::Primitive(Primitive::Int),
+            &[],
+        ),
+    );
+    let expected = unwrap(
+        &env,
+        env.call_static_method(
+            "java/lang/System",
+            "identityHashCode",
+            "(Ljava/lang/Object;)I",
+            &[list.into()],
+        ),
+    );
+    assert_eq!(identity_hash.i().unwrap(), expected.i().unwrap());
+
+    // The empty list overrides `hashCode` to return 1
+    let list_hash = unwrap(&env, env.call_method(list, "hashCode", "()I", &[]));
+    assert_eq!(list_hash.i().unwrap(), 1);
+}
+
+#[test]
+pub fn call_nonvirtual_method_unchecked_method_return_type() {
+    let env = attach_current_thread();
+    let list = unwrap(&env, env.new_object(ARRAYLIST_CLASS, "()V", &[]));
+
+    let hash_code = unwrap(
+        &env,
+        env.get_method_id("java/lang/Object", "hashCode", "()I"),
+    );
+    let ret = JavaType::from_str("()I").unwrap();
+    let result =
+        env.call_nonvirtual_method_unchecked(list, "java/lang/Object", hash_code, ret, &[]);
+    assert!(matches!(result, Err(Error::WrongJValueType("Method", _))));
+}
+
+#[test]
+pub fn call_nonvirtual_method_wrong_arg_count() {
+    let env = attach_current_thread();
+    let list = unwrap(&env, env.new_object(ARRAYLIST_CLASS, "()V", &[]));
+
+    let result = env.call_nonvirtual_method(
+        list,
+        "java/lang/Object",
+        "equals",
+        "(Ljava/lang/Object;)Z",
+        &[],
+    );
+    assert!(matches!(result, Err(Error::InvalidArgList(_))));
+}
+
+#[test]
+pub fn call_nonvirtual_method_wrong_class() {
+    let env = attach_current_thread();
+    let string = env.new_string(TESTING_OBJECT_STR).unwrap();
+
+    let result = env.call_nonvirtual_method(string, "java/util/List", "size", "()I", &[]);
+    assert!(matches!(result, Err(Error::WrongObjectClass(_))));
+}
+
+fn new_stream_tokenizer<'a>(env: &JNIEnv<'a>) -> JObject<'a> {
+    let input = env.new_string("word 42").unwrap();
+    let reader = unwrap(
+        env,
+        env.new_object(
+            "java/io/StringReader",
+            "(Ljava/lang/String;)V",
+            &[input.into()],
+        ),
+    );
+    unwrap(
+        env,
+        env.new_object(
+            "java/io/StreamTokenizer",
+            "(Ljava/io/Reader;)V",
+            &[reader.into()],
+        ),
+    )
+}
+
+#[test]
+pub fn volatile_field_same_class_name() {
+    let env = attach_current_thread();
+    let first = new_field_holder(&env, "I");
+    let second = new_field_holder(&env, "I");
+
+    // The field of one class can't be used on an instance of the other
+    unwrap(
+        &env,
+        env.set_volatile_field(first, "value", "I", JValue::from(1)),
+    );
+    unwrap(
+        &env,
+        env.set_volatile_field(second, "value", "I", JValue::from(2)),
+    );
+    let value = unwrap(&env, env.get_volatile_field(first, "value", "I"));
+    assert_eq!(value.i().unwrap(), 1);
+    let value = unwrap(&env, env.get_volatile_field(second, "value", "I"));
+    assert_eq!(value.i().unwrap(), 2);
+}
+
+#[test]
+pub fn get_set_volatile_field() {
+    let env = attach_current_thread();
+    let tokenizer = new_stream_tokenizer(&env);
+    unwrap(&env, env.call_method(tokenizer, "nextToken", "()I", &[]));
+
+    let sval = unwrap(
+        &env,
+        env.get_volatile_field(tokenizer, "sval", "Ljava/lang/String;"),
+    );
+    let sval: String = env.get_string(sval.l().unwrap().into()).unwrap().into();
+    assert_eq!(sval, "word");
+
+    let new_sval = env.new_string("other").unwrap();
+    unwrap(
+        &env,
+        env.set_volatile_field(tokenizer, "sval", "Ljava/lang/String;", new_sval.into()),
+    );
+    let sval = unwrap(&env, env.get_field(tokenizer, "sval", "Ljava/lang/String;"));
+    assert!(unwrap(
+        &env,
+        env.is_same_object(new_sval, sval.l().unwrap())
+    ));
+
+    unwrap(
+        &env,
+        env.set_volatile_field(tokenizer, "nval", "D", JValue::from(1.5)),
+    );
+    let nval = unwrap(&env, env.get_volatile_field(tokenizer, "nval", "D"));
+    assert_eq!(nval.d().unwrap(), 1.5);
+}
+
+#[test]
+pub fn get_set_volatile_field_wrong_type() {
+    let env = attach_current_thread();
+    let tokenizer = new_stream_tokenizer(&env);
+
+    let result = env.get_volatile_field(tokenizer, "nval", "I");
+    assert!(matches!(result, Err(Error::FieldNotFound { .. })));
+
+    let result = env.get_volatile_field(tokenizer, "missing", "I");
+    assert!(matches!(result, Err(Error::FieldNotFound { .. })));
+
+    let result = env.set_volatile_field(tokenizer, "ttype", "I", JValue::from(1.5));
+    assert!(matches!(result, Err(Error::WrongJValueType(..))));
+}
+
+#[test]
+pub fn box_value_primitives() {
+    let env = attach_current_thread();
+    let values = [
+        (JValue::from(true), "java/lang/Boolean", "true"),
+        (JValue::Byte(1), "java/lang/Byte", "1"),
+        (JValue::Char('c' as jchar), "java/lang/Character", "c"),
+        (JValue::Short(2), "java/lang/Short", "2"),
+        (JValue::Int(3), INTEGER_CLASS, "3"),
+        (JValue::Long(4), "java/lang/Long", "4"),
+        (JValue::Float(5.5), "java/lang/Float", "5.5"),
+        (JValue::Double(6.5), "java/lang/Double", "6.5"),
+    ];
+
+    for (value, class, string) in &values {
+        let boxed = unwrap(&env, env.box_value(*value));
+        assert!(unwrap(&env, env.is_instance_of(boxed, *class)));
+        let boxed_string = unwrap(
+            &env,
+            env.call_method(boxed, "toString", "()Ljava/lang/String;", &[]),
+        );
+        let boxed_string: String = env
+            .get_string(boxed_string.l().unwrap().into())
+            .unwrap()
+            .into();
+        assert_eq!(&boxed_string, string);
+    }
+
+    assert!(matches!(
+        env.box_value(JValue::Void),
+        Err(Error::WrongJValueType(..))
+    ));
+}
+
+#[test]
+pub fn jvalue_try_into() {
+    fn sum(values: &[JValue]) -> Result<f64, Error> {
+        let mut sum = 0.0;
+        for value in values {
+            sum += match value {
+                JValue::Int(_) => i32::try_from(*value)? as f64,
+                JValue::Long(_) => i64::try_from(*value)? as f64,
+                _ => f64::try_from(*value)?,
+            };
+        }
+        Ok(sum)
+    }
+
+    let env = attach_current_thread();
+
+    let flag: bool = JValue::from(true).try_into().unwrap();
+    assert!(flag);
+    let value = unwrap(
+        &env,
+        env.call_static_method(
+            INTEGER_CLASS,
+            "valueOf",
+            "(I)Ljava/lang/Integer;",
+            &[7.into()],
+        ),
+    );
+    let object: JObject = value.try_into().unwrap();
+    assert!(unwrap(&env, env.is_instance_of(object, INTEGER_CLASS)));
+
+    let values = [JValue::Int(1), JValue::Long(2), JValue::Double(0.5)];
+    assert_eq!(sum(&values).unwrap(), 3.5);
+    assert!(matches!(
+        sum(&[JValue::from(true)]),
+        Err(Error::WrongJValueType(_, "bool"))
+    ));
+    assert!(matches!(
+        bool::try_from(JValue::Int(1)),
+        Err(Error::WrongJValueType("bool", "int"))
+    ));
+}
+
+#[test]
+pub fn jvalue_rust_char() {
+    let env = attach_current_thread();
+
+    let upper = unwrap(
+        &env,
+        env.call_static_method(
+            "java/lang/Character",
+            "toUpperCase",
+            "(C)C",
+            &['\u{e9}'.into()],
+        ),
+    );
+    assert_eq!(upper.char().unwrap(), '\u{c9}');
+    assert_eq!(char::try_from(upper).unwrap(), '\u{c9}');
+
+    // Supplementary characters don't fit in a java char and are truncated
+    assert!(matches!(JValue::from('\u{1F600}'), JValue::Char(0xF600)));
+
+    let high_surrogate = JValue::Char(0xD83D);
+    assert_eq!(high_surrogate.c().unwrap(), 0xD83D);
+    assert!(matches!(
+        high_surrogate.char(),
+        Err(Error::InvalidChar(0xD83D))
+    ));
+    assert!(matches!(
+        JValue::Int(65).char(),
+        Err(Error::WrongJValueType(..))
+    ));
+}
+
+#[test]
+pub fn new_object_boxed_args() {
+    let env = attach_current_thread();
+    let string = env.new_string(TESTING_OBJECT_STR).unwrap();
+
+    // `Arrays.ArrayList` has a single constructor taking an `E[]`
+    let list = unwrap(
+        &env,
+        env.new_object_boxed(
+            "java/util/Arrays$ArrayList",
+            &[JValue::from(1), string.into(), JValue::from(2.5)],
+        ),
+    );
+    let list = env.get_list(list).unwrap();
+    assert_eq!(unwrap(&env, list.size()), 3);
+
+    let first = unwrap(&env, list.get(0)).unwrap();
+    assert!(unwrap(&env, env.is_instance_of(first, INTEGER_CLASS)));
+    let second = unwrap(&env, list.get(1)).unwrap();
+    assert!(unwrap(&env, env.is_same_object(second, string)));
+}
+
+#[test]
+pub fn map_string_reverses_chars() {
+    let env = attach_current_thread();
+    let string = env.new_string("abc\u{1F600}").unwrap();
+
+    let utf16 = unwrap(&env, env.get_string_utf16(string));
+    assert_eq!(utf16, "abc\u{1F600}".encode_utf16().collect::<Vec<_>>());
+
+    let mapped = unwrap(
+        &env,
+        env.map_string(string, |chars| {
+            chars.truncate(3);
+            chars.reverse();
+        }),
+    );
+    let mapped: String = env.get_string(mapped).unwrap().into();
+    assert_eq!(mapped, "cba");
+
+    let original: String = env.get_string(string).unwrap().into();
+    assert_eq!(original, "abc\u{1F600}");
+}
+
+#[test]
+pub fn get_string_length() {
+    let env = attach_current_thread();
+
+    let string = unwrap(&env, env.new_string("a\0é\u{1F600}"));
+    // The emoji is a surrogate pair
+    assert_eq!(unwrap(&env, env.get_string_length(string)), 5);
+    // 1 + 2 + 2 + 6 bytes of modified UTF-8
+    assert_eq!(unwrap(&env, env.get_string_utf_length(string)), 11);
+    let java_str = unwrap(&env, env.get_string(string));
+    assert_eq!(java_str.to_bytes().len(), 11);
+}
+
+#[test]
+pub fn get_string_critical() {
+    let env = attach_current_thread();
+
+    let string = unwrap(&env, env.new_string("abc\u{1F600}"));
+    let expected: Vec<jchar> = "abc\u{1F600}".encode_utf16().collect();
+    {
+        let chars = unwrap(&env, env.get_string_critical(string));
+        assert_eq!(&*chars, &expected[..]);
+    }
+
+    // The critical region has ended
+    let len = unwrap(&env, env.call_method(string, "length", "()I", &[]));
+    assert_eq!(len.i().unwrap(), 5);
+}
+
+#[test]
+pub fn get_string_critical_null() {
+    let env = attach_current_thread();
+
+    let result = env.get_string_critical(JObject::null().into());
+    assert!(matches!(result, Err(Error::NullPtr(_))));
+}
+
+#[test]
+pub fn get_string_length_null() {
+    let env = attach_current_thread();
+
+    let result = env.get_string_length(JObject::null().into());
+    assert!(matches!(result, Err(Error::NullPtr(_))));
+    let result = env.get_string_utf_length(JObject::null().into());
+    assert!(matches!(result, Err(Error::NullPtr(_))));
+}
+
+#[test]
+pub fn new_string_utf16_unpaired_surrogate() {
+    let env = attach_current_thread();
+
+    let string = unwrap(&env, env.new_string_utf16(&[0x61, 0xD800]));
+    let length = unwrap(&env, env.call_method(string, "length", "()I", &[]));
+    assert_eq!(length.i().unwrap(), 2);
+    assert_eq!(
+        unwrap(&env, env.get_string_utf16(string)),
+        vec![0x61, 0xD800]
+    );
+}
+
+#[test]
+pub fn new_string_with_nul() {
+    let env = attach_current_thread();
+
+    let string = unwrap(&env, env.new_string("a\0b"));
+    let length = unwrap(&env, env.call_method(string, "length", "()I", &[]));
+    assert_eq!(length.i().unwrap(), 3);
+    assert_eq!(
+        unwrap(&env, env.get_string_utf16(string)),
+        vec![0x61, 0x00, 0x62]
+    );
+
+    let round_trip: String = unwrap(&env, env.get_string(string)).into();
+    assert_eq!(round_trip, "a\0b");
+}
+
+#[test]
+pub fn get_string_modified_utf8() {
+    let env = attach_current_thread();
+
+    // Created from UTF-16, so the encoding is up to the JVM
+    let chars: Vec<jchar> = "a\0b\u{1F600}".encode_utf16().collect();
+    let string = unwrap(&env, env.new_string_utf16(&chars));
+
+    let java_str = unwrap(&env, env.get_string(string));
+    assert_eq!(
+        java_str.to_bytes(),
+        &[b'a', 0xC0, 0x80, b'b', 0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80]
+    );
+    let decoded: String = java_str.into();
+    assert_eq!(decoded, "a\0b\u{1F600}");
+}
+
+#[test]
+pub fn get_string_checked() {
+    let env = attach_current_thread();
+
+    let string = unwrap(&env, env.new_string("a\0é\u{1F600}"));
+    assert_eq!(
+        unwrap(&env, env.get_string_checked(string)),
+        "a\0é\u{1F600}"
+    );
+}
+
+#[test]
+pub fn get_string_checked_invalid_offset() {
+    let env = attach_current_thread();
+
+    // The unpaired surrogate follows a two-byte 'é' and a six-byte surrogate
+    // pair in modified UTF-8
+    let mut chars: Vec<jchar> = "é\u{1F600}".encode_utf16().collect();
+    chars.extend_from_slice(&[0xDC00, 0x78]);
+    let string = unwrap(&env, env.new_string_utf16(&chars));
+
+    let result = env.get_string_checked(string);
+    assert!(matches!(result, Err(Error::InvalidUtf8 { offset: 8 })));
+    assert!(!env.exception_check().unwrap());
+}
+
+#[test]
+pub fn call_bool_method() {
+    let env = attach_current_thread();
+    let empty = env.new_string("").unwrap();
+    let string = env.new_string(TESTING_OBJECT_STR).unwrap();
+
+    assert!(unwrap(
+        &env,
+        env.call_bool_method(empty, "isEmpty", "()Z", &[])
+    ));
+    assert!(!unwrap(
+        &env,
+        env.call_bool_method(string, "isEmpty", "()Z", &[])
+    ));
+
+    let result = env.call_bool_method(string, "length", "()I", &[]);
+    assert!(matches!(result, Err(Error::WrongJValueType("bool", _))));
+}
+
+#[test]
+pub fn call_static_bool_method() {
+    let env = attach_current_thread();
+    let string = env.new_string(TESTING_OBJECT_STR).unwrap();
+
+    let is_null = unwrap(
+        &env,
+        env.call_static_bool_method(
+            "java/util/Objects",
+            "isNull",
+            "(Ljava/lang/Object;)Z",
+            &[string.into()],
+        ),
+    );
+    assert!(!is_null);
+}
+
+#[test]
+pub fn call_static_void_ok() {
+    let env = attach_current_thread();
+
+    let result = env.call_static_void("java/lang/Thread", "sleep", "(J)V", &[JValue::Long(0)]);
+    unwrap(&env, result);
+}
+
+#[test]
+pub fn call_static_void_exception() {
+    let env = attach_current_thread();
+
+    let result = env.call_static_void("java/lang/Thread", "sleep", "(J)V", &[JValue::Long(-1)]);
+    assert!(matches!(result, Err(Error::JavaException)));
+    assert!(env.exception_check().unwrap());
+    env.exception_clear().unwrap();
+}
+
+#[test]
+pub fn call_static_void_non_void_method() {
+    let env = attach_current_thread();
+
+    let result = env.call_static_void("java/lang/Math", "abs", "(I)I", &[JValue::Int(-1)]);
+    assert!(matches!(result, Err(Error::NonVoidMethod(_))));
+}
+
+#[test]
+pub fn call_void_ok() {
+    let env = attach_current_thread();
+    let builder = env
+        .new_object("java/lang/StringBuilder", "()V", &[])
+        .unwrap();
+
+    unwrap(
+        &env,
+        env.call_void(builder, "setLength", "(I)V", &[JValue::Int(2)]),
+    );
+    let length = unwrap(&env, env.call_method(builder, "length", "()I", &[]));
+    assert_eq!(length.i().unwrap(), 2);
+}
+
+#[test]
+pub fn get_string_raw_modified_utf8() {
+    let env = attach_current_thread();
+    let string = env.new_string("a\u{1F600}").unwrap();
+
+    let java_str = unwrap(&env, env.get_string(string));
+    let modified = unsafe { std::ffi::CStr::from_ptr(java_str.get_raw()) };
+    assert_eq!(
+        modified.to_bytes(),
+        &[b'a', 0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80][..]
+    );
+
+    let standard = java_str.to_utf8_cstring().unwrap();
+    assert_eq!(standard.to_bytes(), "a\u{1F600}".as_bytes());
+}
+
+#[test]
+pub fn get_string_raw_nul() {
+    let env = attach_current_thread();
+    let string = env.new_string("a\u{0}b").unwrap();
+
+    let java_str = unwrap(&env, env.get_string(string));
+    let modified = unsafe { std::ffi::CStr::from_ptr(java_str.get_raw()) };
+    assert_eq!(modified.to_bytes(), &[b'a', 0xC0, 0x80, b'b'][..]);
+    assert!(java_str.to_utf8_cstring().is_err());
+}
+
+#[test]
+pub fn monitor_enter_exit() {
+    let env = attach_current_thread();
+    let obj = env.new_object("java/lang/Object", "()V", &[]).unwrap();
+
+    unwrap(&env, env.monitor_enter(obj));
+    assert!(holds_lock(&env, obj));
+    unwrap(&env, env.monitor_exit(obj));
+    assert!(!holds_lock(&env, obj));
+}
+
+#[test]
+pub fn monitor_exit_not_owned() {
+    let env = attach_current_thread();
+    let obj = env.new_object("java/lang/Object", "()V", &[]).unwrap();
+
+    let result = env.monitor_exit(obj);
+    assert!(result.is_err());
+    let exception = env.exception_occurred().unwrap();
+    env.exception_clear().unwrap();
+    assert_exception_type(&env, exception, "java/lang/IllegalMonitorStateException");
+}
+
+#[test]
+pub fn lock_obj_releases_on_drop() {
+    let env = attach_current_thread();
+    let obj = env.new_object("java/lang/Object", "()V", &[]).unwrap();
+
+    {
+        let _guard = unwrap(&env, env.lock_obj(obj));
+        assert!(holds_lock(&env, obj));
+    }
+    assert!(!holds_lock(&env, obj));
+}
+
+fn holds_lock(env: &JNIEnv, obj: JObject) -> bool {
+    env.call_static_method(
+        "java/lang/Thread",
+        "holdsLock",
+        "(Ljava/lang/Object;)Z",
+        &[obj.into()],
+    )
+    .unwrap()
+    .z()
+    .unwrap()
+}
+
+#[test]
+pub fn call_static_method_interrupted() {
+    let env = attach_current_thread();
+    let thread = env
+        .call_static_method(
+            "java/lang/Thread",
+            "currentThread",
+            "()Ljava/lang/Thread;",
+            &[],
+        )
+        .unwrap()
+        .l()
+        .unwrap();
+    env.call_method(thread, "interrupt", "()V", &[]).unwrap();
+
+    let result = env.call_static_void("java/lang/Thread", "sleep", "(J)V", &[JValue::Long(10_000)]);
+    assert!(matches!(result, Err(Error::Interrupted)));
+
+    let exception = env.exception_occurred().unwrap();
+    env.exception_clear().unwrap();
+    assert_exception_type(&env, exception, "java/lang/InterruptedException");
+}
+
+#[test]
+pub fn call_method_unchecked_interrupted() {
+    let env = attach_current_thread();
+    let thread = env
+        .call_static_method(
+            "java/lang/Thread",
+            "currentThread",
+            "()Ljava/lang/Thread;",
+            &[],
+        )
+        .unwrap()
+        .l()
+        .unwrap();
+
+    let join = unwrap(&env, env.get_method_id("java/lang/Thread", "join", "(J)V"));
+    env.call_method(thread, "interrupt", "()V", &[]).unwrap();
+    let result = env.call_method_unchecked(
+        thread,
+        join,
+        JavaType::Primitive(Primitive::Void),
+        &[JValue::Long(10_000)],
+    );
+    assert!(matches!(result, Err(Error::Interrupted)));
+    env.exception_clear().unwrap();
+
+    env.call_method(thread, "interrupt", "()V", &[]).unwrap();
+    let result = env.call_nonvirtual_method(
+        thread,
+        "java/lang/Thread",
+        "join",
+        "(J)V",
+        &[JValue::Long(10_000)],
+    );
+    assert!(matches!(result, Err(Error::Interrupted)));
+    env.exception_clear().unwrap();
+}
+
+#[test]
+pub fn call_method_clearing_interrupted() {
+    let env = attach_current_thread();
+    let thread = env
+        .call_static_method(
+            "java/lang/Thread",
+            "currentThread",
+            "()Ljava/lang/Thread;",
+            &[],
+        )
+        .unwrap()
+        .l()
+        .unwrap();
+    env.call_method(thread, "interrupt", "()V", &[]).unwrap();
+
+    let result = env.call_method_clearing(thread, "join", "(J)V", &[JValue::Long(10_000)]);
+    assert!(matches!(result, Err(Error::JavaExceptionCleared { .. })));
+    assert!(!env.exception_check().unwrap());
+}
+
+#[test]
+pub fn describe_object() {
+    let env = attach_current_thread();
+    let string = env.new_string(TESTING_OBJECT_STR).unwrap();
+
+    let description = unwrap(&env, env.describe_object(string));
+    assert!(description.starts_with("java.lang.String@"));
+    assert!(description.ends_with(&format!(": {}", TESTING_OBJECT_STR)));
+
+    assert_eq!(unwrap(&env, env.describe_object(JObject::null())), "null");
+}
+
+#[test]
+pub fn describe_object_to_string_throws() {
+    let env = attach_current_thread();
+    // A closed formatter throws FormatterClosedException from toString
+    let formatter = unwrap(&env, env.new_object("java/util/Formatter", "()V", &[]));
+    unwrap(&env, env.call_method(formatter, "close", "()V", &[]));
+
+    let description = unwrap(&env, env.describe_object(formatter));
+    assert!(description.starts_with("java.util.Formatter@"));
+    assert!(!description.contains(':'));
+    assert!(!env.exception_check().unwrap());
+}
+
+#[test]
+pub fn describe_object_to_string_interrupted() {
+    let env = attach_current_thread();
+    // A class whose toString throws an InterruptedException
+    let name = "com/example/InterruptedToString";
+    let mut class_file = ClassFile::new(52);
+    let exception = class_file.class("java/lang/InterruptedException");
+    let init = class_file.method_ref("java/lang/InterruptedException", "<init>", "()V");
+    let [e, i] = [exception.to_be_bytes(), init.to_be_bytes()];
+    #[rustfmt::skip]
+    let code = [
+        0xbb, e[0], e[1], // new InterruptedException
+        0x59, // dup
+        0xb7, i[0], i[1], // invokespecial <init>
+        0xbf, // athrow
+    ];
+    class_file.method(ACC_PUBLIC, "toString", "()Ljava/lang/String;", 2, 1, &code);
+    let class_file = class_file.finish(ACC_PUBLIC | ACC_SUPER, name, "java/lang/Object");
+    let loader = new_class_loader(&env);
+    let class = unwrap(&env, env.define_class(name, loader, &class_file));
+    let obj = unwrap(&env, env.alloc_object(class));
+
+    let result = env.describe_object(obj);
+    assert!(matches!(result, Err(Error::Interrupted)));
+    assert_pending_java_exception_detailed(&env, Some("java/lang/InterruptedException"), None);
+}
+
+// A stand-in for a Scala class holding a single value in a field, returned
+// by a method of the same name
+fn scala_holder_class_file(name: &str, accessor: &str, ty: &str) -> Vec<u8> {
+    let mut class = ClassFile::new(52);
+    let field = class.field_ref(name, accessor, ty);
+    class.field(ACC_PRIVATE, accessor, ty);
+    let f = field.to_be_bytes();
+    // aload_0, getfield, areturn
+    class.method(
+        ACC_PUBLIC,
+        accessor,
+        &format!("(){}", ty),
+        1,
+        1,
+        &[0x2a, 0xb4, f[0], f[1], 0xb0],
+    );
+    class.finish(ACC_PUBLIC | ACC_SUPER, name, "java/lang/Object")
+}
+
+// Creates an instance of a stand-in for a Scala class, defined in a new class
+// loader so the tests don't need the Scala library
+fn new_scala_holder<'a>(
+    env: &JNIEnv<'a>,
+    name: &str,
+    accessor: &str,
+    ty: &str,
+    value: JObject,
+) -> JObject<'a> {
+    let loader = new_class_loader(env);
+    let class_file = scala_holder_class_file(name, accessor, ty);
+    let class = unwrap(env, env.define_class(name, loader, &class_file));
+    let obj = unwrap(env, env.alloc_object(class));
+    unwrap(env, env.set_field(obj, accessor, ty, value.into()));
+    obj
+}
+
+#[test]
+pub fn scala_try_success() {
+    let env = attach_current_thread();
+    let value = env.new_string(TESTING_OBJECT_STR).unwrap();
+    let success = new_scala_holder(
+        &env,
+        "scala/util/Success",
+        "value",
+        "Ljava/lang/Object;",
+        *value,
+    );
+
+    let result = unwrap(&env, env.scala_try(success));
+    let value_out = result.ok().expect("Success gives Ok");
+    assert!(unwrap(&env, env.is_same_object(value_out, value)));
+}
+
+#[test]
+pub fn scala_try_failure() {
+    let env = attach_current_thread();
+    let exception = unwrap(
+        &env,
+        env.new_object(
+            RUNTIME_EXCEPTION_CLASS,
+            "(Ljava/lang/String;)V",
+            &[JObject::from(env.new_string(TEST_EXCEPTION_MESSAGE).unwrap()).into()],
+        ),
+    );
+    let failure = new_scala_holder(
+        &env,
+        "scala/util/Failure",
+        "exception",
+        "Ljava/lang/Throwable;",
+        exception,
+    );
+
+    let result = unwrap(&env, env.scala_try(failure));
+    let thrown = result.unwrap_err();
+    assert!(unwrap(&env, env.is_same_object(thrown, exception)));
+    assert_exception_message(&env, thrown, TEST_EXCEPTION_MESSAGE);
+}
+
+#[test]
+pub fn scala_either_right_and_left() {
+    let env = attach_current_thread();
+    let value = env.new_string(TESTING_OBJECT_STR).unwrap();
+
+    let right = new_scala_holder(
+        &env,
+        "scala/util/Right",
+        "value",
+        "Ljava/lang/Object;",
+        *value,
+    );
+    let result = unwrap(&env, env.scala_either(right));
+    assert!(unwrap(&env, env.is_same_object(result.unwrap(), value)));
+
+    let left = new_scala_holder(
+        &env,
+        "scala/util/Left",
+        "value",
+        "Ljava/lang/Object;",
+        *value,
+    );
+    let result = unwrap(&env, env.scala_either(left));
+    assert!(unwrap(&env, env.is_same_object(result.unwrap_err(), value)));
+}
+
+#[test]
+pub fn scala_try_wrong_class() {
+    let env = attach_current_thread();
+    let obj = env.new_object("java/lang/Object", "()V", &[]).unwrap();
+
+    let result = env.scala_try(obj);
+    assert!(matches!(result, Err(Error::WrongObjectClass(_))));
+    assert!(!env.exception_check().unwrap());
+}
+
+#[test]
+pub fn scala_either_wrong_class() {
+    let env = attach_current_thread();
+    let obj = env.new_string("right").unwrap();
+
+    let result = env.scala_either(obj);
+    assert!(matches!(result, Err(Error::WrongObjectClass(_))));
+    assert!(!env.exception_check().unwrap());
+}
+
+#[test]
+pub fn register_native_methods_unknown_method() {
+    let env = attach_current_thread();
+
+    extern "system" fn no_such_method(_: JNIEnv, _: JObject) {}
+    let methods = [NativeMethod {
+        name: "noSuchMethod".into(),
+        sig: "()V".into(),
+        fn_ptr: no_such_method as *mut std::ffi::c_void,
+    }];
+
+    let result = env.register_native_methods("java/util/ArrayList", &methods);
+    assert!(matches!(result, Err(Error::JavaException)));
+    let exception = env.exception_occurred().unwrap();
+    env.exception_clear().unwrap();
+    assert_exception_type(&env, exception, "java/lang/NoSuchMethodError");
+}
+
+#[test]
+pub fn unregister_native_methods_ok() {
+    let env = attach_current_thread();
+
+    // ArrayList has no native methods, so there is nothing to unbind
+    unwrap(&env, env.unregister_native_methods("java/util/ArrayList"));
+}
+
+#[test]
+pub fn new_string_os_matches_java_file() {
+    let env = attach_current_thread();
+    let path = std::path::Path::new("some file.txt");
+
+    let string = unwrap(&env, env.new_string_os(path));
+    let value: String = env.get_string(string).unwrap().into();
+    assert_eq!(value, path.to_str().unwrap());
+
+    let file = env
+        .new_object("java/io/File", "(Ljava/lang/String;)V", &[string.into()])
+        .unwrap();
+    let file_path = env
+        .call_method(file, "getPath", "()Ljava/lang/String;", &[])
+        .unwrap()
+        .l()
+        .unwrap();
+    let file_path: String = env.get_string(file_path.into()).unwrap().into();
+    assert_eq!(file_path, path.to_str().unwrap());
+}
+
+#[test]
+pub fn get_string_region_substring() {
+    let env = attach_current_thread();
+    let string = env.new_string("hello world").unwrap();
+
+    let mut buf = [0; 5];
+    unwrap(&env, env.get_string_region(string, 6, &mut buf));
+    assert_eq!(buf.to_vec(), "world".encode_utf16().collect::<Vec<_>>());
+}
+
+#[test]
+pub fn get_string_region_out_of_bounds() {
+    let env = attach_current_thread();
+    let string = env.new_string("hello").unwrap();
+
+    let mut buf = [0; 5];
+    let result = env.get_string_region(string, 1, &mut buf);
+    assert!(matches!(result, Err(Error::JavaException)));
+    let exception = env.exception_occurred().unwrap();
+    env.exception_clear().unwrap();
+    assert_exception_type(&env, exception, "java/lang/StringIndexOutOfBoundsException");
+}
+
+#[test]
+pub fn get_string_utf_region_substring() {
+    let env = attach_current_thread();
+    let string = env.new_string("h\u{e9}llo world").unwrap();
+
+    let mut buf = [0xff; 16];
+    let len = unwrap(&env, env.get_string_utf_region(string, 0, 5, &mut buf));
+    assert_eq!(&buf[..len], "h\u{e9}llo".as_bytes());
+}
+
+#[test]
+pub fn get_string_utf_region_buffer_too_small() {
+    let env = attach_current_thread();
+    let string = env.new_string("hello").unwrap();
+
+    let mut buf = [0; 8];
+    let result = env.get_string_utf_region(string, 0, 5, &mut buf);
+    assert!(matches!(
+        result,
+        Err(Error::BufferTooSmall {
+            required: 16,
+            actual: 8
+        })
+    ));
+}
+
+#[test]
+pub fn call_method_throwable_returned() {
+    let env = attach_current_thread();
+    let cause = env.new_object(RUNTIME_EXCEPTION_CLASS, "()V", &[]).unwrap();
+    let exception = env
+        .new_object(
+            "java/lang/Exception",
+            "(Ljava/lang/Throwable;)V",
+            &[cause.into()],
+        )
+        .unwrap();
+
+    let result = unwrap(
+        &env,
+        env.call_method_throwable(exception, "getCause", "()Ljava/lang/Throwable;", &[]),
+    );
+    assert!(env.is_same_object(result.unwrap(), cause).unwrap());
+}
+
+#[test]
+pub fn call_method_throwable_null() {
+    let env = attach_current_thread();
+    let exception = env.new_object("java/lang/Exception", "()V", &[]).unwrap();
+
+    let result = unwrap(
+        &env,
+        env.call_method_throwable(exception, "getCause", "()Ljava/lang/Throwable;", &[]),
+    );
+    assert!(result.is_none());
+}
+
+#[test]
+pub fn call_method_throwable_not_throwable() {
+    let env = attach_current_thread();
+    let exception = env.new_object("java/lang/Exception", "()V", &[]).unwrap();
+
+    let result = env.call_method_throwable(exception, "getClass", "()Ljava/lang/Class;", &[]);
+    assert!(matches!(result, Err(Error::WrongObjectClass(_))));
+}
+
+#[test]
+pub fn get_int_array_elements_slice() {
+    let env = attach_current_thread();
+    let java_array = env.new_int_array(4).unwrap();
+    env.set_int_array_region(java_array, 0, &[4, 3, 2, 1])
+        .unwrap();
+
+    {
+        let mut elements = unwrap(
+            &env,
+            env.get_int_array_elements(java_array, ReleaseMode::CopyBack),
+        );
+        assert_eq!(elements.as_slice(), &[4, 3, 2, 1]);
+        elements.as_mut_slice().sort_unstable();
+    }
+
+    let mut res = [0; 4];
+    env.get_int_array_region(java_array, 0, &mut res).unwrap();
+    assert_eq!(res, [1, 2, 3, 4]);
+}
+
+#[test]
+pub fn get_int_array_fixed() {
+    let env = attach_current_thread();
+    let java_array = env.new_int_array(3).unwrap();
+    env.set_int_array_region(java_array, 0, &[7, 8, 9]).unwrap();
+
+    let [x, y, z] = unwrap(&env, env.get_int_array_fixed::<3>(java_array));
+    assert_eq!((x, y, z), (7, 8, 9));
+
+    let result = env.get_int_array_fixed::<2>(java_array);
+    assert!(matches!(
+        result,
+        Err(Error::ArrayLengthMismatch {
+            expected: 2,
+            actual: 3
+        })
+    ));
+}
+
+#[test]
+pub fn local_scope_returns_value() {
+    let env = attach_current_thread();
+
+    let size: jni::errors::Result<jint> = local_scope!(env, 16, {
+        let list = env.new_object("java/util/ArrayList", "()V", &[])?;
+        let element = env.new_string("element")?;
+        env.call_method(list, "add", "(Ljava/lang/Object;)Z", &[element.into()])?;
+        env.call_method(list, "size", "()I", &[])?.i()
+    });
+    assert_eq!(unwrap(&env, size), 1);
+}
+
+#[test]
+pub fn local_scope_pops_frame_on_error() {
+    let env = attach_current_thread();
+
+    let mut weak = None;
+    let result: jni::errors::Result<()> = local_scope!(env, 16, {
+        let obj = env.new_object("java/lang/Object", "()V", &[])?;
+        weak = Some(env.new_weak_ref(obj)?);
+        env.find_class("java/lang/DoesNotExist")?;
+        Ok(())
+    });
+    assert!(matches!(result, Err(Error::JavaException)));
+    env.exception_clear().unwrap();
+
+    // The object was only held by the local reference created in the scope,
+    // so it can only be collected once the frame has been popped. Garbage
+    // collection is only a hint, so it's requested a few times
+    let weak = weak.unwrap();
+    let collected = (0..10).any(|_| {
+        unwrap(
+            &env,
+            env.call_static_method("java/lang/System", "gc", "()V", &[]),
+        );
+        unwrap(&env, weak.is_garbage_collected(&env))
+    });
+    assert!(collected);
+
+    // The frame has been popped, so a local reference created in the outer
+    // frame is still valid after popping another frame
+    let outer = env.new_string("outer").unwrap();
+    env.with_local_frame(16, || Ok(JObject::null())).unwrap();
+    let value: String = env.get_string(outer).unwrap().into();
+    assert_eq!(value, "outer");
+}
+
+#[test]
+pub fn get_version_at_least_requested() {
+    let env = attach_current_thread();
+
+    let version = unwrap(&env, env.get_version());
+    assert!(i32::from(version) >= i32::from(jni::JNIVersion::V8));
+    assert_eq!(jni::JNIVersion::from(i32::from(version)), version);
+}
+
+#[test]
+pub fn validate_method() {
+    let env = attach_current_thread();
+
+    unwrap(&env, env.validate_method("java/util/List", "size", "()I"));
+    // Inherited from Object
+    unwrap(
+        &env,
+        env.validate_method(ARRAYLIST_CLASS, "hashCode", "()I"),
+    );
+    unwrap(
+        &env,
+        env.validate_static_method(MATH_CLASS, MATH_ABS_METHOD_NAME, MATH_ABS_SIGNATURE),
+    );
+}
+
+#[test]
+pub fn validate_method_not_found() {
+    let env = attach_current_thread();
+
+    let result = env.validate_method(ARRAYLIST_CLASS, "nonexistent", "()V");
+    assert!(matches!(
+        result,
+        Err(Error::MethodNotFound { ref name, .. }) if name == "java/util/ArrayList.nonexistent"
+    ));
+    assert!(!env.exception_check().unwrap());
+
+    // Static methods are not found as object methods
+    let result = env.validate_method(MATH_CLASS, MATH_ABS_METHOD_NAME, MATH_ABS_SIGNATURE);
+    assert!(matches!(result, Err(Error::MethodNotFound { .. })));
+    assert!(!env.exception_check().unwrap());
+
+    let result = env.validate_static_method("com/example/Missing", "run", "()V");
+    assert!(matches!(result, Err(Error::MethodNotFound { .. })));
+    assert!(!env.exception_check().unwrap());
+}
+
+#[test]
+pub fn throwable_get_message_and_cause() {
+    let env = attach_current_thread();
+    let inner: JThrowable = unwrap(&env, env.new_object(EXCEPTION_CLASS, "()V", &[])).into();
+    let message = unwrap(&env, env.new_string(TEST_EXCEPTION_MESSAGE));
+    let outer: JThrowable = unwrap(
+        &env,
+        env.new_object(
+            RUNTIME_EXCEPTION_CLASS,
+            "(Ljava/lang/String;Ljava/lang/Throwable;)V",
+            &[message.into(), inner.into()],
+        ),
+    )
+    .into();
+
+    let message = unwrap(&env, outer.get_message(&env)).unwrap();
+    let message: String = env.get_string(message).unwrap().into();
+    assert_eq!(message, TEST_EXCEPTION_MESSAGE);
+
+    let cause = unwrap(&env, outer.get_cause(&env)).unwrap();
+    assert!(unwrap(&env, env.is_same_object(cause, inner)));
+    assert!(unwrap(&env, cause.get_message(&env)).is_none());
+    assert!(unwrap(&env, cause.get_cause(&env)).is_none());
+}
+
+#[test]
+pub fn throwable_get_stack_trace() {
+    let env = attach_current_thread();
+    let input = unwrap(&env, env.new_string("not a number"));
+    let result = env.call_static_method(
+        INTEGER_CLASS,
+        "parseInt",
+        "(Ljava/lang/String;)I",
+        &[input.into()],
+    );
+    assert!(matches!(result, Err(Error::JavaException)));
+    let exception = env.exception_occurred().unwrap();
+    env.exception_clear().unwrap();
+
+    let stack_trace = unwrap(&env, exception.get_stack_trace(&env));
+    let class_names: Vec<String> = stack_trace
+        .into_iter()
+        .map(|element| {
+            let name = unwrap(
+                &env,
+                env.call_method(element, "getClassName", "()Ljava/lang/String;", &[]),
+            );
+            env.get_string(name.l().unwrap().into()).unwrap().into()
+        })
+        .collect();
+    assert!(class_names.iter().any(|name| name == "java.lang.Integer"));
+}
+
+#[test]
+pub fn get_created_java_vms() {
+    let env = attach_current_thread();
+    let current = unwrap(&env, env.get_java_vm());
+
+    let vms = jni::JavaVM::get_created_java_vms().unwrap();
+    assert_eq!(vms.len(), 1);
+    assert_eq!(vms[0].get_java_vm_pointer(), current.get_java_vm_pointer());
+}
+
+#[test]
+pub fn call_method_direct_buffer_ok() {
+    let env = attach_current_thread();
+    let mut vec: Vec<u8> = vec![0, 1, 2, 3];
+    let buf = vec.as_mut_slice();
+    let direct_buffer = env.new_direct_byte_buffer(buf).unwrap();
+
+    let guard = unwrap(
+        &env,
+        env.call_method_direct_buffer(direct_buffer, "duplicate", "()Ljava/nio/ByteBuffer;", &[]),
+    );
+    assert!(!unwrap(
+        &env,
+        env.is_same_object(direct_buffer, guard.as_obj())
+    ));
+    assert_eq!(guard.as_slice(), &[0, 1, 2, 3]);
+}
+
+#[test]
+pub fn call_method_direct_buffer_not_direct() {
+    let env = attach_current_thread();
+    let heap_buffer = unwrap(
+        &env,
+        env.call_static_method(
+            "java/nio/ByteBuffer",
+            "allocate",
+            "(I)Ljava/nio/ByteBuffer;",
+            &[JValue::from(4)],
+        ),
+    )
+    .l()
+    .unwrap();
+
+    let result =
+        env.call_method_direct_buffer(heap_buffer, "duplicate", "()Ljava/nio/ByteBuffer;", &[]);
+    assert!(matches!(result, Err(Error::NullPtr(_))));
+}
+
+// Group test for testing the family of new_PRIMITIVE_array functions with correct arguments
+#[test]
+pub fn new_primitive_array_ok() {
+    let env = attach_current_thread();
+    const SIZE: jsize = 16;
+
+    let result = env.new_boolean_array(SIZE);
+    assert!(result.is_ok());
+    assert!(!result.unwrap().is_null());
+
+    let result = env.new_byte_array(SIZE);
+    assert!(result.is_ok());
+    assert!(!result.unwrap().is_null());
+
+    let result = env.new_char_array(SIZE);
+    assert!(result.is_ok());
+    assert!(!result.unwrap().is_null());
+
+    let result = env.new_short_array(SIZE);
+    assert!(result.is_ok());
+    assert!(!result.unwrap().is_null());
+
+    let result = env.new_int_array(SIZE);
+    assert!(result.is_ok());
+    assert!(!result.unwrap().is_null());
+
+    let result = env.new_long_array(SIZE);
+    assert!(result.is_ok());
+    assert!(!result.unwrap().is_null());
+
+    let result = env.new_float_array(SIZE);
+    assert!(result.is_ok());
+    assert!(!result.unwrap().is_null());
+
+    let result = env.new_double_array(SIZE);
+    assert!(result.is_ok());
+    assert!(!result.unwrap().is_null());
+}
+
+// Group test for testing the family of new_PRIMITIVE_array functions with wrong arguments
+#[test]
+pub fn new_primitive_array_wrong() {
+    let env = attach_current_thread();
+    const WRONG_SIZE: jsize = -1;
+
+    let result = env.new_boolean_array(WRONG_SIZE);
+    assert_exception(&result, "JNIEnv#new_boolean_array should throw exception");
+    assert_pending_java_exception(&env);
+
+    let result = env.new_byte_array(WRONG_SIZE);
+    assert_exception(&result, "JNIEnv#new_byte_array should throw exception");
+    assert_pending_java_exception(&env);
+
+    let result = env.new_char_array(WRONG_SIZE);
+    assert_exception(&result, "JNIEnv#new_char_array should throw exception");
+    assert_pending_java_exception(&env);
+
+    let result = env.new_short_array(WRONG_SIZE);
+    assert_exception(&result, "JNIEnv#new_short_array should throw exception");
+    assert_pending_java_exception(&env);
+
+    let result = env.new_int_array(WRONG_SIZE);
+    assert_exception(&result, "JNIEnv#new_int_array should throw exception");
+    assert_pending_java_exception(&env);
+
+    let result = env.new_long_array(WRONG_SIZE);
+    assert_exception(&result, "JNIEnv#new_long_array should throw exception");
+    assert_pending_java_exception(&env);
+
+    let result = env.new_float_array(WRONG_SIZE);
+    assert_exception(&result, "JNIEnv#new_float_array should throw exception");
+    assert_pending_java_exception(&env);
+
+    let result = env.new_double_array(WRONG_SIZE);
+    assert_exception(&result, "JNIEnv#new_double_array should throw exception");
+    assert_pending_java_exception(&env);
+}
+
+#[test]
+fn get_super_class_ok() {
+    let env = attach_current_thread();
+    let result = env.get_superclass(ARRAYLIST_CLASS);
+    assert!(result.is_ok());
+    assert!(!result.unwrap().is_null());
+}
+
+#[test]
+fn get_super_class_null() {
+    let env = attach_current_thread();
+    let result = env.get_superclass("java/lang/Object");
+    assert!(result.is_ok());
+    assert!(result.unwrap().is_null());
+}
+
+#[test]
+fn get_superclass_opt() {
+    let env = attach_current_thread();
+
+    assert!(unwrap(&env, env.get_superclass_opt("java/lang/Object")).is_none());
+    assert!(unwrap(&env, env.get_superclass_opt("java/util/List")).is_none());
+
+    let superclass = unwrap(&env, env.get_superclass_opt(INTEGER_CLASS)).unwrap();
+    let number = env.find_class("java/lang/Number").unwrap();
+    assert!(unwrap(&env, env.is_same_object(superclass, number)));
+}
+
+#[test]
+fn convert_byte_array() {
+    let env = attach_current_thread();
+    let src: Vec<u8> = vec![1, 2, 3, 4];
+    let java_byte_array = env.byte_array_from_slice(&src).unwrap();
+
+    let dest = env.convert_byte_array(java_byte_array);
+    assert!(dest.is_ok());
+    assert_eq!(dest.unwrap(), src);
+}
+
+#[test]
+fn local_ref_null() {
+    let env = attach_current_thread();
+    let null_obj = JObject::null();
+
+    let result = env.new_local_ref::<JObject>(null_obj);
+    assert!(result.is_ok());
+    assert!(result.unwrap().is_null());
+
+    // try to delete null reference
+    let result = env.delete_local_ref(null_obj);
+    assert!(result.is_ok());
+}
+
+#[test]
+fn auto_local_ref_null() {
+    let env = attach_current_thread();
+
+    let local = unwrap(&env, env.new_auto_local_ref(JObject::null()));
+    assert!(local.as_obj().is_null());
+}
+
+#[test]
+fn auto_local_ref_from_global() {
+    let env = attach_current_thread();
+    let obj = unwrap(&env, env.new_object(ARRAYLIST_CLASS, "()V", &[]));
+    let global = unwrap(&env, env.new_global_ref(obj));
+    unwrap(&env, env.delete_local_ref(obj));
+
+    let local = unwrap(&env, env.new_auto_local_ref(global.as_obj()));
+    assert!(!local.as_obj().is_null());
+    assert!(unwrap(
+        &env,
+        env.is_same_object(local.as_obj(), global.as_obj())
+    ));
+    let size = unwrap(&env, env.call_method(&local, "size", "()I", &[]));
+    assert_eq!(size.i().unwrap(), 0);
+}
+
+#[test]
+fn new_global_ref_null() {
+    let env = attach_current_thread();
+    let null_obj = JObject::null();
+    let result = env.new_global_ref(null_obj);
+    assert!(result.is_ok());
+    assert!(result.unwrap().as_obj().is_null());
+}
+
+#[test]
+fn auto_local_null() {
+    let env = attach_current_thread();
+    let null_obj = JObject::null();
+    {
+        let auto_ref = AutoLocal::new(&env, null_obj);
+        assert!(auto_ref.as_obj().is_null());
+    }
+    assert!(null_obj.is_null());
+}
+
+#[test]
+fn short_lifetime_with_local_frame() {
+    let env = attach_current_thread();
+    let object = short_lifetime_with_local_frame_sub_fn(&env);
+    assert!(object.is_ok());
+}
+
+fn short_lifetime_with_local_frame_sub_fn<'a>(env: &'_ JNIEnv<'a>) -> Result<JObject<'a>, Error> {
+    env.with_local_frame(16, || {
+        env.new_object(INTEGER_CLASS, "(I)V", &[JValue::from(5)])
+    })
+}
+
+#[test]
+fn short_lifetime_list() {
+    let env = attach_current_thread();
+    let first_list_object = short_lifetime_list_sub_fn(&env).unwrap();
+    let value = env.call_method(first_list_object, "intValue", "()I", &[]);
+    assert_eq!(value.unwrap().i().unwrap(), 1);
+}
+
+fn short_lifetime_list_sub_fn<'a>(env: &'_ JNIEnv<'a>) -> Result<JObject<'a>, Error> {
+    let list_object = env.new_object(ARRAYLIST_CLASS, "()V", &[])?;
+    let list = JList::from_env(env, list_object)?;
+    let element = env.new_object(INTEGER_CLASS, "(I)V", &[JValue::from(1)])?;
+    list.add(element)?;
+    short_lifetime_list_sub_fn_get_first_element(&list)
+}
+
+fn short_lifetime_list_sub_fn_get_first_element<'a>(
+    list: &'_ JList<'a, '_>,
+) -> Result<JObject<'a>, Error> {
+    let mut iterator = list.iter()?;
+    Ok(iterator.next().unwrap())
+}
+
+#[test]
+fn list_random_access() {
+    let env = attach_current_thread();
+    let elements: Vec<JObject> = ["a", "b", "a"]
+        .iter()
+        .map(|s| unwrap(&env, env.new_string(s)).into())
+        .collect();
+    let list = unwrap(&env, JList::from_iter(&env, elements.iter().copied()));
+    let b = elements[1];
+
+    let second = unwrap(&env, list.get(1)).unwrap();
+    assert!(unwrap(&env, env.is_same_object(second, b)));
+    assert_eq!(unwrap(&env, list.index_of(elements[0])), Some(0));
+    let c = unwrap(&env, env.new_string("c"));
+    assert_eq!(unwrap(&env, list.index_of(*c)), None);
+
+    let previous = unwrap(&env, list.set(1, JObject::null())).unwrap();
+    assert!(unwrap(&env, env.is_same_object(previous, b)));
+    assert!(unwrap(&env, list.get(1)).is_none());
+    // The iterator yields the null element rather than skipping it
+    let nulls: Vec<bool> = unwrap(&env, list.iter()).map(|e| e.is_null()).collect();
+    assert_eq!(nulls, [false, true, false]);
+
+    assert!(unwrap(&env, list.remove(1)).is_none());
+    let removed = unwrap(&env, list.remove(1)).unwrap();
+    assert!(unwrap(&env, env.is_same_object(removed, elements[2])));
+    assert_eq!(unwrap(&env, list.size()), 1);
+}
+
+#[test]
+fn list_contains_and_clear() {
+    let env = attach_current_thread();
+    let elements: Vec<JObject> = (1..=3)
+        .map(|i| {
+            unwrap(
+                &env,
+                env.new_object(INTEGER_CLASS, "(I)V", &[JValue::from(i)]),
+            )
+        })
+        .collect();
+    let list = unwrap(&env, JList::from_iter(&env, elements.iter().copied()));
+
+    // Elements are compared with `equals`
+    let two = unwrap(
+        &env,
+        env.new_object(INTEGER_CLASS, "(I)V", &[JValue::from(2)]),
+    );
+    assert!(unwrap(&env, list.contains(two)));
+    let four = unwrap(
+        &env,
+        env.new_object(INTEGER_CLASS, "(I)V", &[JValue::from(4)]),
+    );
+    assert!(!unwrap(&env, list.contains(four)));
+
+    unwrap(&env, list.remove(1));
+    assert!(!unwrap(&env, list.contains(two)));
+
+    unwrap(&env, list.clear());
+    assert_eq!(unwrap(&env, list.size()), 0);
+    assert!(!unwrap(&env, list.contains(elements[0])));
+    unwrap(&env, list.add(four));
+    assert!(unwrap(&env, list.contains(four)));
+}
+
+#[test]
+fn list_index_out_of_bounds() {
+    let env = attach_current_thread();
+    let list = unwrap(&env, env.new_object(ARRAYLIST_CLASS, "()V", &[]));
+    let list = unwrap(&env, JList::from_env(&env, list));
+
+    // Each exception is cleared before the next call
+    for idx in 0..3 {
+        let result = match idx {
+            0 => list.get(0),
+            1 => list.set(0, JObject::null()),
+            _ => list.remove(0),
+        };
+        assert!(matches!(result, Err(Error::JavaException)));
+        let exception = unwrap(&env, env.exception_occurred());
+        unwrap(&env, env.exception_clear());
+        assert!(unwrap(
+            &env,
+            env.is_instance_of(exception, "java/lang/IndexOutOfBoundsException")
+        ));
+    }
+}
+
+#[test]
+fn iterator_from_iterable() {
+    let env = attach_current_thread();
+    let set = unwrap(&env, env.new_object("java/util/TreeSet", "()V", &[]));
+    for s in &["b", "a", "c"] {
+        let element = unwrap(&env, env.new_string(s));
+        unwrap(
+            &env,
+            env.call_method(set, "add", "(Ljava/lang/Object;)Z", &[element.into()]),
+        );
+    }
+
+    let iter = unwrap(&env, JIterator::from_iterable(&env, set));
+    let elements: Vec<String> = iter
+        .map(|element| unwrap(&env, env.get_string(element.into())).into())
+        .collect();
+    assert_eq!(elements, ["a", "b", "c"]);
+
+    let result = JIterator::from_iterable(&env, JObject::null());
+    assert!(matches!(result, Err(Error::NullPtr(_))));
+}
+
+#[test]
+fn iterator_try_next() {
+    let env = attach_current_thread();
+    let list = unwrap(&env, env.new_object(ARRAYLIST_CLASS, "()V", &[]));
+    let list = unwrap(&env, JList::from_env(&env, list));
+    unwrap(&env, list.add(JObject::null()));
+
+    let iter = unwrap(
+        &env,
+        env.call_method(*list, "iterator", "()Ljava/util/Iterator;", &[]),
+    );
+    let iter = unwrap(&env, JIterator::from_env(&env, iter.l().unwrap()));
+    assert!(unwrap(&env, iter.try_next()).unwrap().is_null());
+
+    // Modifying the list invalidates the iterator
+    unwrap(&env, list.add(JObject::null()));
+    assert!(matches!(iter.try_next(), Err(Error::JavaException)));
+    let exception = unwrap(&env, env.exception_occurred());
+    unwrap(&env, env.exception_clear());
+    assert!(unwrap(
+        &env,
+        env.is_instance_of(exception, "java/util/ConcurrentModificationException")
+    ));
+}
+
+#[test]
+fn list_from_iter() {
+    let env = attach_current_thread();
+    let first = unwrap(&env, env.new_string("first"));
+
+    let others = (1..=3).map(|i| unwrap(&env, env.new_string(format!("element {}", i))));
+    let list = unwrap(
+        &env,
+        JList::from_iter(&env, std::iter::once(first).chain(others)),
+    );
+    assert!(unwrap(&env, env.is_instance_of(*list, ARRAYLIST_CLASS)));
+    assert_eq!(unwrap(&env, list.size()), 4);
+
+    let elements: Vec<String> = unwrap(&env, list.iter())
+        .map(|element| unwrap(&env, env.get_string(element.into())).into())
+        .collect();
+    assert_eq!(elements, ["first", "element 1", "element 2", "element 3"]);
+
+    // References created before the call are not deleted
+    let first: String = unwrap(&env, env.get_string(first)).into();
+    assert_eq!(first, "first");
+}
+
+#[test]
+fn list_iter_auto() {
+    let env = attach_current_thread();
+    let list = unwrap(&env, env.new_object(ARRAYLIST_CLASS, "()V", &[]));
+    let list = unwrap(&env, JList::from_env(&env, list));
+    for i in 1..=3 {
+        let element = unwrap(
+            &env,
+            env.new_object(INTEGER_CLASS, "(I)V", &[JValue::from(i)]),
+        );
+        unwrap(&env, list.add(element));
+        unwrap(&env, env.delete_local_ref(element));
+    }
+
+    let sum: jint = unwrap(&env, list.iter_auto())
+        .map(|element| {
+            unwrap(&env, env.call_method(&element, "intValue", "()I", &[]))
+                .i()
+                .unwrap()
+        })
+        .sum();
+    assert_eq!(sum, 6);
+
+    // Elements that are forgotten outlive the iteration
+    let first = unwrap(&env, list.iter_auto()).next().unwrap().forget();
+    let value = unwrap(&env, env.call_method(first, "intValue", "()I", &[]));
+    assert_eq!(value.i().unwrap(), 1);
+}
+
+#[test]
+fn get_object_array_element() {
+    let env = attach_current_thread();
+    let array = env
+        .new_object_array(1, STRING_CLASS, JObject::null())
+        .unwrap();
+    assert!(!array.is_null());
+    assert!(env.get_object_array_element(array, 0).unwrap().is_null());
+    let test_str = env.new_string("test").unwrap();
+    env.set_object_array_element(array, 0, test_str).unwrap();
+    assert!(!env.get_object_array_element(array, 0).unwrap().is_null());
+}
+
+#[test]
+fn stream_to_vec() {
+    let env = attach_current_thread();
+    let range = unwrap(
+        &env,
+        env.call_static_method(
+            "java/util/stream/IntStream",
+            "range",
+            "(II)Ljava/util/stream/IntStream;",
+            &[JValue::Int(0), JValue::Int(5)],
+        ),
+    );
+    let stream = unwrap(
+        &env,
+        env.call_method(
+            range.l().unwrap(),
+            "boxed",
+            "()Ljava/util/stream/Stream;",
+            &[],
+        ),
+    );
+
+    let elements = unwrap(&env, env.stream_to_vec(stream.l().unwrap()));
+    let values: Vec<jint> = elements
+        .into_iter()
+        .map(|element| {
+            unwrap(&env, env.call_method(element, "intValue", "()I", &[]))
+                .i()
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(values, vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn object_array_iter() {
+    let env = attach_current_thread();
+    let array = unwrap(&env, env.new_object_array(3, STRING_CLASS, JObject::null()));
+    for (i, value) in ["a", "b"].iter().enumerate() {
+        let value = env.new_string(value).unwrap();
+        env.set_object_array_element(array, i as jsize, value)
+            .unwrap();
+    }
+
+    let iter = unwrap(&env, env.object_array_iter(array));
+    assert_eq!(iter.size_hint(), (0, Some(3)));
+    let elements: Vec<Option<String>> = iter
+        .map(|element| {
+            let element = env.auto_local(element);
+            if element.as_obj().is_null() {
+                None
+            } else {
+                Some(env.get_string(element.as_obj().into()).unwrap().into())
+            }
+        })
+        .collect();
+    assert_eq!(
+        elements,
+        vec![Some("a".to_owned()), Some("b".to_owned()), None]
+    );
+}
+
+#[test]
+fn object_array_iter_try_next() {
+    let env = attach_current_thread();
+    let array = unwrap(&env, env.new_object_array(1, STRING_CLASS, JObject::null()));
+
+    let mut iter = unwrap(&env, env.object_array_iter_auto(array));
+    let element = unwrap(&env, iter.try_next()).unwrap();
+    assert!(element.as_obj().is_null());
+    assert!(unwrap(&env, iter.try_next()).is_none());
+}
+
+#[test]
+fn object_array_iter_auto() {
+    let env = attach_current_thread();
+    let array = unwrap(&env, env.new_object_array(3, STRING_CLASS, JObject::null()));
+    for (i, value) in ["a", "b"].iter().enumerate() {
+        let value = env.new_string(value).unwrap();
+        env.set_object_array_element(array, i as jsize, value)
+            .unwrap();
+    }
+
+    let iter = unwrap(&env, env.object_array_iter_auto(array));
+    assert_eq!(iter.size_hint(), (0, Some(3)));
+    let elements: Vec<Option<String>> = iter
+        .map(|element| {
+            if element.as_obj().is_null() {
+                None
+            } else {
+                Some(env.get_string(element.as_obj().into()).unwrap().into())
+            }
+        })
+        .collect();
+    assert_eq!(
+        elements,
+        vec![Some("a".to_owned()), Some("b".to_owned()), None]
+    );
+}
+
+#[test]
+fn object_array_init() {
+    let env = attach_current_thread();
+    let array = unwrap(
+        &env,
+        env.object_array_init(3, STRING_CLASS, |i| {
+            env.new_string(i.to_string()).map(JObject::from)
+        }),
+    );
+
+    for i in 0..3 {
+        let element = unwrap(&env, env.get_object_array_element(array, i));
+        let element: String = env.get_string(element.into()).unwrap().into();
+        assert_eq!(element, i.to_string());
+    }
+}
+
+#[test]
+fn object_array_init_error() {
+    let env = attach_current_thread();
+    let mut calls = 0;
+    let result = env.object_array_init(3, STRING_CLASS, |i| {
+        calls += 1;
+        if i == 1 {
+            Err(Error::NullPtr("test"))
+        } else {
+            env.new_string("element").map(JObject::from)
+        }
+    });
+
+    assert!(matches!(result, Err(Error::NullPtr("test"))));
+    assert_eq!(calls, 2);
+}
+
+#[test]
+pub fn throw_new() {
+    let env = attach_current_thread();
+
+    let result = env.throw_new(RUNTIME_EXCEPTION_CLASS, "Test Exception");
+    assert!(result.is_ok());
+    assert_pending_java_exception_detailed(
+        &env,
+        Some(RUNTIME_EXCEPTION_CLASS),
+        Some("Test Exception"),
+    );
+}
+
+#[test]
+pub fn throw_new_fail() {
+    let env = attach_current_thread();
+
+    let result = env.throw_new("java/lang/NonexistentException", "Test Exception");
+    assert!(result.is_err());
+    // Just to clear the java.lang.NoClassDefFoundError
+    assert_pending_java_exception(&env);
+}
+
+#[test]
+pub fn throw_new_returning() {
+    let env = attach_current_thread();
+
+    let exception = unwrap(
+        &env,
+        env.throw_new_returning(RUNTIME_EXCEPTION_CLASS, TEST_EXCEPTION_MESSAGE),
+    );
+    let pending = env.exception_occurred().unwrap();
+    env.exception_clear().unwrap();
+
+    assert!(unwrap(
+        &env,
+        env.is_same_object(pending, exception.as_obj())
+    ));
+    assert!(unwrap(
+        &env,
+        env.is_instance_of(exception.as_obj(), RUNTIME_EXCEPTION_CLASS)
+    ));
+    assert_exception_message(&env, pending, TEST_EXCEPTION_MESSAGE);
+}
+
+#[test]
+pub fn throw_new_returning_fail() {
+    let env = attach_current_thread();
+
+    let result = env.throw_new_returning("java/lang/NonexistentException", "Test Exception");
+    assert!(result.is_err());
+    // Just to clear the java.lang.NoClassDefFoundError
+    assert_pending_java_exception(&env);
+}
+
+#[test]
+pub fn catch_unwind_returns_value() {
+    let env = attach_current_thread();
+
+    let value: jint = env.catch_unwind(|| 42);
+    assert_eq!(value, 42);
+    assert!(!env.exception_check().unwrap());
+}
+
+#[test]
+pub fn catch_unwind_throws_for_panic() {
+    let env = attach_current_thread();
+
+    let value: jint = env.catch_unwind(|| panic!("{} went wrong", "something"));
+    assert_eq!(value, 0);
+    assert_pending_java_exception_detailed(
+        &env,
+        Some(RUNTIME_EXCEPTION_CLASS),
+        Some("something went wrong"),
+    );
+
+    let obj: jobject = env.catch_unwind_throwing("java/lang/IllegalStateException", || {
+        panic!("static message")
+    });
+    assert!(obj.is_null());
+    assert_pending_java_exception_detailed(
+        &env,
+        Some("java/lang/IllegalStateException"),
+        Some("static message"),
+    );
+}
+
+#[test]
+pub fn catch_unwind_keeps_pending_exception() {
+    let env = attach_current_thread();
+
+    env.catch_unwind::<(), _>(|| {
+        env.throw_new(EXCEPTION_CLASS, TEST_EXCEPTION_MESSAGE)
+            .unwrap();
+        panic!("after throwing");
+    });
+    assert_pending_java_exception_detailed(
+        &env,
+        Some(EXCEPTION_CLASS),
+        Some(TEST_EXCEPTION_MESSAGE),
+    );
+}
+
+#[test]
+pub fn throw_captured_exception() {
+    let env = attach_current_thread();
+    unwrap(
+        &env,
+        env.throw_new(RUNTIME_EXCEPTION_CLASS, TEST_EXCEPTION_MESSAGE),
+    );
+    let error = env
+        .capture_exception::<()>(Err(Error::JavaException))
+        .unwrap_err();
+    assert!(!env.exception_check().unwrap());
+
+    let exception = error.java_exception().unwrap();
+    unwrap(&env, env.throw(exception));
+
+    let pending = env.exception_occurred().unwrap();
+    env.exception_clear().unwrap();
+    assert!(unwrap(
+        &env,
+        env.is_same_object(pending, exception.as_obj())
+    ));
+    assert_exception_message(&env, pending, TEST_EXCEPTION_MESSAGE);
+}
+
+#[derive(Debug, PartialEq)]
+enum ParseError {
+    InvalidNumber(String),
+    Unexpected(String),
+    Jni(String),
+}
+
+impl From<Error> for ParseError {
+    fn from(other: Error) -> Self {
+        ParseError::Jni(other.to_string())
+    }
+}
+
+fn parse_int(env: &JNIEnv, input: &str) -> Result<jint, ParseError> {
+    let input = env.auto_local(env.new_string(input)?);
+    env.call_static_method(
+        INTEGER_CLASS,
+        "parseInt",
+        "(Ljava/lang/String;)I",
+        &[input.as_obj().into()],
+    )
+    .map_java_err(env, |exception, env| {
+        let message = exception
+            .get_message(env)
+            .ok()
+            .flatten()
+            .and_then(|message| env.get_string(message).ok())
+            .map(String::from)
+            .unwrap_or_default();
+        match env.is_instance_of(exception, "java/lang/NumberFormatException") {
+            Ok(true) => ParseError::InvalidNumber(message),
+            _ => ParseError::Unexpected(message),
+        }
+    })?
+    .i()
+    .map_err(ParseError::from)
+}
+
+#[test]
+pub fn map_java_err() {
+    let env = attach_current_thread();
+
+    assert_eq!(parse_int(&env, "42"), Ok(42));
+    assert_eq!(
+        parse_int(&env, "forty-two"),
+        Err(ParseError::InvalidNumber(
+            "For input string: \"forty-two\"".to_owned()
+        ))
+    );
+    assert!(!env.exception_check().unwrap());
+}
+
+#[test]
+pub fn map_java_err_cleared_exception() {
+    let env = attach_current_thread();
+
+    let res = env.capture_exception(env.find_class("java/lang/NonexistentClass"));
+    let res: Result<_, ParseError> = res.map_java_err(&env, |exception, env| {
+        assert!(env
+            .is_instance_of(exception, "java/lang/NoClassDefFoundError")
+            .unwrap());
+        ParseError::Unexpected("missing class".to_owned())
+    });
+    assert_eq!(
+        res.unwrap_err(),
+        ParseError::Unexpected("missing class".to_owned())
+    );
+}
+
+#[test]
+pub fn map_java_err_other_error() {
+    let env = attach_current_thread();
+
+    let res = env
+        .call_static_method(INTEGER_CLASS, "parseInt", "(Ljava/lang/String;)I", &[])
+        .map_java_err(&env, |_, _| ParseError::Unexpected(String::new()));
+    assert!(matches!(res, Err(ParseError::Jni(_))));
+}
+
+#[test]
+pub fn with_pending_exception_preserved() {
+    let env = attach_current_thread();
+    unwrap(
+        &env,
+        env.throw_new(RUNTIME_EXCEPTION_CLASS, TEST_EXCEPTION_MESSAGE),
+    );
+    let thrown = env.exception_occurred().unwrap();
+
+    // The cleanup can call into java, which fails with a pending exception
+    let len = unwrap(
+        &env,
+        env.with_pending_exception_preserved(|| {
+            assert!(!env.exception_check().unwrap());
+            let string = env.new_string(TESTING_OBJECT_STR).unwrap();
+            env.call_method(string, "length", "()I", &[])
+                .unwrap()
+                .i()
+                .unwrap()
+        }),
+    );
+    assert_eq!(len, TESTING_OBJECT_STR.len() as jint);
+
+    let pending = env.exception_occurred().unwrap();
+    assert!(unwrap(&env, env.is_same_object(pending, thrown)));
+    assert_pending_java_exception_detailed(
+        &env,
+        Some(RUNTIME_EXCEPTION_CLASS),
+        Some(TEST_EXCEPTION_MESSAGE),
+    );
+}
+
+#[test]
+pub fn with_pending_exception_preserved_suppresses_cleanup_exception() {
+    let env = attach_current_thread();
+    unwrap(
+        &env,
+        env.throw_new(RUNTIME_EXCEPTION_CLASS, TEST_EXCEPTION_MESSAGE),
+    );
+
+    unwrap(
+        &env,
+        env.with_pending_exception_preserved(|| {
+            env.throw_new("java/lang/IllegalStateException", "cleanup failed")
+                .unwrap();
+        }),
+    );
+
+    let pending = env.exception_occurred().unwrap();
+    env.exception_clear().unwrap();
+    assert_exception_message(&env, pending, TEST_EXCEPTION_MESSAGE);
+    let suppressed = unwrap(
+        &env,
+        env.call_method(pending, "getSuppressed", "()[Ljava/lang/Throwable;", &[]),
+    )
+    .l()
+    .unwrap();
+    let suppressed: Vec<JObject> =
+        unwrap(&env, env.object_array_iter(suppressed.into_inner())).collect();
+    assert_eq!(suppressed.len(), 1);
+    let suppressed = JThrowable::from(suppressed[0]);
+    assert_exception_type(&env, suppressed, "java/lang/IllegalStateException");
+    assert_exception_message(&env, suppressed, "cleanup failed");
+}
+
+#[test]
+pub fn with_pending_exception_preserved_rethrown_in_cleanup() {
+    let env = attach_current_thread();
+    unwrap(
+        &env,
+        env.throw_new(RUNTIME_EXCEPTION_CLASS, TEST_EXCEPTION_MESSAGE),
+    );
+    let thrown = JThrowable::from(env.exception_occurred().unwrap().into_inner());
+
+    // An exception can't suppress itself, so `addSuppressed` fails
+    unwrap(
+        &env,
+        env.with_pending_exception_preserved(|| {
+            env.throw(thrown).unwrap();
+        }),
+    );
+
+    let pending = env.exception_occurred().unwrap();
+    assert!(unwrap(&env, env.is_same_object(pending, thrown)));
+    assert_pending_java_exception_detailed(
+        &env,
+        Some(RUNTIME_EXCEPTION_CLASS),
+        Some(TEST_EXCEPTION_MESSAGE),
+    );
+}
+
+#[test]
+pub fn with_pending_exception_preserved_no_exception() {
+    let env = attach_current_thread();
+
+    let value = unwrap(&env, env.with_pending_exception_preserved(|| 42));
+    assert_eq!(value, 42);
+    assert!(!env.exception_check().unwrap());
+}
+
+#[test]
+pub fn get_object_ref_type() {
+    let env = attach_current_thread();
+    let local = unwrap(&env, env.new_string("ref"));
+    let global = unwrap(&env, env.new_global_ref(local));
+    let weak = unwrap(&env, env.new_weak_ref(local));
+
+    assert_eq!(unwrap(&env, env.get_object_ref_type(local)), RefType::Local);
+    assert_eq!(
+        unwrap(&env, env.get_object_ref_type(&global)),
+        RefType::Global
+    );
+    assert_eq!(
+        unwrap(&env, env.get_object_ref_type(weak.as_raw())),
+        RefType::WeakGlobal
+    );
+    assert_eq!(
+        unwrap(&env, env.get_object_ref_type(JObject::null())),
+        RefType::Invalid
+    );
+}
+
+#[test]
+pub fn exception_describe_and_keep() {
+    let env = attach_current_thread();
+
+    // Nothing to describe
+    unwrap(&env, env.exception_describe_and_keep());
+    assert!(!unwrap(&env, env.exception_check()));
+
+    unwrap(&env, env.throw_new(RUNTIME_EXCEPTION_CLASS, "described"));
+    let thrown = unwrap(&env, env.exception_occurred());
+    unwrap(&env, env.exception_describe_and_keep());
+    assert!(unwrap(&env, env.exception_check()));
+    let pending = unwrap(&env, env.exception_occurred());
+    unwrap(&env, env.exception_clear());
+    assert!(unwrap(&env, env.is_same_object(pending, thrown)));
+
+    unwrap(&env, env.throw_new(RUNTIME_EXCEPTION_CLASS, "described"));
+    unwrap(&env, env.exception_describe());
+    assert!(!unwrap(&env, env.exception_check()));
+}
+
+#[test]
+pub fn with_cleared_exception() {
+    let env = attach_current_thread();
+
+    let (res, exception) = unwrap(
+        &env,
+        env.with_cleared_exception(|| {
+            env.call_static_method(
+                INTEGER_CLASS,
+                "parseInt",
+                "(Ljava/lang/String;)I",
+                &[env.new_string("nope").unwrap().into()],
+            )
+        }),
+    );
+    assert!(matches!(res, Err(Error::JavaException)));
+    assert!(!unwrap(&env, env.exception_check()));
+    let exception = exception.unwrap();
+    match &exception {
+        Error::JavaExceptionCleared { class, message, .. } => {
+            assert_eq!(class, "java.lang.NumberFormatException");
+            assert_eq!(message.as_deref(), Some("For input string: \"nope\""));
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+    assert!(unwrap(
+        &env,
+        env.is_instance_of(
+            exception.java_exception().unwrap(),
+            "java/lang/NumberFormatException"
+        )
+    ));
+
+    let (res, exception) = unwrap(&env, env.with_cleared_exception(|| 42));
+    assert_eq!(res, 42);
+    assert!(exception.is_none());
+}
+
+#[test]
+pub fn throw_defaults() {
+    let env = attach_current_thread();
+
+    test_throwable_descriptor_with_default_type(&env, TEST_EXCEPTION_MESSAGE);
+    test_throwable_descriptor_with_default_type(&env, TEST_EXCEPTION_MESSAGE.to_owned());
+    test_throwable_descriptor_with_default_type(&env, JNIString::from(TEST_EXCEPTION_MESSAGE));
+}
+
+#[test]
+pub fn ref_equals() {
+    let env = attach_current_thread();
+    let local: JObject = env.new_string(TESTING_OBJECT_STR).unwrap().into();
+    let other: JObject = env.new_string(TESTING_OBJECT_STR).unwrap().into();
+    let global = env.new_global_ref(local).unwrap();
+
+    // Different references to the same object
+    assert_ne!(local.into_inner(), global.as_obj().into_inner());
+    assert!(unwrap(&env, env.ref_equals(local, global.as_obj())));
+    assert!(RefEq::new(&env, local) == RefEq::new(&env, &global));
+
+    // Equal but distinct objects
+    assert!(!unwrap(&env, env.ref_equals(local, other)));
+    assert!(RefEq::new(&env, local) != RefEq::new(&env, other));
+
+    assert!(RefEq::new(&env, JObject::null()) == RefEq::new(&env, JObject::null()));
+}
+
+#[test]
+pub fn object_hash_code_and_equals() {
+    let env = attach_current_thread();
+    let string: JObject = env.new_string(TESTING_OBJECT_STR).unwrap().into();
+    let other: JObject = env.new_string(TESTING_OBJECT_STR).unwrap().into();
+
+    let expected = unwrap(&env, env.call_method(string, "hashCode", "()I", &[]));
+    assert_eq!(
+        unwrap(&env, env.object_hash_code(string)),
+        expected.i().unwrap()
+    );
+    assert_eq!(
+        unwrap(&env, env.object_hash_code(string)),
+        unwrap(&env, env.object_hash_code(other))
+    );
+
+    assert!(unwrap(&env, env.objects_equal(string, other)));
+    assert!(!unwrap(&env, env.objects_equal(string, JObject::null())));
+
+    assert!(matches!(
+        env.object_hash_code(JObject::null()),
+        Err(Error::NullPtr(_))
+    ));
+    assert!(matches!(
+        env.objects_equal(JObject::null(), string),
+        Err(Error::NullPtr(_))
+    ));
 }
 
 #[test]
@@ -0,0 +1,107 @@
+#![cfg(all(feature = "invocation", feature = "macros"))]
+
+use jni::{
+    errors::{Error, Result},
+    jni_export,
+    objects::{JClass, JObject, JString},
+    sys::jint,
+    JNIEnv,
+};
+
+mod util;
+use util::{attach_current_thread, unwrap};
+
+#[jni_export(class = "com.example.Exported")]
+fn greet<'a>(env: &JNIEnv<'a>, _class: JClass<'a>, name: JString<'a>) -> Result<JString<'a>> {
+    let name: String = env.get_string(name)?.into();
+    env.new_string(format!("Hello, {}!", name))
+}
+
+#[jni_export(class = "com/example/Under_score", name = "parseInt")]
+fn parse_int(env: &JNIEnv, _this: JObject, value: JString) -> Result<jint> {
+    env.call_static_method(
+        "java/lang/Integer",
+        "parseInt",
+        "(Ljava/lang/String;)I",
+        &[value.into()],
+    )?
+    .i()
+}
+
+#[jni_export(class = "com.example.Exported", name = "wrongType")]
+fn wrong_type(_env: &JNIEnv, _class: JClass) -> Result<jint> {
+    Err(Error::WrongJValueType("int", "see java field"))
+}
+
+#[jni_export(class = "com.example.Exported", name = "panics")]
+fn panics(_env: &JNIEnv, _class: JClass, value: jint) {
+    panic!("unexpected value {}", value);
+}
+
+// The generated functions are called directly, as the JVM would after
+// looking them up.
+fn raw_env<'a>(env: &JNIEnv<'a>) -> JNIEnv<'a> {
+    unsafe { JNIEnv::from_raw(env.get_native_interface()) }.unwrap()
+}
+
+fn take_exception(env: &JNIEnv, class: &str) -> String {
+    let exception = env.exception_occurred().unwrap();
+    env.exception_clear().unwrap();
+    assert!(env.is_instance_of(exception, class).unwrap());
+    let message = exception.get_message(env).unwrap().unwrap();
+    env.get_string(message).unwrap().into()
+}
+
+#[test]
+pub fn jni_export_returns_value() {
+    let env = attach_current_thread();
+    let name = unwrap(&env, env.new_string("world"));
+
+    let greeting = Java_com_example_Exported_greet(raw_env(&env), JObject::null().into(), name);
+    let greeting: String = unwrap(&env, env.get_string(greeting)).into();
+    assert_eq!(greeting, "Hello, world!");
+
+    let value = unwrap(&env, env.new_string("42"));
+    let value = Java_com_example_Under_1score_parseInt(raw_env(&env), JObject::null(), value);
+    assert_eq!(value, 42);
+}
+
+#[test]
+pub fn jni_export_keeps_pending_exception() {
+    let env = attach_current_thread();
+    let value = unwrap(&env, env.new_string("forty-two"));
+
+    let value = Java_com_example_Under_1score_parseInt(raw_env(&env), JObject::null(), value);
+    assert_eq!(value, 0);
+    take_exception(&env, "java/lang/NumberFormatException");
+}
+
+#[test]
+pub fn jni_export_throws_for_error() {
+    let env = attach_current_thread();
+
+    let greeting = Java_com_example_Exported_greet(
+        raw_env(&env),
+        JObject::null().into(),
+        JObject::null().into(),
+    );
+    assert!(greeting.is_null());
+    take_exception(&env, "java/lang/NullPointerException");
+
+    let value = Java_com_example_Exported_wrongType(raw_env(&env), JObject::null().into());
+    assert_eq!(value, 0);
+    let message = take_exception(&env, "java/lang/IllegalArgumentException");
+    assert_eq!(
+        message,
+        "Invalid JValue type cast: int. Actual type: see java field"
+    );
+}
+
+#[test]
+pub fn jni_export_throws_for_panic() {
+    let env = attach_current_thread();
+
+    Java_com_example_Exported_panics(raw_env(&env), JObject::null().into(), 7);
+    let message = take_exception(&env, "java/lang/RuntimeException");
+    assert_eq!(message, "unexpected value 7");
+}
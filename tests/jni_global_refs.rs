@@ -6,7 +6,7 @@ use std::{
 };
 
 use jni::{
-    objects::{AutoLocal, JValue},
+    objects::{AutoLocal, GlobalRef, JValue},
     sys::jint,
 };
 
@@ -78,3 +78,63 @@ pub fn global_ref_works_in_other_threads() {
         );
     }
 }
+
+#[test]
+pub fn global_ref_raw_round_trip() {
+    let env = attach_current_thread();
+    let string = unwrap(&env, env.new_string("global"));
+    let global = unwrap(&env, env.new_global_ref(string));
+    assert_eq!(global.as_raw(), global.as_obj().into_inner());
+
+    // Adopt a global reference created outside of the wrapper
+    let raw = unsafe {
+        let internal = env.get_native_interface();
+        (**internal).NewGlobalRef.unwrap()(internal, global.as_raw())
+    };
+    let adopted = unsafe { GlobalRef::from_raw(unwrap(&env, env.get_java_vm()), raw) };
+    assert_eq!(adopted.as_raw(), raw);
+    drop(global);
+
+    let value: String = unwrap(&env, env.get_string(adopted.as_obj().into())).into();
+    assert_eq!(value, "global");
+}
+
+#[test]
+pub fn global_ref_shared_between_threads() {
+    let env = attach_current_thread();
+    let string = unwrap(&env, env.new_string("shared"));
+    // No clone per thread: the same `GlobalRef` is shared behind an `Arc`
+    let global = Arc::new(unwrap(&env, env.new_global_ref(string)));
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let global = Arc::clone(&global);
+            spawn(move || {
+                let env = attach_current_thread();
+                let value: String = unwrap(&env, env.get_string(global.as_obj().into())).into();
+                assert_eq!(value, "shared");
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
+
+#[test]
+pub fn global_ref_new_global() {
+    let env = attach_current_thread();
+    let string = unwrap(&env, env.new_string("global"));
+    let global = unwrap(&env, env.new_global_ref(string));
+
+    let clone = global.clone();
+    assert_eq!(clone.as_raw(), global.as_raw());
+    let other = unwrap(&env, global.new_global(&env));
+    assert_ne!(other.as_raw(), global.as_raw());
+    assert!(unwrap(&env, env.is_same_object(&other, &global)));
+
+    drop(global);
+    drop(clone);
+    let value: String = unwrap(&env, env.get_string(other.as_obj().into())).into();
+    assert_eq!(value, "global");
+}
@@ -0,0 +1,107 @@
+#![cfg(feature = "invocation")]
+
+use std::thread::spawn;
+
+use jni::{
+    objects::{JObject, WeakRef},
+    JNIEnv,
+};
+
+mod util;
+use util::{attach_current_thread, unwrap};
+
+#[test]
+pub fn weak_ref_upgrade_while_alive() {
+    let env = attach_current_thread();
+    let obj = env.new_string("alive").unwrap();
+    let weak = unwrap(&env, env.new_weak_ref(obj));
+
+    let local = unwrap(&env, weak.upgrade_local(&env)).unwrap();
+    assert!(unwrap(&env, env.is_same_object(local, obj)));
+    let global = unwrap(&env, weak.upgrade_global(&env)).unwrap();
+    assert!(unwrap(&env, env.is_same_object(global.as_obj(), obj)));
+
+    let raw: jni::sys::jobject = (&weak).into();
+    assert_eq!(weak.as_raw(), raw);
+}
+
+#[test]
+pub fn weak_ref_is_garbage_collected() {
+    let env = attach_current_thread();
+    let obj = env.new_string("alive").unwrap();
+    let weak = unwrap(&env, env.new_weak_ref(obj));
+    assert!(!unwrap(&env, weak.is_garbage_collected(&env)));
+
+    let collected = unwrap(&env, env.new_object_weak("java/lang/Object", "()V", &[]));
+    assert!(wait_for_collection(&env, &collected));
+    assert!(unwrap(&env, collected.upgrade_local(&env)).is_none());
+    assert!(!unwrap(&env, weak.is_garbage_collected(&env)));
+}
+
+#[test]
+pub fn weak_ref_is_same_object() {
+    let env = attach_current_thread();
+    let obj = env.new_string("alive").unwrap();
+    let other = env.new_string("alive").unwrap();
+    let weak = unwrap(&env, env.new_weak_ref(obj));
+    let same = unwrap(&env, env.new_weak_ref(obj));
+    let different = unwrap(&env, env.new_weak_ref(other));
+
+    assert!(unwrap(&env, weak.is_same_object(&env, &same)));
+    assert!(!unwrap(&env, weak.is_same_object(&env, &different)));
+    assert!(unwrap(&env, weak.is_weak_ref_to(&env, obj.into())));
+    assert!(!unwrap(&env, weak.is_weak_ref_to(&env, other.into())));
+    assert!(!unwrap(&env, weak.is_weak_ref_to(&env, JObject::null())));
+}
+
+#[test]
+pub fn weak_ref_is_same_object_collected() {
+    let env = attach_current_thread();
+    let first = unwrap(&env, env.new_object_weak("java/lang/Object", "()V", &[]));
+    let second = unwrap(&env, env.new_object_weak("java/lang/Object", "()V", &[]));
+    assert!(!unwrap(&env, first.is_same_object(&env, &second)));
+
+    assert!(wait_for_collection(&env, &first));
+    assert!(wait_for_collection(&env, &second));
+    assert!(unwrap(&env, first.is_same_object(&env, &second)));
+    assert!(unwrap(&env, first.is_weak_ref_to(&env, JObject::null())));
+}
+
+#[test]
+pub fn weak_ref_works_in_other_threads() {
+    let env = attach_current_thread();
+    let obj = env.new_string("shared").unwrap();
+    let _global = unwrap(&env, env.new_global_ref(obj));
+    let weak = unwrap(&env, env.new_weak_ref(obj));
+
+    spawn(move || {
+        let env = attach_current_thread();
+        let local = unwrap(&env, weak.upgrade_local(&env)).unwrap();
+        let value: String = unwrap(&env, env.get_string(local.into())).into();
+        assert_eq!(value, "shared");
+    })
+    .join()
+    .unwrap();
+}
+
+#[test]
+pub fn new_object_weak_collected() {
+    let env = attach_current_thread();
+    let weak = unwrap(&env, env.new_object_weak("java/lang/Object", "()V", &[]));
+
+    assert!(wait_for_collection(&env, &weak));
+}
+
+// Garbage collection is only a hint, so it's requested a few times
+fn wait_for_collection(env: &JNIEnv, weak: &WeakRef) -> bool {
+    for _ in 0..10 {
+        unwrap(
+            env,
+            env.call_static_method("java/lang/System", "gc", "()V", &[]),
+        );
+        if unwrap(env, weak.is_garbage_collected(env)) {
+            return true;
+        }
+    }
+    false
+}
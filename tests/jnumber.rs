@@ -0,0 +1,62 @@
+#![cfg(feature = "invocation")]
+
+use jni::{
+    errors::Error,
+    objects::{JInteger, JLong, JValue},
+};
+
+mod util;
+use util::{attach_current_thread, unwrap};
+
+#[test]
+pub fn jinteger_value() {
+    let env = attach_current_thread();
+
+    let boxed = unwrap(&env, env.box_value(JValue::Int(-42)));
+    let integer = unwrap(&env, JInteger::from_env(&env, boxed));
+    assert_eq!(unwrap(&env, integer.int_value()), -42);
+
+    let integer = unwrap(&env, JInteger::new(&env, i32::MAX));
+    assert_eq!(unwrap(&env, integer.int_value()), i32::MAX);
+
+    // Usable as an argument
+    let hash = unwrap(
+        &env,
+        env.call_static_method(
+            "java/util/Objects",
+            "hashCode",
+            "(Ljava/lang/Object;)I",
+            &[(&integer).into()],
+        ),
+    );
+    assert_eq!(hash.i().unwrap(), i32::MAX);
+}
+
+#[test]
+pub fn jlong_value() {
+    let env = attach_current_thread();
+
+    let boxed = unwrap(&env, env.box_value(JValue::Long(1 << 40)));
+    let long = unwrap(&env, JLong::from_env(&env, boxed));
+    assert_eq!(unwrap(&env, long.long_value()), 1 << 40);
+
+    let long = unwrap(&env, JLong::new(&env, i64::MIN));
+    assert_eq!(unwrap(&env, long.long_value()), i64::MIN);
+}
+
+#[test]
+pub fn jnumber_wrong_class() {
+    let env = attach_current_thread();
+
+    let boxed = unwrap(&env, env.box_value(JValue::Long(1)));
+    assert!(matches!(
+        JInteger::from_env(&env, boxed),
+        Err(Error::WrongObjectClass(_))
+    ));
+
+    let boxed = unwrap(&env, env.box_value(JValue::Int(1)));
+    assert!(matches!(
+        JLong::from_env(&env, boxed),
+        Err(Error::WrongObjectClass(_))
+    ));
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "invocation")]
+
+use jni::{errors::Error, objects::JValue};
+
+mod util;
+use util::{attach_current_thread, unwrap};
+
+#[test]
+pub fn method_handle_static() {
+    let env = attach_current_thread();
+    let abs = unwrap(
+        &env,
+        env.bind_static_method_handle("java/lang/Math", "abs", "(I)I"),
+    );
+
+    for x in -2..3 {
+        let result = unwrap(&env, abs.invoke(&env, &[JValue::Int(x)]));
+        assert_eq!(result.i().unwrap(), x.abs());
+    }
+}
+
+#[test]
+pub fn method_handle_virtual() {
+    let env = attach_current_thread();
+    let concat = unwrap(
+        &env,
+        env.bind_method_handle(
+            "java/lang/String",
+            "concat",
+            "(Ljava/lang/String;)Ljava/lang/String;",
+        ),
+    );
+    let first = env.new_string("foo").unwrap();
+    let second = env.new_string("bar").unwrap();
+
+    let result = unwrap(&env, concat.invoke(&env, &[first.into(), second.into()]));
+    let result: String = env.get_string(result.l().unwrap().into()).unwrap().into();
+    assert_eq!(result, "foobar");
+}
+
+#[test]
+pub fn method_handle_void() {
+    let env = attach_current_thread();
+    let set_length = unwrap(
+        &env,
+        env.bind_method_handle("java/lang/StringBuilder", "setLength", "(I)V"),
+    );
+    let builder = env
+        .new_object("java/lang/StringBuilder", "()V", &[])
+        .unwrap();
+
+    let result = unwrap(
+        &env,
+        set_length.invoke(&env, &[builder.into(), JValue::Int(3)]),
+    );
+    assert!(result.v().is_ok());
+    let length = env.call_method(builder, "length", "()I", &[]).unwrap();
+    assert_eq!(length.i().unwrap(), 3);
+}
+
+#[test]
+pub fn method_handle_invalid_arg_count() {
+    let env = attach_current_thread();
+    let abs = unwrap(
+        &env,
+        env.bind_static_method_handle("java/lang/Math", "abs", "(I)I"),
+    );
+
+    let result = abs.invoke(&env, &[]);
+    assert!(matches!(result, Err(Error::InvalidArgList(_))));
+}
+
+#[test]
+pub fn method_handle_not_found() {
+    let env = attach_current_thread();
+
+    let result = env.bind_static_method_handle("java/lang/Math", "noSuchMethod", "()V");
+    assert!(matches!(result, Err(Error::JavaException)));
+    env.exception_clear().unwrap();
+}
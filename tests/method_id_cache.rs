@@ -0,0 +1,84 @@
+#![cfg(feature = "invocation")]
+
+use jni::{errors::Error, objects::JValue, objects::MethodIdCache};
+
+mod util;
+use util::{attach_current_thread, unwrap};
+
+#[test]
+pub fn method_id_cache_static_call() {
+    let env = attach_current_thread();
+    let cache = MethodIdCache::new();
+
+    for x in -2..3 {
+        let abs = unwrap(
+            &env,
+            env.call_static_method_cached(
+                &cache,
+                "java/lang/Math",
+                "abs",
+                "(I)I",
+                &[JValue::Int(x)],
+            ),
+        );
+        assert_eq!(abs.i().unwrap(), x.abs());
+    }
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+pub fn method_id_cache_object_call() {
+    let env = attach_current_thread();
+    let cache = MethodIdCache::new();
+    let list = env.new_object("java/util/ArrayList", "()V", &[]).unwrap();
+    let element = env.new_string("element").unwrap();
+
+    let added = unwrap(
+        &env,
+        env.call_method_cached(
+            &cache,
+            list,
+            "java/util/List",
+            "add",
+            "(Ljava/lang/Object;)Z",
+            &[element.into()],
+        ),
+    );
+    assert!(added.z().unwrap());
+    let size = unwrap(
+        &env,
+        env.call_method_cached(&cache, list, "java/util/List", "size", "()I", &[]),
+    );
+    assert_eq!(size.i().unwrap(), 1);
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+pub fn method_id_cache_same_id() {
+    let env = attach_current_thread();
+    let cache = MethodIdCache::new();
+
+    let first = unwrap(
+        &env,
+        env.get_cached_method_id(&cache, "java/lang/Object", "hashCode", "()I"),
+    );
+    let second = unwrap(
+        &env,
+        env.get_cached_method_id(&cache, "java/lang/Object", "hashCode", "()I"),
+    );
+    assert_eq!(first.into_inner(), second.into_inner());
+    assert_eq!(cache.len(), 1);
+
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+#[test]
+pub fn method_id_cache_wrong_class() {
+    let env = attach_current_thread();
+    let cache = MethodIdCache::new();
+    let obj = env.new_object("java/lang/Object", "()V", &[]).unwrap();
+
+    let result = env.call_method_cached(&cache, obj, "java/util/List", "size", "()I", &[]);
+    assert!(matches!(result, Err(Error::WrongObjectClass(_))));
+}
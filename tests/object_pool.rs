@@ -0,0 +1,62 @@
+#![cfg(feature = "invocation")]
+
+use jni::{
+    errors::{Error, JniError},
+    objects::JValue,
+};
+
+mod util;
+use util::{attach_current_thread, unwrap};
+
+#[test]
+pub fn object_pool_reuses_objects() {
+    let env = attach_current_thread();
+
+    let result = env.with_object_pool("java/lang/StringBuilder", 2, |env, pool| {
+        assert_eq!(pool.available(), 2);
+
+        let builder = pool.take()?;
+        env.call_method(
+            builder,
+            "append",
+            "(I)Ljava/lang/StringBuilder;",
+            &[JValue::Int(42)],
+        )?;
+        env.call_void(builder, "setLength", "(I)V", &[JValue::Int(0)])?;
+        pool.recycle(builder);
+        assert_eq!(pool.available(), 2);
+
+        let again = pool.take()?;
+        assert!(env.is_same_object(builder, again)?);
+        let length = env.call_method(again, "length", "()I", &[])?.i()?;
+        Ok(length)
+    });
+
+    assert_eq!(unwrap(&env, result), 0);
+}
+
+#[test]
+pub fn object_pool_allocates_when_empty() {
+    let env = attach_current_thread();
+
+    let result = env.with_object_pool("java/lang/Object", 1, |env, pool| {
+        let first = pool.take()?;
+        assert_eq!(pool.available(), 0);
+        let second = pool.take()?;
+        env.is_same_object(first, second)
+    });
+
+    assert!(!unwrap(&env, result));
+}
+
+#[test]
+pub fn object_pool_too_large() {
+    let env = attach_current_thread();
+
+    let result = env.with_object_pool("java/lang/Object", i32::MAX as usize, |_, _| Ok(()));
+
+    assert!(matches!(
+        result,
+        Err(Error::JniCall(JniError::InvalidArguments))
+    ));
+}
@@ -0,0 +1,49 @@
+#![cfg(feature = "invocation")]
+
+use jni::objects::StringPool;
+
+mod util;
+use util::{attach_current_thread, unwrap};
+
+#[test]
+pub fn string_pool_reuses_strings() {
+    let env = attach_current_thread();
+    let pool = StringPool::new();
+
+    let first = unwrap(&env, pool.get(&env, "key"));
+    let second = unwrap(&env, pool.get(&env, "key"));
+    assert!(unwrap(
+        &env,
+        env.is_same_object(first.as_obj(), second.as_obj())
+    ));
+    let value: String = unwrap(&env, env.get_string(first.as_obj().into())).into();
+    assert_eq!(value, "key");
+
+    unwrap(&env, pool.get(&env, "other"));
+    assert_eq!(pool.len(), 2);
+
+    pool.clear();
+    assert!(pool.is_empty());
+}
+
+#[test]
+pub fn bounded_string_pool_evicts_oldest() {
+    let env = attach_current_thread();
+    let pool = StringPool::bounded(2);
+
+    let a = unwrap(&env, pool.get(&env, "a"));
+    unwrap(&env, pool.get(&env, "b"));
+    unwrap(&env, pool.get(&env, "c"));
+    assert_eq!(pool.len(), 2);
+
+    // "a" was evicted, so it gets created again, while the evicted reference
+    // stays valid.
+    let new_a = unwrap(&env, pool.get(&env, "a"));
+    assert!(!unwrap(
+        &env,
+        env.is_same_object(a.as_obj(), new_a.as_obj())
+    ));
+    let value: String = unwrap(&env, env.get_string(a.as_obj().into())).into();
+    assert_eq!(value, "a");
+    assert_eq!(pool.len(), 2);
+}
@@ -0,0 +1,30 @@
+#![cfg(feature = "invocation")]
+
+mod util;
+use util::{attach_current_thread_permanently, call_java_abs, jvm};
+
+#[test]
+fn with_attached_frame_detaches_only_if_attached() {
+    assert_eq!(jvm().threads_attached(), 0);
+    let val = jvm()
+        .with_attached_frame(16, |env| {
+            assert_eq!(jvm().threads_attached(), 1);
+            env.new_string("local")?;
+            Ok(call_java_abs(env, -1))
+        })
+        .unwrap();
+    assert_eq!(val, 1);
+    assert_eq!(jvm().threads_attached(), 0);
+    assert!(jvm().get_env().is_err());
+
+    // An existing attachment is reused and kept.
+    let env = attach_current_thread_permanently();
+    jvm()
+        .with_attached_frame(16, |env| {
+            assert_eq!(call_java_abs(env, -2), 2);
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(jvm().threads_attached(), 1);
+    assert_eq!(call_java_abs(&env, -3), 3);
+}
@@ -0,0 +1,35 @@
+#![cfg(feature = "invocation")]
+
+mod util;
+use util::{call_java_abs, jvm};
+
+#[test]
+fn scoped_daemon_thread_detaches_on_drop() {
+    assert_eq!(jvm().threads_attached(), 0);
+    {
+        let guard = jvm().attach_current_thread_as_daemon_scoped().unwrap();
+        assert_eq!(jvm().threads_attached(), 1);
+        let val = call_java_abs(&guard, -1);
+        assert_eq!(val, 1);
+
+        let thread = guard
+            .call_static_method(
+                "java/lang/Thread",
+                "currentThread",
+                "()Ljava/lang/Thread;",
+                &[],
+            )
+            .unwrap()
+            .l()
+            .unwrap();
+        let is_daemon = guard
+            .call_method(thread, "isDaemon", "()Z", &[])
+            .unwrap()
+            .z()
+            .unwrap();
+        assert!(is_daemon);
+    }
+    assert_eq!(jvm().threads_attached(), 0);
+    // Verify that this thread is really detached.
+    assert!(jvm().get_env().is_err());
+}
@@ -0,0 +1,39 @@
+#![cfg(feature = "invocation")]
+
+use jni::{errors::Error, JavaVM};
+
+mod util;
+use util::{call_java_abs, jvm};
+
+// Deeply nested code, which doesn't get a `JNIEnv` passed in
+fn nested_abs(value: i32) -> i32 {
+    JavaVM::with_current_env(|env| call_java_abs(env, value)).unwrap()
+}
+
+#[test]
+fn with_env_sets_current_env() {
+    assert_eq!(jvm().threads_attached(), 0);
+    assert!(matches!(
+        JavaVM::with_current_env(|_| ()),
+        Err(Error::NoCurrentEnv)
+    ));
+
+    let val = jvm()
+        .with_env(|env| {
+            assert_eq!(jvm().threads_attached(), 1);
+            let outer = env.get_native_interface();
+
+            // Nested calls reuse the attachment and restore the outer env
+            jvm().with_env(|_| assert_eq!(nested_abs(-1), 1)).unwrap();
+            JavaVM::with_current_env(|env| assert_eq!(env.get_native_interface(), outer)).unwrap();
+            nested_abs(-2)
+        })
+        .unwrap();
+    assert_eq!(val, 2);
+
+    assert_eq!(jvm().threads_attached(), 0);
+    assert!(matches!(
+        JavaVM::with_current_env(|_| ()),
+        Err(Error::NoCurrentEnv)
+    ));
+}
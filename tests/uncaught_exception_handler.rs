@@ -0,0 +1,166 @@
+#![cfg(feature = "invocation")]
+
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use jni::{
+    errors::Result,
+    objects::{JObject, JValue},
+    JNIEnv,
+};
+
+mod util;
+use util::{attach_current_thread, unwrap};
+
+static EXCEPTION_MESSAGE: &str = "thrown by runnable";
+
+/// Creates a `Runnable` that throws the given exception, through a method handle.
+fn throwing_runnable<'a>(env: &JNIEnv<'a>, exception: JObject<'a>) -> Result<JObject<'a>> {
+    let void = env
+        .get_static_field("java/lang/Void", "TYPE", "Ljava/lang/Class;")?
+        .l()?;
+    let exception_class = env.get_object_class(exception)?;
+    let thrower = env
+        .call_static_method(
+            "java/lang/invoke/MethodHandles",
+            "throwException",
+            "(Ljava/lang/Class;Ljava/lang/Class;)Ljava/lang/invoke/MethodHandle;",
+            &[void.into(), exception_class.into()],
+        )?
+        .l()?;
+
+    let bound_args = JObject::from(env.new_object_array(1, "java/lang/Object", exception)?);
+    let runner = env
+        .call_static_method(
+            "java/lang/invoke/MethodHandles",
+            "insertArguments",
+            "(Ljava/lang/invoke/MethodHandle;I[Ljava/lang/Object;)Ljava/lang/invoke/MethodHandle;",
+            &[thrower.into(), JValue::Int(0), bound_args.into()],
+        )?
+        .l()?;
+
+    let runnable_class = env.find_class("java/lang/Runnable")?;
+    env.call_static_method(
+        "java/lang/invoke/MethodHandleProxies",
+        "asInterfaceInstance",
+        "(Ljava/lang/Class;Ljava/lang/invoke/MethodHandle;)Ljava/lang/Object;",
+        &[runnable_class.into(), runner.into()],
+    )?
+    .l()
+}
+
+#[test]
+pub fn uncaught_exception_handler_called() {
+    let env = attach_current_thread();
+
+    let message = unwrap(&env, env.new_string(EXCEPTION_MESSAGE));
+    let exception = unwrap(
+        &env,
+        env.new_object(
+            "java/lang/IllegalStateException",
+            "(Ljava/lang/String;)V",
+            &[message.into()],
+        ),
+    );
+    let runnable = unwrap(&env, throwing_runnable(&env, exception));
+    let thread = unwrap(
+        &env,
+        env.new_object(
+            "java/lang/Thread",
+            "(Ljava/lang/Runnable;)V",
+            &[runnable.into()],
+        ),
+    );
+
+    let caught = Arc::new(Mutex::new(None));
+    let handler_caught = caught.clone();
+    unwrap(
+        &env,
+        env.set_uncaught_exception_handler(thread, move |env, thread, exception| {
+            let name = env
+                .call_method(thread, "getName", "()Ljava/lang/String;", &[])
+                .and_then(|name| name.l())
+                .and_then(|name| env.get_string(name.into()).map(String::from))
+                .unwrap();
+            let message = exception.get_message(env).unwrap().unwrap();
+            let message: String = env.get_string(message).unwrap().into();
+            *handler_caught.lock().unwrap() = Some((name, message));
+        }),
+    );
+
+    unwrap(&env, env.call_method(thread, "start", "()V", &[]));
+    unwrap(&env, env.call_method(thread, "join", "()V", &[]));
+
+    let name = unwrap(
+        &env,
+        env.call_method(thread, "getName", "()Ljava/lang/String;", &[]),
+    );
+    let name: String = env.get_string(name.l().unwrap().into()).unwrap().into();
+    assert_eq!(
+        caught.lock().unwrap().take(),
+        Some((name, EXCEPTION_MESSAGE.to_owned()))
+    );
+}
+
+#[test]
+pub fn uncaught_exception_handler_freed() {
+    let env = attach_current_thread();
+    let captured = Arc::new(());
+
+    env.with_local_frame(4, || {
+        let thread = env.new_object("java/lang/Thread", "()V", &[])?;
+        let handler_captured = captured.clone();
+        env.set_uncaught_exception_handler(thread, move |_, _, _| {
+            let _ = &handler_captured;
+        })?;
+        Ok(JObject::null())
+    })
+    .unwrap();
+
+    // The closure is dropped once the handler, along with its thread, has
+    // been collected
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while Arc::strong_count(&captured) > 1 {
+        assert!(Instant::now() < deadline, "the closure wasn't dropped");
+        unwrap(
+            &env,
+            env.call_static_method("java/lang/System", "gc", "()V", &[]),
+        );
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+pub fn uncaught_exception_handler_own_class_loader() {
+    let env = attach_current_thread();
+
+    let thread = unwrap(&env, env.new_object("java/lang/Thread", "()V", &[]));
+    unwrap(
+        &env,
+        env.set_uncaught_exception_handler(thread, |_, _, _| {}),
+    );
+    let handler = unwrap(
+        &env,
+        env.call_method(
+            thread,
+            "getUncaughtExceptionHandler",
+            "()Ljava/lang/Thread$UncaughtExceptionHandler;",
+            &[],
+        ),
+    )
+    .l()
+    .unwrap();
+
+    // The handler class isn't defined in the bootstrap class loader
+    let class = unwrap(&env, env.get_object_class(handler));
+    let loader = unwrap(
+        &env,
+        env.call_method(class, "getClassLoader", "()Ljava/lang/ClassLoader;", &[]),
+    )
+    .l()
+    .unwrap();
+    assert!(!loader.is_null());
+}
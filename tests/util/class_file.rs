@@ -0,0 +1,171 @@
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+pub const ACC_PUBLIC: u16 = 0x0001;
+pub const ACC_PRIVATE: u16 = 0x0002;
+pub const ACC_STATIC: u16 = 0x0008;
+pub const ACC_FINAL: u16 = 0x0010;
+pub const ACC_SUPER: u16 = 0x0020;
+
+/// A minimal writer of class files, for defining test fixtures at runtime
+/// instead of depending on classes of a particular JDK or library.
+///
+/// Methods are written without stack map frames, so their code must not
+/// branch.
+pub struct ClassFile {
+    major_version: u16,
+    constants: Vec<u8>,
+    constant_count: u16,
+    constant_indices: HashMap<Vec<u8>, u16>,
+    fields: Vec<u8>,
+    field_count: u16,
+    methods: Vec<u8>,
+    method_count: u16,
+    attributes: Vec<u8>,
+    attribute_count: u16,
+}
+
+impl ClassFile {
+    /// Creates an empty class file of the given major version, e.g. `52` for
+    /// java 8.
+    pub fn new(major_version: u16) -> Self {
+        ClassFile {
+            major_version,
+            constants: Vec::new(),
+            constant_count: 1,
+            constant_indices: HashMap::new(),
+            fields: Vec::new(),
+            field_count: 0,
+            methods: Vec::new(),
+            method_count: 0,
+            attributes: Vec::new(),
+            attribute_count: 0,
+        }
+    }
+
+    fn constant(&mut self, entry: Vec<u8>) -> u16 {
+        if let Some(&index) = self.constant_indices.get(&entry) {
+            return index;
+        }
+        let index = self.constant_count;
+        self.constants.extend_from_slice(&entry);
+        self.constant_indices.insert(entry, index);
+        self.constant_count += 1;
+        index
+    }
+
+    /// Returns the constant pool index of an `Utf8` entry.
+    pub fn utf8(&mut self, value: &str) -> u16 {
+        let mut entry = vec![1];
+        entry.extend_from_slice(&(value.len() as u16).to_be_bytes());
+        entry.extend_from_slice(value.as_bytes());
+        self.constant(entry)
+    }
+
+    /// Returns the constant pool index of a `Class` entry.
+    pub fn class(&mut self, name: &str) -> u16 {
+        let name = self.utf8(name);
+        self.constant(tagged(7, &[name]))
+    }
+
+    /// Returns the constant pool index of a `Fieldref` entry.
+    pub fn field_ref(&mut self, class: &str, name: &str, descriptor: &str) -> u16 {
+        let class = self.class(class);
+        let name_and_type = self.name_and_type(name, descriptor);
+        self.constant(tagged(9, &[class, name_and_type]))
+    }
+
+    /// Returns the constant pool index of a `Methodref` entry.
+    pub fn method_ref(&mut self, class: &str, name: &str, descriptor: &str) -> u16 {
+        let class = self.class(class);
+        let name_and_type = self.name_and_type(name, descriptor);
+        self.constant(tagged(10, &[class, name_and_type]))
+    }
+
+    fn name_and_type(&mut self, name: &str, descriptor: &str) -> u16 {
+        let name = self.utf8(name);
+        let descriptor = self.utf8(descriptor);
+        self.constant(tagged(12, &[name, descriptor]))
+    }
+
+    /// Adds a field without attributes.
+    pub fn field(&mut self, access: u16, name: &str, descriptor: &str) {
+        let name = self.utf8(name);
+        let descriptor = self.utf8(descriptor);
+        self.fields
+            .extend_from_slice(&u2s(&[access, name, descriptor, 0]));
+        self.field_count += 1;
+    }
+
+    /// Adds a method with the given bytecode as its `Code` attribute.
+    pub fn method(
+        &mut self,
+        access: u16,
+        name: &str,
+        descriptor: &str,
+        max_stack: u16,
+        max_locals: u16,
+        code: &[u8],
+    ) {
+        let name = self.utf8(name);
+        let descriptor = self.utf8(descriptor);
+        let code_name = self.utf8("Code");
+
+        let mut data = u2s(&[max_stack, max_locals]);
+        data.extend_from_slice(&(code.len() as u32).to_be_bytes());
+        data.extend_from_slice(code);
+        // No exception table and no attributes
+        data.extend_from_slice(&u2s(&[0, 0]));
+
+        self.methods
+            .extend_from_slice(&u2s(&[access, name, descriptor, 1]));
+        push_attribute(&mut self.methods, code_name, &data);
+        self.method_count += 1;
+    }
+
+    /// Adds a class attribute.
+    pub fn attribute(&mut self, name: &str, data: &[u8]) {
+        let name = self.utf8(name);
+        push_attribute(&mut self.attributes, name, data);
+        self.attribute_count += 1;
+    }
+
+    /// Writes the class file of a class without interfaces.
+    pub fn finish(mut self, access: u16, name: &str, super_class: &str) -> Vec<u8> {
+        let this_class = self.class(name);
+        let super_class = self.class(super_class);
+
+        let mut bytes = vec![0xCA, 0xFE, 0xBA, 0xBE];
+        bytes.extend_from_slice(&u2s(&[0, self.major_version, self.constant_count]));
+        bytes.extend_from_slice(&self.constants);
+        bytes.extend_from_slice(&u2s(&[access, this_class, super_class, 0]));
+        bytes.extend_from_slice(&self.field_count.to_be_bytes());
+        bytes.extend_from_slice(&self.fields);
+        bytes.extend_from_slice(&self.method_count.to_be_bytes());
+        bytes.extend_from_slice(&self.methods);
+        bytes.extend_from_slice(&self.attribute_count.to_be_bytes());
+        bytes.extend_from_slice(&self.attributes);
+        bytes
+    }
+}
+
+/// Encodes big-endian `u2` values.
+pub fn u2s(values: &[u16]) -> Vec<u8> {
+    values
+        .iter()
+        .flat_map(|value| value.to_be_bytes())
+        .collect()
+}
+
+fn tagged(tag: u8, values: &[u16]) -> Vec<u8> {
+    let mut entry = vec![tag];
+    entry.extend_from_slice(&u2s(values));
+    entry
+}
+
+fn push_attribute(bytes: &mut Vec<u8>, name: u16, data: &[u8]) {
+    bytes.extend_from_slice(&name.to_be_bytes());
+    bytes.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    bytes.extend_from_slice(data);
+}
@@ -5,7 +5,9 @@ use jni::{
     JavaVM,
 };
 
+mod class_file;
 mod example_proxy;
+pub use self::class_file::*;
 pub use self::example_proxy::AtomicIntegerProxy;
 
 pub fn jvm() -> &'static Arc<JavaVM> {